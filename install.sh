@@ -20,11 +20,12 @@ fi
 # Create ~/.local/bin if it doesn't exist
 mkdir -p ~/.local/bin
 
-# Copy the compiled executable to ~/.local/bin
-echo "Installing rust_roast to ~/.local/bin/"
+# Copy the compiled executables to ~/.local/bin
+echo "Installing rust_roast and cargo-roast to ~/.local/bin/"
 cp ./target/release/rust_roast ~/.local/bin/rust_roast
+cp ./target/release/cargo-roast ~/.local/bin/cargo-roast
 
-# Make it executable (though cargo build --release usually handles this)
-chmod +x ~/.local/bin/rust_roast
+# Make them executable (though cargo build --release usually handles this)
+chmod +x ~/.local/bin/rust_roast ~/.local/bin/cargo-roast
 
 echo "rust_roast installed successfully!"
\ No newline at end of file
@@ -0,0 +1,71 @@
+use clap::{Args, Parser};
+use rust_roast::cli::RoastArgs;
+use rust_roast::i18n::{tr, trf};
+use rust_roast::{config, devenv, project, render};
+
+// Cargo runs `cargo roast [args]` as `cargo-roast roast [args]`
+#[derive(Parser)]
+#[command(name = "cargo", bin_name = "cargo")]
+enum Cargo {
+    /// Roasts the current Rust workspace
+    Roast(CargoRoastArgs),
+}
+
+#[derive(Args)]
+#[command(version)]
+struct CargoRoastArgs {
+    #[command(flatten)]
+    roast: RoastArgs,
+}
+
+fn main() {
+    let Cargo::Roast(args) = Cargo::parse();
+    let cwd = std::env::current_dir().unwrap_or_else(|_| ".".into());
+    let root = match project::find_root(&cwd) {
+        Some(root) => root,
+        None => {
+            eprintln!("cargo-roast: no Cargo.toml found in {} or any parent directory", cwd.display());
+            std::process::exit(1);
+        }
+    };
+    let project = match project::inspect(&root) {
+        Ok(project) => project,
+        Err(err) => {
+            eprintln!("cargo-roast: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let config = config::load_config();
+    args.roast.init(&config);
+
+    render::specs_header(tr("header.project"));
+    render::spec(tr("label.project"), &format!("{} ({})", project.name, project.root.display()));
+    render::spec(tr("label.edition"), &project.edition);
+    render::spec(tr("label.members"), &project.members.to_string());
+    render::spec(tr("label.dependencies"), &project.dependencies.to_string());
+    render::spec(tr("label.duplicate_crates"), &project.duplicate_crates.len().to_string());
+    render::spec(tr("label.unsafe_blocks"), &project.unsafe_blocks.to_string());
+    let unwraps = trf("value.unwraps", &[
+        ("unwraps", &project.unwraps.to_string()),
+        ("density", &format!("{:.1}", project.unwraps_per_kloc())),
+    ]);
+    render::spec(tr("label.unwraps"), &unwraps);
+    render::spec(tr("label.target"), &devenv::format_size(project.target_size));
+
+    let mut roaster = args.roast.roaster(&config);
+    roaster.snapshot = project::snapshot(&project);
+    let roasts = match roaster.llm_roasts() {
+        Some(roasts) => roasts,
+        None => {
+            let mut roasts = Vec::new();
+            for (name, builtin) in project::roast_pools(&project, &config.project, config.dev.target_gb) {
+                roasts.extend(roaster.pick(name, builtin).map(|roast| roast.map(|text| project::fill(text, &project))));
+            }
+            roasts
+        }
+    };
+    render::roasts(&roaster, &roasts);
+    render::finish();
+    roaster.history.save();
+}
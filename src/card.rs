@@ -0,0 +1,207 @@
+use crate::i18n::tr;
+use crate::render::{wrap, Report};
+use crate::theme::{Color, Style, Theme};
+use resvg::tiny_skia::{Pixmap, Transform};
+use resvg::usvg;
+use unicode_width::UnicodeWidthStr;
+
+// The card's font, embedded so PNGs come out the same on every machine.
+// Characters it doesn't have, like Japanese, come from the system's fonts,
+// so only cards it covers are reproducible (see `covers`).
+const FONTS: &[&[u8]] = &[
+    include_bytes!("../assets/fonts/DejaVuSansMono.ttf"),
+    include_bytes!("../assets/fonts/DejaVuSansMono-Bold.ttf"),
+];
+const FONT_FAMILY: &str = "DejaVu Sans Mono";
+
+const FONT_SIZE: f64 = 14.0;
+// DejaVu Sans Mono's advance width at FONT_SIZE
+const CHAR_WIDTH: f64 = 8.43;
+const LINE_HEIGHT: f64 = 20.0;
+const PADDING: f64 = 28.0;
+const WIDTH: f64 = 720.0;
+// Text columns that fit between the paddings
+const COLUMNS: usize = ((WIDTH - 2.0 * PADDING) / CHAR_WIDTH) as usize;
+
+// Columns of the score bar
+const BAR_COLUMNS: usize = 24;
+
+// PNGs are drawn at twice the SVG's size, so they stay sharp on HiDPI screens
+const PNG_SCALE: f32 = 2.0;
+
+// Colors for roles that don't set one
+pub const DEFAULT_BACKGROUND: Color = Color::Rgb(0x1c, 0x1c, 0x1c);
+pub const DEFAULT_FOREGROUND: Color = Color::Rgb(0xe5, 0xe5, 0xe5);
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Draws a card line by line, top to bottom
+struct Card<'a> {
+    theme: &'a Theme,
+    body: Vec<String>,
+    // Baseline of the next line
+    y: f64,
+}
+
+impl Card<'_> {
+    fn foreground(&self) -> Color {
+        self.theme.card.fg.unwrap_or(DEFAULT_FOREGROUND)
+    }
+
+    // `text` at `column` on the current line, in `style`
+    fn text(&mut self, column: usize, text: &str, style: &Style) {
+        let x = PADDING + column as f64 * CHAR_WIDTH;
+        if let Some(bg) = style.bg {
+            self.body.push(format!(
+                r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"/>"#,
+                x,
+                self.y - FONT_SIZE,
+                text.width() as f64 * CHAR_WIDTH,
+                LINE_HEIGHT,
+                bg.hex()
+            ));
+        }
+        let mut attributes = format!(r#"fill="{}""#, style.fg.unwrap_or(self.foreground()).hex());
+        if style.bold {
+            attributes.push_str(r#" font-weight="bold""#);
+        }
+        if style.italic {
+            attributes.push_str(r#" font-style="italic""#);
+        }
+        if style.underline {
+            attributes.push_str(r#" text-decoration="underline""#);
+        }
+        if style.dim {
+            attributes.push_str(r#" opacity="0.6""#);
+        }
+        self.body.push(format!(r#"<text x="{:.1}" y="{:.1}" {}>{}</text>"#, x, self.y, attributes, escape(text)));
+    }
+
+    fn next_line(&mut self) {
+        self.y += LINE_HEIGHT;
+    }
+
+    // A bar `share` full, from `column` to `column + BAR_COLUMNS`
+    fn bar(&mut self, column: usize, share: f64, color: Color) {
+        let x = PADDING + column as f64 * CHAR_WIDTH;
+        let width = BAR_COLUMNS as f64 * CHAR_WIDTH;
+        let y = self.y - FONT_SIZE * 0.75;
+        let height = FONT_SIZE * 0.75;
+        self.body.push(format!(
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="3" fill="{}" opacity="0.25"/>"#,
+            x,
+            y,
+            width,
+            height,
+            self.foreground().hex()
+        ));
+        self.body.push(format!(
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="3" fill="{}"/>"#,
+            x,
+            y,
+            width * share.clamp(0.0, 1.0),
+            height,
+            color.hex()
+        ));
+    }
+}
+
+// The report as an SVG card: each spec section as a table, the score as a
+// bar, then the roasts, all in the theme's colors. Nothing in it depends on
+// the time or the machine drawing it, so the same report (and the same
+// --seed) always gives the same bytes.
+pub fn svg(report: &Report, theme: &Theme) -> String {
+    let mut card = Card { theme, body: Vec::new(), y: PADDING + FONT_SIZE };
+    for section in &report.sections {
+        card.text(0, &section.title, &theme.header);
+        card.next_line();
+        let column = 2 + section.specs.iter().map(|(label, _)| label.width()).max().unwrap_or(0) + 2;
+        for (label, value) in &section.specs {
+            card.text(2, label, &theme.label);
+            for line in wrap(value, COLUMNS.saturating_sub(column), COLUMNS.saturating_sub(column)) {
+                card.text(column, &line, &theme.value);
+                card.next_line();
+            }
+        }
+        card.next_line();
+    }
+    if let Some(score) = report.score {
+        let label = tr("label.score");
+        let column = 2 + label.width() + 2;
+        // The bar takes the color roasts at the matching severity would have
+        let style = match score {
+            70.. => &theme.gentle,
+            40.. => &theme.standard,
+            _ => &theme.savage,
+        };
+        card.text(2, label, &theme.label);
+        card.bar(column, score as f64 / 100.0, style.fg.unwrap_or(card.foreground()));
+        card.text(column + BAR_COLUMNS + 2, &format!("{}/100", score), &theme.value);
+        card.next_line();
+        card.next_line();
+    }
+    if !report.heading.is_empty() {
+        card.text(0, &report.heading, &theme.roast);
+        card.next_line();
+    }
+    for (_, roast) in &report.roasts {
+        let style = theme.severity(roast.severity);
+        for (index, line) in wrap(&roast.text, COLUMNS - 2, COLUMNS - 4).iter().enumerate() {
+            card.text(if index == 0 { 2 } else { 4 }, line, style);
+            card.next_line();
+        }
+    }
+    let height = (card.y - FONT_SIZE + PADDING).ceil();
+    let background = theme.card.bg.unwrap_or(DEFAULT_BACKGROUND);
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="{font}, monospace" font-size="{size}">"#,
+        w = WIDTH,
+        h = height,
+        font = FONT_FAMILY,
+        size = FONT_SIZE
+    );
+    svg.push('\n');
+    svg.push_str(&format!(
+        r#"<rect x="0.5" y="0.5" width="{}" height="{}" rx="12" fill="{}" stroke="{}"/>"#,
+        WIDTH - 1.0,
+        height - 1.0,
+        background.hex(),
+        card.foreground().hex()
+    ));
+    svg.push('\n');
+    for element in &card.body {
+        svg.push_str(element);
+        svg.push('\n');
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// Whether the bundled font has every character of `text`, so a PNG of it
+// comes out the same on any machine
+pub fn covers(text: &str) -> bool {
+    match ttf_parser::Face::parse(FONTS[0], 0) {
+        Ok(face) => text.chars().all(|c| c.is_ascii() || face.glyph_index(c).is_some()),
+        Err(_) => false,
+    }
+}
+
+// An SVG card drawn as a PNG
+pub fn png(svg: &str) -> Result<Vec<u8>, String> {
+    let mut options = usvg::Options { font_family: FONT_FAMILY.to_string(), ..usvg::Options::default() };
+    for font in FONTS {
+        options.fontdb_mut().load_font_data(font.to_vec());
+    }
+    // Only when needed: scanning them is slow, and they differ from machine to machine.
+    // Loaded after the embedded faces, so those win over a system copy of the same font.
+    if !covers(svg) {
+        options.fontdb_mut().load_system_fonts();
+    }
+    let tree = usvg::Tree::from_str(svg, &options).map_err(|err| err.to_string())?;
+    let size = tree.size().to_int_size().scale_by(PNG_SCALE).ok_or("card is too large")?;
+    let mut pixmap = Pixmap::new(size.width(), size.height()).ok_or("card is too large")?;
+    resvg::render(&tree, Transform::from_scale(PNG_SCALE, PNG_SCALE), &mut pixmap.as_mut());
+    pixmap.encode_png().map_err(|err| err.to_string())
+}
@@ -0,0 +1,108 @@
+use crate::config::Config;
+use crate::history::History;
+use crate::i18n;
+use crate::pack;
+use crate::render::{self, Format};
+use crate::roast::{Backend, Mode, Roaster, Severity, Tone};
+use crate::theme::{self, ColorChoice};
+use clap::Args;
+
+// The flags both `rust_roast` and `cargo roast` take: how roasts are chosen
+// and how they're shown. Each one falls back to the config file when left out.
+#[derive(Args)]
+pub struct RoastArgs {
+    /// How harsh the roasts get (defaults to the config file, then "standard")
+    #[arg(long, value_enum, global = true)]
+    pub severity: Option<Severity>,
+
+    /// Leave out roasts about the person rather than the machine
+    #[arg(long, global = true)]
+    pub workplace_safe: bool,
+
+    /// Compliment instead of roasting
+    #[arg(long, global = true, conflicts_with = "mixed")]
+    pub toast: bool,
+
+    /// Follow each roast with a backhanded compliment
+    #[arg(long, global = true)]
+    pub mixed: bool,
+
+    /// Where roasts come from: the pools as written, generated from grammars, remixed by a Markov chain, or a language model
+    #[arg(long, value_enum, global = true)]
+    pub backend: Option<Backend>,
+
+    /// Seed for picking roasts, so the same input gives the same output (implies --no-history)
+    #[arg(long, global = true)]
+    pub seed: Option<u64>,
+
+    /// Base URL of an OpenAI-compatible API for --backend llm, e.g. http://localhost:8080/v1
+    #[arg(long, global = true)]
+    pub endpoint: Option<String>,
+
+    /// Don't read or update the history of recently shown roasts
+    #[arg(long, global = true)]
+    pub no_history: bool,
+
+    /// Color theme: default, monochrome, solarized, high-contrast, dracula, or one of your own
+    #[arg(long, global = true)]
+    pub theme: Option<String>,
+
+    /// When to use colors (auto turns them off when stdout isn't a terminal or NO_COLOR is set)
+    #[arg(long, value_enum, global = true)]
+    pub color: Option<ColorChoice>,
+
+    /// Output format: text for the terminal, an SVG or PNG card to share, an HTML page, or Markdown to paste into issues
+    #[arg(long, value_enum, global = true)]
+    pub format: Option<Format>,
+
+    /// With --format markdown, add the raw snapshot as JSON under a <details> fold
+    #[arg(long, global = true)]
+    pub details: bool,
+
+    /// Language for roasts and labels, e.g. "de" or "pt_BR" (defaults to LC_MESSAGES / LANG)
+    #[arg(long, global = true)]
+    pub lang: Option<String>,
+}
+
+impl RoastArgs {
+    pub fn format(&self) -> Format {
+        self.format.unwrap_or_default()
+    }
+
+    // Sets up the theme, the language and the output format
+    pub fn init(&self, config: &Config) {
+        theme::init(self.theme.as_deref().unwrap_or(&config.output.theme), self.color.unwrap_or(config.output.color));
+        i18n::init(i18n::detect(self.lang.as_deref().or(config.roast.lang.as_deref())));
+        render::set_format(self.format());
+        render::set_details(self.details);
+    }
+
+    // The roaster these flags ask for. Call after `init`, since which packs
+    // are loaded depends on the language.
+    pub fn roaster(&self, config: &Config) -> Roaster {
+        let tone = Tone {
+            severity: self.severity.unwrap_or(config.roast.severity),
+            workplace_safe: self.workplace_safe || config.roast.workplace_safe,
+        };
+        // Steering away from recent roasts would make a seeded run depend on
+        // the runs before it
+        let history = if self.no_history || self.seed.is_some() { History::disabled() } else { History::load(config.history.runs) };
+        let mode = if self.toast {
+            Mode::Toast
+        } else if self.mixed {
+            Mode::Mixed
+        } else {
+            config.roast.mode
+        };
+        let mut llm = config.llm.clone();
+        llm.endpoint = self.endpoint.clone().or(llm.endpoint);
+        Roaster::builder(pack::load_packs(i18n::languages()))
+            .tone(tone)
+            .history(history)
+            .mode(mode)
+            .backend(self.backend.unwrap_or(config.roast.backend))
+            .llm(llm)
+            .seed(self.seed)
+            .build()
+    }
+}
@@ -0,0 +1,217 @@
+use crate::pack::{PackRoast, RoastPack};
+use crate::roast::Roast;
+use crate::snapshot::{self, Snapshot};
+
+// A mismatch between components, e.g. a flagship GPU next to a dual-core CPU.
+// When the condition holds the combo is roasted first, and the single-category
+// roasts it covers ("cpu", "gpu", ...) are skipped so the joke isn't told twice.
+pub struct ComboRule {
+    pub id: String,
+    pub condition: String,
+    pub covers: Vec<String>,
+    pub roasts: Vec<Roast>,
+    // Lines from translation packs, preferred over `roasts` when present
+    pub localized: Vec<Roast>,
+}
+
+fn builtin(id: &str, condition: &str, covers: &[&str], roasts: &[Roast]) -> ComboRule {
+    ComboRule {
+        id: id.to_string(),
+        condition: condition.to_string(),
+        covers: covers.iter().map(|c| c.to_string()).collect(),
+        roasts: roasts.to_vec(),
+        localized: Vec::new(),
+    }
+}
+
+fn builtin_rules() -> Vec<ComboRule> {
+    vec![
+        builtin("gpu_bottleneck", "gpu.tier == high_end && cpu.cores <= 4", &["cpu", "gpu"], &[
+            Roast::gentle("A high-end GPU paired with a {cpu_cores}-core CPU. The GPU spends most of its day waiting."),
+            Roast::standard("{gpu} and a {cpu_cores}-core CPU. That's not a build, that's a bottleneck with RGB."),
+            Roast::savage("You spent the whole budget on {gpu} and bought the CPU with the change in the couch."),
+        ]),
+        builtin("cores_no_memory", "cpu.cores >= 16 && ram.total_gb <= 8", &["cpu", "ram"], &[
+            Roast::gentle("{cpu_cores} cores sharing {ram_gb} GB of RAM. They take turns."),
+            Roast::standard("{cpu_cores} cores and {ram_gb} GB of RAM. Every core gets a whole few hundred megabytes to itself."),
+            Roast::savage("A {cpu_cores}-core workstation with {ram_gb} GB of RAM is a race car with a lawnmower fuel tank."),
+        ]),
+        builtin("ram_on_spinning_rust", "ram.total_gb >= 32 && storage.hdd && storage.ssd == false", &["ram"], &[
+            Roast::gentle("{ram_gb} GB of RAM loading everything from a spinning disk. At least the cache is roomy."),
+            Roast::standard("{ram_gb} GB of RAM and a hard drive. You can hold everything in memory, once it finally arrives."),
+            Roast::savage("All that RAM and no SSD. Your boot sequence has an intermission."),
+        ]),
+        builtin("server_gamer", "gpu.tier == high_end && chassis == server", &["gpu", "form_factor"], &[
+            Roast::gentle("A gaming GPU in a server chassis. The rack has never been so colourful."),
+            Roast::standard("{gpu} in a server. Either you're training models or playing games on company time."),
+            Roast::savage("A gaming card in a rack-mount server. The SREs would like a word.").personal(),
+        ]),
+        builtin("throttled_laptop", "form_factor == laptop && cpu.cores >= 8 && power.profile == power-saver", &["cpu", "form_factor"], &[
+            Roast::gentle("A {cpu_cores}-core laptop in power-saver mode. All that silicon, on a diet."),
+            Roast::standard("You bought a {cpu_cores}-core laptop and run it in battery saver. It's a sports car in school-zone mode."),
+            Roast::savage("{cpu_cores} cores throttled to save battery. You paid for performance and chose not to have it."),
+        ]),
+    ]
+}
+
+// Built-in combos with any pack [[combos]] entries merged in
+pub fn combo_rules(packs: &[RoastPack]) -> Vec<ComboRule> {
+    let mut rules = builtin_rules();
+    for pack in packs {
+        for entry in &pack.combos {
+            if entry.id.is_empty() {
+                continue;
+            }
+            let index = match rules.iter().position(|rule| rule.id == entry.id) {
+                Some(index) => index,
+                None => {
+                    rules.push(builtin(&entry.id, "", &[], &[]));
+                    rules.len() - 1
+                }
+            };
+            let rule = &mut rules[index];
+            let roasts = entry.roasts.iter().map(PackRoast::to_roast);
+            if pack.lang.is_some() {
+                rule.localized.extend(roasts);
+            } else {
+                rule.roasts.extend(roasts);
+            }
+            if let Some(condition) = &entry.when {
+                rule.condition = condition.clone();
+            }
+            if let Some(covers) = &entry.covers {
+                rule.covers = covers.clone();
+            }
+        }
+    }
+    rules
+}
+
+// Combos whose conditions hold, most specific first. A combo without a
+// condition never fires.
+pub fn firing<'a>(rules: &'a [ComboRule], snapshot: &Snapshot) -> Vec<&'a ComboRule> {
+    let mut fired: Vec<(&ComboRule, usize)> = rules
+        .iter()
+        .filter(|rule| !rule.condition.trim().is_empty())
+        .filter_map(|rule| snapshot::check(&rule.condition, snapshot).ok().flatten().map(|s| (rule, s)))
+        .collect();
+    fired.sort_by_key(|(_, specificity)| std::cmp::Reverse(*specificity));
+    fired.into_iter().map(|(rule, _)| rule).collect()
+}
+
+// Fills {cpu_cores}, {ram_gb} and {gpu} from the snapshot
+pub fn fill(roast: &str, snapshot: &Snapshot) -> String {
+    let fact = |key: &str| match snapshot.get(key) {
+        Some(snapshot::Fact::Number(n)) => format!("{:.0}", n),
+        Some(snapshot::Fact::Text(text)) => text.clone(),
+        Some(snapshot::Fact::Bool(flag)) => flag.to_string(),
+        None => "?".to_string(),
+    };
+    roast
+        .replace("{cpu_cores}", &fact("cpu.cores"))
+        .replace("{ram_gb}", &fact("ram.total_gb"))
+        .replace("{gpu}", &fact("gpu.name"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(text: &str) -> RoastPack {
+        toml::from_str(text).unwrap()
+    }
+
+    fn ids<'a>(rules: &[&'a ComboRule]) -> Vec<&'a str> {
+        rules.iter().map(|rule| rule.id.as_str()).collect()
+    }
+
+    #[test]
+    fn fires_when_the_condition_holds() {
+        let rules = combo_rules(&[]);
+        let mut snapshot = Snapshot::default();
+        snapshot.set("gpu.tier", "high_end");
+        snapshot.set("cpu.cores", 4.0);
+        assert_eq!(ids(&firing(&rules, &snapshot)), ["gpu_bottleneck"]);
+        snapshot.set("cpu.cores", 8.0);
+        assert!(firing(&rules, &snapshot).is_empty());
+    }
+
+    #[test]
+    fn most_specific_fires_first() {
+        let extra = pack(
+            r#"
+            [[combos]]
+            id = "broad"
+            when = "gpu.tier == high_end"
+            roasts = ["Nice GPU."]
+            "#,
+        );
+        let rules = combo_rules(&[extra]);
+        let mut snapshot = Snapshot::default();
+        snapshot.set("gpu.tier", "high_end");
+        snapshot.set("cpu.cores", 2.0);
+        snapshot.set("chassis", "server");
+        let fired = ids(&firing(&rules, &snapshot));
+        assert_eq!(fired.last(), Some(&"broad"));
+        assert!(fired.contains(&"gpu_bottleneck") && fired.contains(&"server_gamer"));
+    }
+
+    #[test]
+    fn combos_without_a_condition_never_fire() {
+        // A pack adding lines to an id no built-in has, and giving no `when`
+        let extra = pack(
+            r#"
+            [[combos]]
+            id = "orphan"
+            roasts = ["Never said."]
+            "#,
+        );
+        let rules = combo_rules(&[extra]);
+        assert!(rules.iter().any(|rule| rule.id == "orphan"));
+        assert!(firing(&rules, &Snapshot::default()).is_empty());
+    }
+
+    #[test]
+    fn packs_extend_and_override_builtins() {
+        let extra = pack(
+            r#"
+            [[combos]]
+            id = "gpu_bottleneck"
+            when = "gpu.tier == high_end && cpu.cores <= 6"
+            covers = ["gpu"]
+            roasts = ["One more."]
+            "#,
+        );
+        let translation = pack(
+            r#"
+            [[combos]]
+            id = "gpu_bottleneck"
+            roasts = ["Noch einer."]
+            "#,
+        );
+        let translation = RoastPack { lang: Some("de".to_string()), ..translation };
+        let rules = combo_rules(&[extra, translation]);
+        let rule = rules.iter().find(|rule| rule.id == "gpu_bottleneck").unwrap();
+        assert_eq!(rule.condition, "gpu.tier == high_end && cpu.cores <= 6");
+        assert_eq!(rule.covers, ["gpu"]);
+        assert_eq!(rule.roasts.last().unwrap().text, "One more.");
+        assert_eq!(rule.localized.len(), 1);
+        assert_eq!(rule.localized[0].text, "Noch einer.");
+    }
+
+    #[test]
+    fn builtins_cover_the_categories_they_roast() {
+        let rules = combo_rules(&[]);
+        let covers = |id: &str| rules.iter().find(|rule| rule.id == id).unwrap().covers.clone();
+        assert_eq!(covers("gpu_bottleneck"), ["cpu", "gpu"]);
+        assert_eq!(covers("ram_on_spinning_rust"), ["ram"]);
+    }
+
+    #[test]
+    fn fills_facts_from_the_snapshot() {
+        let mut snapshot = Snapshot::default();
+        snapshot.set("cpu.cores", 2.0);
+        snapshot.set("gpu.name", "RTX 4090");
+        assert_eq!(fill("{gpu} with {cpu_cores} cores and {ram_gb} GB", &snapshot), "RTX 4090 with 2 cores and ? GB");
+    }
+}
@@ -0,0 +1,314 @@
+use crate::render::Layout;
+use crate::roast::{Backend, Mode, Severity};
+use crate::theme::ColorChoice;
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+
+// Settings from ~/.config/rust_roast/config.toml. Every field has a default,
+// so a missing file or a partial one is fine.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub roast: RoastConfig,
+    pub output: OutputConfig,
+    pub history: HistoryConfig,
+    pub uptime: UptimeConfig,
+    pub os: OsConfig,
+    pub packages: PackagesConfig,
+    pub dev: DevConfig,
+    pub project: ProjectConfig,
+    pub git: GitConfig,
+    pub tui: TuiConfig,
+    pub watch: WatchConfig,
+    pub llm: LlmConfig,
+}
+
+// Defaults for the --severity, --workplace-safe, --toast/--mixed, --backend and --lang flags
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct RoastConfig {
+    pub severity: Severity,
+    pub workplace_safe: bool,
+    // roast, toast or mixed
+    pub mode: Mode,
+    // pools, grammar, markov or llm
+    pub backend: Backend,
+    // Language tag like "de" or "pt_BR"; unset means LC_ALL / LC_MESSAGES / LANG
+    pub lang: Option<String>,
+}
+
+// How results are shown
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct OutputConfig {
+    // A built-in theme, or a file in ~/.config/rust_roast/themes/ without ".toml"
+    pub theme: String,
+    // auto, always or never
+    pub color: ColorChoice,
+    // plain, or fetch for specs and roasts beside a logo
+    pub layout: Layout,
+    // "auto" for the distro or CPU vendor logo, a built-in logo, or a file in
+    // ~/.config/rust_roast/logos/ without ".toml"
+    pub logo: String,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        OutputConfig {
+            theme: "default".to_string(),
+            color: ColorChoice::Auto,
+            layout: Layout::Plain,
+            logo: "auto".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    // Runs whose roasts are avoided next time; 0 turns the history off
+    pub runs: usize,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        HistoryConfig { runs: 10 }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct UptimeConfig {
+    // Uptime (in days) after which pending patches are assumed
+    pub long_days: u64,
+    // Uptime (in days) that earns the "museum piece" roasts
+    pub ancient_days: u64,
+    // Boots within the last 24 hours that count as a reboot loop
+    pub reboots_per_day: usize,
+}
+
+impl Default for UptimeConfig {
+    fn default() -> Self {
+        UptimeConfig {
+            long_days: 30,
+            ancient_days: 365,
+            reboots_per_day: 3,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct OsConfig {
+    // Kernel series older than this (in years) get roasted
+    pub old_kernel_years: u64,
+}
+
+impl Default for OsConfig {
+    fn default() -> Self {
+        OsConfig { old_kernel_years: 3 }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct PackagesConfig {
+    // Installed packages (across all managers) that count as bloat
+    pub bloat: usize,
+    // `cargo install` binaries that earn a roast
+    pub cargo_bins: usize,
+}
+
+impl Default for PackagesConfig {
+    fn default() -> Self {
+        PackagesConfig {
+            bloat: 2500,
+            cargo_bins: 20,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct DevConfig {
+    // Installed rustup toolchains that count as hoarding
+    pub toolchains: usize,
+    // Size of ~/.cargo/registry (in GB) that earns a roast
+    pub cargo_registry_gb: f64,
+    // Size of target/ (in GB) that earns a roast
+    pub target_gb: f64,
+    // Size of a single dotfile (in KB) that earns a roast
+    pub dotfile_kb: u64,
+}
+
+impl Default for DevConfig {
+    fn default() -> Self {
+        DevConfig {
+            toolchains: 4,
+            cargo_registry_gb: 2.0,
+            target_gb: 5.0,
+            dotfile_kb: 32,
+        }
+    }
+}
+
+// Thresholds for `cargo roast`
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ProjectConfig {
+    // Distinct dependencies that count as too many
+    pub dependencies: usize,
+    // Crates locked at more than one version
+    pub duplicates: usize,
+    pub unsafe_blocks: usize,
+    pub unwraps_per_kloc: f64,
+}
+
+impl Default for ProjectConfig {
+    fn default() -> Self {
+        ProjectConfig {
+            dependencies: 40,
+            duplicates: 3,
+            unsafe_blocks: 5,
+            unwraps_per_kloc: 5.0,
+        }
+    }
+}
+
+// Thresholds for `rust_roast git`
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct GitConfig {
+    // Share of commits with lazy messages ("wip", "fix", ...)
+    pub lazy_share: f64,
+    // Share of commits made between midnight and 5am
+    pub night_share: f64,
+    // Size of a single blob in history (in MB)
+    pub big_blob_mb: u64,
+    // Local branches
+    pub branches: usize,
+    // Resets and rebases in the HEAD reflog
+    pub rewrites: usize,
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        GitConfig {
+            lazy_share: 0.2,
+            night_share: 0.25,
+            big_blob_mb: 10,
+            branches: 20,
+            rewrites: 10,
+        }
+    }
+}
+
+// Thresholds for `rust_roast tui`: readings at or above these switch the
+// roast panel to that part of the machine
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct TuiConfig {
+    // CPU and GPU load, in percent
+    pub cpu_busy: f32,
+    pub gpu_busy: f32,
+    // RAM in use, in percent
+    pub ram_full: f32,
+    // The hottest sensor, in °C
+    pub hot_celsius: f32,
+}
+
+impl Default for TuiConfig {
+    fn default() -> Self {
+        TuiConfig {
+            cpu_busy: 90.0,
+            gpu_busy: 90.0,
+            ram_full: 90.0,
+            hot_celsius: 85.0,
+        }
+    }
+}
+
+// Thresholds and pacing for `rust_roast watch`
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct WatchConfig {
+    // Seconds between samples
+    pub interval_secs: u64,
+    // Battery charge (in percent) that counts as low while discharging
+    pub battery_low: u32,
+    // Swap in use (in MB) that counts as swapping
+    pub swap_mb: u64,
+    // A rise of this many °C within `spike_secs` is a temperature spike
+    pub spike_celsius: f32,
+    pub spike_secs: u64,
+    // Share of RAM a single program (all its processes) may use
+    pub program_ram_share: f64,
+    // Seconds before the same kind of event is roasted again
+    pub cooldown_secs: u64,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        WatchConfig {
+            interval_secs: 2,
+            battery_low: 10,
+            swap_mb: 64,
+            spike_celsius: 15.0,
+            spike_secs: 30,
+            program_ram_share: 0.25,
+            cooldown_secs: 300,
+        }
+    }
+}
+
+// The model server for --backend llm
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LlmConfig {
+    // Base URL of an OpenAI-compatible API, e.g. "http://localhost:8080/v1"
+    pub endpoint: Option<String>,
+    pub model: String,
+    // Seconds to wait for the whole reply before falling back to the pools
+    pub timeout_secs: u64,
+    // Roasts to ask for per run
+    pub roasts: usize,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        LlmConfig {
+            endpoint: None,
+            model: "default".to_string(),
+            timeout_secs: 20,
+            roasts: 5,
+        }
+    }
+}
+
+// Directory that holds user configuration (XDG_CONFIG_HOME or ~/.config)
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join("rust_roast"));
+    }
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config").join("rust_roast"))
+}
+
+// Loads config.toml, falling back to defaults if it is missing or broken
+pub fn load_config() -> Config {
+    let path = match config_dir() {
+        Some(dir) => dir.join("config.toml"),
+        None => return Config::default(),
+    };
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(_) => return Config::default(),
+    };
+    match toml::from_str(&text) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("rust_roast: ignoring {}: {}", path.display(), err.to_string().trim());
+            Config::default()
+        }
+    }
+}
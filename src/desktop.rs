@@ -0,0 +1,285 @@
+use crate::roast::Roast;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use sysinfo::{ProcessExt, System, SystemExt};
+
+pub struct DesktopInfo {
+    // Desktop environment from XDG_CURRENT_DESKTOP / DESKTOP_SESSION
+    pub desktop: Option<String>,
+    // Window manager found in the process list
+    pub window_manager: Option<String>,
+    pub tiling: bool,
+    // "Wayland", "X11" or "TTY"
+    pub display_server: Option<String>,
+    pub displays: Vec<Display>,
+}
+
+impl DesktopInfo {
+    // Highest known refresh rate across connected displays
+    pub fn max_refresh_hz(&self) -> Option<f64> {
+        self.displays.iter().filter_map(|d| d.refresh_hz).reduce(f64::max)
+    }
+
+    // A 1366x768-class panel (or worse) is connected
+    pub fn has_low_res_panel(&self) -> bool {
+        self.displays.iter().any(|d| d.width <= 1366 && d.height <= 768)
+    }
+}
+
+pub struct Display {
+    pub connector: String,
+    pub width: u32,
+    pub height: u32,
+    // Refresh rate of the preferred mode, from the EDID
+    pub refresh_hz: Option<f64>,
+}
+
+impl std::fmt::Display for Display {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)?;
+        if let Some(hz) = self.refresh_hz {
+            write!(f, " @ {:.0} Hz", hz)?;
+        }
+        Ok(())
+    }
+}
+
+// (process name, display name)
+const TILING_WMS: &[(&str, &str)] = &[
+    ("i3", "i3"),
+    ("sway", "Sway"),
+    ("hyprland", "Hyprland"),
+    ("bspwm", "bspwm"),
+    ("dwm", "dwm"),
+    ("xmonad", "xmonad"),
+    ("awesome", "awesome"),
+    ("qtile", "Qtile"),
+    ("herbstluftwm", "herbstluftwm"),
+    ("river", "river"),
+    ("niri", "niri"),
+    ("leftwm", "LeftWM"),
+];
+
+const FLOATING_WMS: &[(&str, &str)] = &[
+    ("kwin_wayland", "KWin"),
+    ("kwin_x11", "KWin"),
+    ("gnome-shell", "Mutter"),
+    ("mutter", "Mutter"),
+    ("xfwm4", "Xfwm4"),
+    ("openbox", "Openbox"),
+    ("fluxbox", "Fluxbox"),
+    ("marco", "Marco"),
+    ("muffin", "Muffin"),
+    ("cinnamon", "Muffin"),
+    ("enlightenment", "Enlightenment"),
+    ("icewm", "IceWM"),
+    ("weston", "Weston"),
+];
+
+pub fn gather(sys: &System) -> DesktopInfo {
+    let desktop = desktop_environment();
+    let window_manager = window_manager(sys);
+
+    // Tiling compositors often set XDG_CURRENT_DESKTOP to their own name
+    let desktop_lower = desktop.as_deref().unwrap_or_default().to_lowercase();
+    let tiling = TILING_WMS.iter().any(|(process, name)| {
+        window_manager.as_deref() == Some(*name) || desktop_lower == *process
+    });
+
+    DesktopInfo {
+        desktop,
+        window_manager,
+        tiling,
+        display_server: display_server(),
+        displays: connected_displays(),
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn desktop_environment() -> Option<String> {
+    // XDG_CURRENT_DESKTOP is a colon-separated list like "ubuntu:GNOME"
+    env_var("XDG_CURRENT_DESKTOP")
+        .map(|value| value.split(':').next_back().unwrap_or_default().to_string())
+        .or_else(|| env_var("DESKTOP_SESSION"))
+}
+
+fn window_manager(sys: &System) -> Option<String> {
+    let running: Vec<String> = sys.processes().values().map(|p| p.name().to_lowercase()).collect();
+    TILING_WMS
+        .iter()
+        .chain(FLOATING_WMS.iter())
+        .find(|(process, _)| running.iter().any(|name| name == process))
+        .map(|(_, name)| name.to_string())
+}
+
+fn display_server() -> Option<String> {
+    if env_var("WAYLAND_DISPLAY").is_some() {
+        return Some("Wayland".to_string());
+    }
+    let session_type = env_var("XDG_SESSION_TYPE").or_else(loginctl_session_type);
+    match session_type.as_deref() {
+        Some("wayland") => Some("Wayland".to_string()),
+        Some("x11") => Some("X11".to_string()),
+        Some("tty") => Some("TTY".to_string()),
+        _ if env_var("DISPLAY").is_some() => Some("X11".to_string()),
+        _ => None,
+    }
+}
+
+// Session type via `loginctl show-session`, for shells that lost the XDG variables
+fn loginctl_session_type() -> Option<String> {
+    let session = env_var("XDG_SESSION_ID").unwrap_or_else(|| "self".to_string());
+    let output = Command::new("loginctl")
+        .args(["show-session", &session, "-p", "Type", "--value"])
+        .output()
+        .ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() && !value.is_empty() {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+// Connected outputs from /sys/class/drm/card*-*/ (status, modes and edid)
+fn connected_displays() -> Vec<Display> {
+    let entries = match fs::read_dir("/sys/class/drm") {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut displays: Vec<Display> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| read_display(&entry.path()))
+        .collect();
+    displays.sort_by(|a, b| a.connector.cmp(&b.connector));
+    displays
+}
+
+fn read_display(path: &Path) -> Option<Display> {
+    let status = fs::read_to_string(path.join("status")).ok()?;
+    if status.trim() != "connected" {
+        return None;
+    }
+    // The first mode listed is the preferred one, e.g. "1920x1080"
+    let modes = fs::read_to_string(path.join("modes")).ok()?;
+    let (width, height) = modes.lines().next()?.trim().split_once('x')?;
+    let height = height.trim_end_matches(|c: char| !c.is_ascii_digit());
+
+    let name = path.file_name()?.to_string_lossy();
+    // card0-HDMI-A-1 -> HDMI-A-1
+    let connector = name.split_once('-').map_or(name.as_ref(), |(_, c)| c).to_string();
+    let refresh_hz = fs::read(path.join("edid")).ok().and_then(|edid| edid_refresh_rate(&edid));
+
+    Some(Display {
+        connector,
+        width: width.parse().ok()?,
+        height: height.parse().ok()?,
+        refresh_hz,
+    })
+}
+
+// Refresh rate of the first detailed timing descriptor (the preferred mode) in an EDID block
+fn edid_refresh_rate(edid: &[u8]) -> Option<f64> {
+    let dtd = edid.get(54..72)?;
+    let pixel_clock = u16::from_le_bytes([dtd[0], dtd[1]]) as f64 * 10_000.0;
+    if pixel_clock == 0.0 {
+        return None;
+    }
+    let h_total = (dtd[2] as u32 | (dtd[4] as u32 & 0xF0) << 4) + (dtd[3] as u32 | (dtd[4] as u32 & 0x0F) << 8);
+    let v_total = (dtd[5] as u32 | (dtd[7] as u32 & 0xF0) << 4) + (dtd[6] as u32 | (dtd[7] as u32 & 0x0F) << 8);
+    if h_total == 0 || v_total == 0 {
+        return None;
+    }
+    Some(pixel_clock / (h_total * v_total) as f64)
+}
+
+pub const TILING_WM_ROASTS: &[Roast] = &[
+    Roast::savage("A tiling window manager. Your dotfiles repo has more stars than your actual projects.").personal(),
+    Roast::standard("You use {wm}. I bet you mention it before you say hello.").personal(),
+    Roast::gentle("{wm} user spotted. How's the config rewrite going? Week three?"),
+    Roast::standard("You spent 40 hours configuring {wm} to save 4 seconds a day. Great math."),
+    Roast::standard("A tiling WM and a 'minimal' setup that somehow needs 14 scripts to show the battery level."),
+];
+
+pub const LOW_RES_ROASTS: &[Roast] = &[
+    Roast::standard("1366x768? That's not a screen, that's a postage stamp with a backlight."),
+    Roast::savage("Your display resolution peaked in 2009 and so, apparently, did your standards.").personal(),
+    Roast::gentle("At 1366x768 you can see almost two whole paragraphs at once. Living large."),
+    Roast::standard("Your panel has fewer pixels than my phone's lock screen wallpaper."),
+];
+
+pub const SIXTY_HZ_GAMER_ROASTS: &[Roast] = &[
+    Roast::standard("A gaming rig on a 60 Hz display. You're rendering frames your monitor will never show."),
+    Roast::gentle("All that GPU power and you're capped at {hz} Hz. Like buying a sports car to sit in traffic."),
+    Roast::standard("60 Hz? Your frames per second are sponsored by the year 2012."),
+    Roast::standard("You bought the GPU, forgot the monitor. Classic.").personal(),
+];
+
+// The pools above, by name
+pub const POOLS: &[(&str, &[Roast])] = &[
+    ("desktop.tiling", TILING_WM_ROASTS),
+    ("desktop.low_res", LOW_RES_ROASTS),
+    ("desktop.sixty_hz", SIXTY_HZ_GAMER_ROASTS),
+];
+
+// Fills the {wm} and {hz} placeholders
+pub fn fill(roast: &str, info: &DesktopInfo) -> String {
+    let wm = info.window_manager.as_deref().or(info.desktop.as_deref()).unwrap_or("your WM");
+    let hz = info.max_refresh_hz().unwrap_or(60.0);
+    roast.replace("{wm}", wm).replace("{hz}", &format!("{:.0}", hz))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 128-byte EDID block whose preferred mode is 1920x1080 with 280 and
+    // 45 lines of blanking (2200x1125 in total) at `pixel_clock` (10 kHz units)
+    fn edid(pixel_clock: u16) -> Vec<u8> {
+        let mut edid = vec![0; 128];
+        edid[..8].copy_from_slice(&[0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00]);
+        edid[54..56].copy_from_slice(&pixel_clock.to_le_bytes());
+        // Horizontal active and blanking, low 8 bits each, then their high nibbles
+        edid[56..59].copy_from_slice(&[0x80, 0x18, 0x71]);
+        // The same for vertical
+        edid[59..62].copy_from_slice(&[0x38, 0x2D, 0x40]);
+        edid
+    }
+
+    #[test]
+    fn refresh_rate_from_the_preferred_mode() {
+        // 148.5 MHz / (2200 * 1125) = 60 Hz
+        assert_eq!(edid_refresh_rate(&edid(14_850)), Some(60.0));
+        // 356.4 MHz / (2200 * 1125) = 144 Hz
+        assert_eq!(edid_refresh_rate(&edid(35_640)), Some(144.0));
+    }
+
+    #[test]
+    fn no_refresh_rate_without_a_timing() {
+        // A zero pixel clock marks a display descriptor, not a timing
+        assert_eq!(edid_refresh_rate(&edid(0)), None);
+        assert_eq!(edid_refresh_rate(&edid(14_850)[..60]), None);
+        let mut blank = edid(14_850);
+        blank[56..62].fill(0);
+        assert_eq!(edid_refresh_rate(&blank), None);
+    }
+
+    #[test]
+    fn reads_a_connector_from_sysfs() {
+        let dir = std::env::temp_dir().join(format!("rust_roast-drm-{}", std::process::id())).join("card1-eDP-1");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("status"), "connected\n").unwrap();
+        fs::write(dir.join("modes"), "1920x1080\n1280x720\n").unwrap();
+        fs::write(dir.join("edid"), edid(35_640)).unwrap();
+        let display = read_display(&dir).unwrap();
+        assert_eq!(display.connector, "eDP-1");
+        assert_eq!(display.to_string(), "1920x1080 @ 144 Hz");
+        fs::write(dir.join("status"), "disconnected\n").unwrap();
+        assert!(read_display(&dir).is_none());
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+    }
+}
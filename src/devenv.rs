@@ -0,0 +1,375 @@
+use crate::config::DevConfig;
+use crate::roast::Roast;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+// How long a size walk may take and how deep it goes. A registry or target
+// dir can hold hundreds of thousands of files; past these limits the size is
+// a lower bound, which only makes a roast about it less likely.
+const SIZE_BUDGET: Duration = Duration::from_millis(500);
+const SIZE_DEPTH: usize = 12;
+
+pub struct DevEnv {
+    // Basename of $SHELL, e.g. "zsh"
+    pub shell: Option<String>,
+    // Basename of $VISUAL or $EDITOR, e.g. "nvim"
+    pub editor: Option<String>,
+    // Installed rustup toolchains, e.g. "stable-x86_64-unknown-linux-gnu"
+    pub rust_toolchains: Vec<String>,
+    pub node_version: Option<String>,
+    pub python_version: Option<String>,
+    // `python2 --version`, whether or not it's the default python
+    pub python2_version: Option<String>,
+    // Bytes in ~/.cargo/registry
+    pub cargo_registry: u64,
+    // Bytes in the target/ dir of the current directory (or CARGO_TARGET_DIR)
+    pub target_dir: u64,
+    // Largest dotfile in $HOME and its size in bytes
+    pub largest_dotfile: Option<(String, u64)>,
+}
+
+impl DevEnv {
+    // Short one-line summary of the installed toolchains
+    pub fn toolchain_summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.rust_toolchains.is_empty() {
+            let channels: Vec<&str> = self
+                .rust_toolchains
+                .iter()
+                .map(|t| t.split('-').next().unwrap_or(t))
+                .collect();
+            parts.push(format!("rust {}", channels.join(", ")));
+        }
+        if let Some(node) = &self.node_version {
+            parts.push(format!("node {}", node));
+        }
+        if let Some(python) = &self.python_version {
+            parts.push(format!("python {}", python));
+        }
+        parts.join("; ")
+    }
+
+    // The Python 2 that's installed: `python2`, or the default python if
+    // that's still a 2.x
+    pub fn python2(&self) -> Option<&str> {
+        let default = self.python_version.as_deref().filter(|v| v.starts_with("2."));
+        self.python2_version.as_deref().or(default)
+    }
+}
+
+fn env_basename(name: &str) -> Option<String> {
+    let value = std::env::var(name).ok().filter(|v| !v.is_empty())?;
+    // $EDITOR may carry arguments, e.g. "code --wait"
+    let program = value.split_whitespace().next()?;
+    Path::new(program).file_name().map(|n| n.to_string_lossy().to_string())
+}
+
+pub fn gather() -> DevEnv {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|h| h.join(".cargo")));
+    let rustup_home = std::env::var_os("RUSTUP_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|h| h.join(".rustup")));
+    let target = std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("target"));
+
+    DevEnv {
+        shell: env_basename("SHELL"),
+        editor: env_basename("VISUAL").or_else(|| env_basename("EDITOR")),
+        rust_toolchains: rustup_home.map(|dir| rust_toolchains(&dir)).unwrap_or_default(),
+        node_version: command_version("node", "--version"),
+        python_version: command_version("python3", "--version")
+            .or_else(|| command_version("python", "--version"))
+            .map(|v| v.trim_start_matches("Python ").to_string()),
+        python2_version: command_version("python2", "--version").map(|v| v.trim_start_matches("Python ").to_string()),
+        cargo_registry: cargo_home.map_or(0, |dir| dir_size(&dir.join("registry"))),
+        target_dir: dir_size(&target),
+        largest_dotfile: home.and_then(|dir| largest_dotfile(&dir)),
+    }
+}
+
+fn rust_toolchains(rustup_home: &Path) -> Vec<String> {
+    let mut toolchains: Vec<String> = fs::read_dir(rustup_home.join("toolchains"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    toolchains.sort();
+    toolchains
+}
+
+// First line of `<program> --version`, if the program is on PATH
+fn command_version(program: &str, flag: &str) -> Option<String> {
+    let output = Command::new(program).arg(flag).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // Python 2 prints its version to stderr
+    let text = if output.stdout.is_empty() { output.stderr } else { output.stdout };
+    String::from_utf8_lossy(&text).lines().next().map(|l| l.trim().to_string())
+}
+
+// Total size of regular files below a directory, not following symlinks,
+// within SIZE_BUDGET and SIZE_DEPTH
+pub fn dir_size(path: &Path) -> u64 {
+    walk_size(path, SIZE_DEPTH, Some(Instant::now() + SIZE_BUDGET))
+}
+
+// The exact size, however long the walk takes. Symlinks aren't followed, so
+// this always ends.
+pub fn full_size(path: &Path) -> u64 {
+    walk_size(path, usize::MAX, None)
+}
+
+fn walk_size(path: &Path, depth: usize, deadline: Option<Instant>) -> u64 {
+    if depth == 0 || deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+        return 0;
+    }
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => walk_size(&entry.path(), depth - 1, deadline),
+            Ok(kind) if kind.is_file() => entry.metadata().map_or(0, |m| m.len()),
+            _ => 0,
+        })
+        .sum()
+}
+
+fn largest_dotfile(home: &Path) -> Option<(String, u64)> {
+    fs::read_dir(home)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with('.'))
+        // Only config files; histories and caches grow on their own
+        .filter(|entry| !entry.file_name().to_string_lossy().contains("history"))
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_file()))
+        .filter_map(|entry| Some((entry.file_name().to_string_lossy().to_string(), entry.metadata().ok()?.len())))
+        .max_by_key(|(_, size)| *size)
+}
+
+pub fn format_size(bytes: u64) -> String {
+    if bytes as f64 >= GB {
+        format!("{:.1} GB", bytes as f64 / GB)
+    } else if bytes >= 1024 * 1024 {
+        format!("{:.0} MB", bytes as f64 / 1024.0 / 1024.0)
+    } else {
+        format!("{:.0} KB", bytes as f64 / 1024.0)
+    }
+}
+
+// Every dev roast pool (name and built-in lines) that applies to this setup
+pub fn roast_pools(env: &DevEnv, config: &DevConfig) -> Vec<(&'static str, &'static [Roast])> {
+    let mut pools: Vec<(&'static str, &'static [Roast])> = Vec::new();
+
+    match env.shell.as_deref() {
+        Some("bash") => pools.push(("dev.bash", BASH_ROASTS)),
+        Some("zsh") => pools.push(("dev.zsh", ZSH_ROASTS)),
+        Some("fish") => pools.push(("dev.fish", FISH_ROASTS)),
+        Some("nu") => pools.push(("dev.nushell", NUSHELL_ROASTS)),
+        _ => {}
+    }
+    match env.editor.as_deref() {
+        Some("vi" | "vim" | "nvim") => pools.push(("dev.vim", VIM_ROASTS)),
+        Some("emacs" | "emacsclient") => pools.push(("dev.emacs", EMACS_ROASTS)),
+        Some("nano" | "pico") => pools.push(("dev.nano", NANO_ROASTS)),
+        Some("code" | "codium" | "code-insiders") => pools.push(("dev.vscode", VSCODE_EDITOR_ROASTS)),
+        Some("hx" | "helix") => pools.push(("dev.helix", HELIX_ROASTS)),
+        _ => {}
+    }
+
+    if env.rust_toolchains.len() >= config.toolchains {
+        pools.push(("dev.toolchains", TOOLCHAIN_HOARD_ROASTS));
+    } else if env.rust_toolchains.iter().any(|t| t.starts_with("nightly")) {
+        pools.push(("dev.nightly", NIGHTLY_ROASTS));
+    }
+    if env.python2().is_some() {
+        pools.push(("dev.python2", PYTHON2_ROASTS));
+    }
+    if env.cargo_registry as f64 >= config.cargo_registry_gb * GB {
+        pools.push(("dev.registry", REGISTRY_ROASTS));
+    }
+    if env.target_dir as f64 >= config.target_gb * GB {
+        pools.push(("dev.target", TARGET_ROASTS));
+    }
+    if env.largest_dotfile.as_ref().is_some_and(|(_, size)| *size >= config.dotfile_kb * 1024) {
+        pools.push(("dev.dotfile", DOTFILE_ROASTS));
+    }
+    pools
+}
+
+const BASH_ROASTS: &[Roast] = &[
+    Roast::gentle("Still on bash? The default shell for people who never opened the settings."),
+    Roast::savage("Bash user. Your prompt is just `$` and so is your personality.").personal(),
+    Roast::standard("You use bash like it's 1989. Because it is, in your terminal."),
+];
+
+const ZSH_ROASTS: &[Roast] = &[
+    Roast::standard("zsh with oh-my-zsh, I assume? Your prompt takes longer to load than your code takes to run."),
+    Roast::savage("Your zsh prompt shows git status, battery, weather and horoscope. Still no idea what you're doing.").personal(),
+    Roast::gentle("zsh: because bash wasn't complicated enough to configure."),
+];
+
+const FISH_ROASTS: &[Roast] = &[
+    Roast::gentle("fish shell? Enjoy rewriting every script you copy from Stack Overflow."),
+    Roast::standard("You use fish. Friendly interactive shell, unfriendly to every POSIX script ever written."),
+];
+
+const NUSHELL_ROASTS: &[Roast] = &[
+    Roast::standard("Nushell. Your shell is a database now. Your files are still a mess.").personal(),
+    Roast::gentle("You pipe tables in Nushell and still can't find that one file."),
+];
+
+const VIM_ROASTS: &[Roast] = &[
+    Roast::gentle("{editor} as your editor. We'll wait while you figure out how to exit."),
+    Roast::standard("Your {editor} config has 400 plugins to become a worse VS Code."),
+    Roast::savage("You use {editor} and tell everyone about it. Your productivity gains are entirely theoretical.").personal(),
+];
+
+const EMACS_ROASTS: &[Roast] = &[
+    Roast::gentle("Emacs: a great operating system, lacking only a decent text editor."),
+    Roast::standard("Your Emacs config is older than some programming languages. And longer."),
+    Roast::standard("You use Emacs. Your pinky has filed a complaint with HR.").personal(),
+];
+
+const NANO_ROASTS: &[Roast] = &[
+    Roast::gentle("nano as your EDITOR. At least you'll never get stuck trying to quit."),
+    Roast::standard("You use nano. The shortcuts are printed on the screen and you still press the wrong ones.").personal(),
+];
+
+const VSCODE_EDITOR_ROASTS: &[Roast] = &[
+    Roast::gentle("Your EDITOR is VS Code. Git commit messages now take 4 seconds to start typing."),
+    Roast::standard("`code --wait` as your git editor. Nothing says commitment like opening Electron for one line."),
+];
+
+const HELIX_ROASTS: &[Roast] = &[
+    Roast::gentle("Helix? You learned a whole new modal editor just to be different from vim users."),
+    Roast::savage("Helix user. Selection-first editing, decision-last career planning.").personal(),
+];
+
+const NIGHTLY_ROASTS: &[Roast] = &[
+    Roast::gentle("A nightly Rust toolchain. Living dangerously for one feature flag you could've done without."),
+    Roast::savage("You're on nightly because a blog post told you to. Your code is still on stable-quality.").personal(),
+];
+
+const TOOLCHAIN_HOARD_ROASTS: &[Roast] = &[
+    Roast::gentle("{toolchains} Rust toolchains installed. Collecting compilers like trading cards?"),
+    Roast::standard("You have {toolchains} toolchains and still get 'edition2024 is required'."),
+];
+
+const PYTHON2_ROASTS: &[Roast] = &[
+    Roast::gentle("Python {python2} is still installed. It's been end-of-life since 2020. Let it rest."),
+    Roast::standard("Python 2 on PATH? That's not legacy code, that's archaeology."),
+];
+
+const REGISTRY_ROASTS: &[Roast] = &[
+    Roast::standard("Your ~/.cargo/registry is {registry}. You've downloaded half of crates.io and used serde."),
+    Roast::standard("{registry} of cached crates. Your disk is a crates.io mirror with a desktop on top."),
+    Roast::gentle("Your cargo registry is {registry}. `left-pad` would be proud."),
+];
+
+const TARGET_ROASTS: &[Roast] = &[
+    Roast::standard("This target/ directory is {target}. Rust: fast at runtime, heavy on disk."),
+    Roast::standard("{target} of build artifacts for a hello world with 300 dependencies."),
+    Roast::gentle("Your target/ folder is {target}. Ever heard of `cargo clean`? It's free."),
+];
+
+const DOTFILE_ROASTS: &[Roast] = &[
+    Roast::standard("Your {dotfile} is {dotfile_size}. That's not a config file, that's a novel."),
+    Roast::standard("{dotfile} at {dotfile_size}. Somewhere in there is an alias you wrote in 2016 and forgot."),
+    Roast::gentle("A {dotfile_size} {dotfile}? Your shell startup time must be measured in seasons."),
+];
+
+// The pools above, by name
+pub const POOLS: &[(&str, &[Roast])] = &[
+    ("dev.bash", BASH_ROASTS),
+    ("dev.zsh", ZSH_ROASTS),
+    ("dev.fish", FISH_ROASTS),
+    ("dev.nushell", NUSHELL_ROASTS),
+    ("dev.vim", VIM_ROASTS),
+    ("dev.emacs", EMACS_ROASTS),
+    ("dev.nano", NANO_ROASTS),
+    ("dev.vscode", VSCODE_EDITOR_ROASTS),
+    ("dev.helix", HELIX_ROASTS),
+    ("dev.nightly", NIGHTLY_ROASTS),
+    ("dev.toolchains", TOOLCHAIN_HOARD_ROASTS),
+    ("dev.python2", PYTHON2_ROASTS),
+    ("dev.registry", REGISTRY_ROASTS),
+    ("dev.target", TARGET_ROASTS),
+    ("dev.dotfile", DOTFILE_ROASTS),
+];
+
+// Fills the dev placeholders ({shell}, {editor}, {toolchains}, ...)
+pub fn fill(roast: &str, env: &DevEnv) -> String {
+    let (dotfile, dotfile_size) = env.largest_dotfile.clone().unwrap_or_default();
+    roast
+        .replace("{shell}", env.shell.as_deref().unwrap_or("sh"))
+        .replace("{editor}", env.editor.as_deref().unwrap_or("vi"))
+        .replace("{toolchains}", &env.rust_toolchains.len().to_string())
+        .replace("{python2}", env.python2().unwrap_or("2"))
+        .replace("{python}", env.python_version.as_deref().unwrap_or("?"))
+        .replace("{registry}", &format_size(env.cargo_registry))
+        .replace("{target}", &format_size(env.target_dir))
+        .replace("{dotfile_size}", &format_size(dotfile_size))
+        .replace("{dotfile}", &dotfile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(python: Option<&str>, python2: Option<&str>) -> DevEnv {
+        DevEnv {
+            shell: None,
+            editor: None,
+            rust_toolchains: Vec::new(),
+            node_version: None,
+            python_version: python.map(str::to_string),
+            python2_version: python2.map(str::to_string),
+            cargo_registry: 0,
+            target_dir: 0,
+            largest_dotfile: None,
+        }
+    }
+
+    #[test]
+    fn python2_next_to_python3_counts() {
+        assert_eq!(env(Some("3.12.3"), Some("2.7.18")).python2(), Some("2.7.18"));
+        assert_eq!(env(Some("2.7.18"), None).python2(), Some("2.7.18"));
+        assert_eq!(env(Some("3.12.3"), None).python2(), None);
+        let pools = roast_pools(&env(Some("3.12.3"), Some("2.7.18")), &DevConfig::default());
+        assert!(pools.iter().any(|(name, _)| *name == "dev.python2"));
+        assert_eq!(fill("{python2} and {python}", &env(Some("3.12.3"), Some("2.7.18"))), "2.7.18 and 3.12.3");
+    }
+
+    #[test]
+    fn size_walks_stop_at_their_limits() {
+        let dir = std::env::temp_dir().join(format!("rust_roast-size-{}", std::process::id()));
+        let deep = dir.join("a/b");
+        fs::create_dir_all(&deep).unwrap();
+        fs::write(dir.join("top"), [0; 100]).unwrap();
+        fs::write(deep.join("bottom"), [0; 10]).unwrap();
+        let later = Some(Instant::now() + Duration::from_secs(60));
+        assert_eq!(walk_size(&dir, SIZE_DEPTH, later), 110);
+        // One level reads `dir` itself, and nothing below it
+        assert_eq!(walk_size(&dir, 1, later), 100);
+        assert_eq!(walk_size(&dir, 0, later), 0);
+        assert_eq!(walk_size(&dir, SIZE_DEPTH, Some(Instant::now())), 0);
+        assert_eq!(dir_size(&dir), 110);
+        assert_eq!(full_size(&dir), 110);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,384 @@
+use crate::uptime::parse_utc;
+use crate::roast::Roast;
+use std::collections::HashMap;
+use std::fs;
+
+const YEAR: u64 = 365 * 24 * 60 * 60;
+
+// The fields of /etc/os-release that the roasts care about
+#[derive(Default)]
+pub struct OsRelease {
+    pub id: String,
+    pub id_like: Vec<String>,
+    pub name: String,
+    pub pretty_name: String,
+    pub version: String,
+    pub version_id: String,
+    pub version_codename: String,
+}
+
+impl OsRelease {
+    // ID followed by the ID_LIKE parents, most specific first
+    pub fn lineage(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.id.as_str()).chain(self.id_like.iter().map(|s| s.as_str()))
+    }
+
+    pub fn is_rolling(&self) -> bool {
+        self.lineage().any(|id| ROLLING_IDS.contains(&id))
+            || self.version_id.is_empty()
+            || matches!(self.version_codename.as_str(), "sid" | "rawhide" | "tumbleweed")
+    }
+
+    pub fn is_ubuntu_lts(&self) -> bool {
+        self.id == "ubuntu" && self.version.contains("LTS")
+    }
+}
+
+// Reads /etc/os-release, falling back to /usr/lib/os-release
+pub fn read_os_release() -> Option<OsRelease> {
+    let text = fs::read_to_string("/etc/os-release")
+        .or_else(|_| fs::read_to_string("/usr/lib/os-release"))
+        .ok()?;
+    Some(parse_os_release(&text))
+}
+
+fn parse_os_release(text: &str) -> OsRelease {
+    let fields: HashMap<&str, String> = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), unquote(value.trim())))
+        .collect();
+    let get = |key: &str| fields.get(key).cloned().unwrap_or_default();
+
+    OsRelease {
+        id: get("ID").to_lowercase(),
+        id_like: get("ID_LIKE").split_whitespace().map(|s| s.to_lowercase()).collect(),
+        name: get("NAME"),
+        pretty_name: get("PRETTY_NAME"),
+        version: get("VERSION"),
+        version_id: get("VERSION_ID"),
+        version_codename: get("VERSION_CODENAME"),
+    }
+}
+
+// Strips shell-style quoting from an os-release value
+fn unquote(value: &str) -> String {
+    let inner = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value);
+    inner.replace("\\\"", "\"").replace("\\$", "$").replace("\\\\", "\\")
+}
+
+const ROLLING_IDS: &[&str] = &["arch", "manjaro", "endeavouros", "gentoo", "opensuse-tumbleweed", "void", "artix", "garuda", "cachyos", "solus"];
+
+// (ID, VERSION_ID, release date, end of life). Dates are when standard support ended.
+const RELEASES: &[(&str, &str, &str, &str)] = &[
+    ("ubuntu", "14.04", "2014-04-17", "2019-04-30"),
+    ("ubuntu", "16.04", "2016-04-21", "2021-04-30"),
+    ("ubuntu", "18.04", "2018-04-26", "2023-05-31"),
+    ("ubuntu", "20.04", "2020-04-23", "2025-05-31"),
+    ("ubuntu", "22.04", "2022-04-21", "2027-06-01"),
+    ("ubuntu", "23.04", "2023-04-20", "2024-01-25"),
+    ("ubuntu", "23.10", "2023-10-12", "2024-07-11"),
+    ("ubuntu", "24.04", "2024-04-25", "2029-05-31"),
+    ("ubuntu", "24.10", "2024-10-10", "2025-07-10"),
+    ("ubuntu", "25.04", "2025-04-17", "2026-01-15"),
+    ("ubuntu", "25.10", "2025-10-09", "2026-07-09"),
+    ("ubuntu", "26.04", "2026-04-23", "2031-05-31"),
+    ("debian", "8", "2015-04-26", "2018-06-17"),
+    ("debian", "9", "2017-06-17", "2020-07-18"),
+    ("debian", "10", "2019-07-06", "2022-09-10"),
+    ("debian", "11", "2021-08-14", "2024-08-14"),
+    ("debian", "12", "2023-06-10", "2026-06-10"),
+    ("debian", "13", "2025-08-09", "2028-08-09"),
+    ("fedora", "37", "2022-11-15", "2023-12-05"),
+    ("fedora", "38", "2023-04-18", "2024-05-21"),
+    ("fedora", "39", "2023-11-07", "2024-11-26"),
+    ("fedora", "40", "2024-04-23", "2025-05-13"),
+    ("fedora", "41", "2024-10-29", "2025-11-19"),
+    ("fedora", "42", "2025-04-15", "2026-05-13"),
+    ("fedora", "43", "2025-10-28", "2026-12-09"),
+    ("centos", "7", "2014-07-07", "2024-06-30"),
+    ("centos", "8", "2019-09-24", "2021-12-31"),
+    ("linuxmint", "19", "2018-06-29", "2023-04-30"),
+    ("linuxmint", "20", "2020-06-27", "2025-04-30"),
+    ("linuxmint", "21", "2022-07-31", "2027-04-30"),
+    ("linuxmint", "22", "2024-07-25", "2029-04-30"),
+    ("opensuse-leap", "15.4", "2022-06-08", "2023-12-07"),
+    ("opensuse-leap", "15.5", "2023-06-07", "2024-12-31"),
+    ("opensuse-leap", "15.6", "2024-06-12", "2025-12-31"),
+    ("alpine", "3.17", "2022-11-22", "2024-11-22"),
+    ("alpine", "3.18", "2023-05-09", "2025-05-09"),
+    ("alpine", "3.19", "2023-12-07", "2025-11-01"),
+    ("alpine", "3.20", "2024-05-22", "2026-04-01"),
+];
+
+// Release dates of kernel series, used to estimate how old a running kernel is
+const KERNELS: &[(u32, u32, &str)] = &[
+    (2, 6, "2003-12-17"),
+    (3, 0, "2011-07-21"),
+    (3, 10, "2013-06-30"),
+    (4, 0, "2015-04-12"),
+    (4, 4, "2016-01-10"),
+    (4, 9, "2016-12-11"),
+    (4, 14, "2017-11-12"),
+    (4, 19, "2018-10-22"),
+    (5, 4, "2019-11-24"),
+    (5, 10, "2020-12-13"),
+    (5, 15, "2021-10-31"),
+    (6, 1, "2022-12-11"),
+    (6, 6, "2023-10-30"),
+    (6, 8, "2024-03-10"),
+    (6, 12, "2024-11-17"),
+    (6, 14, "2025-03-24"),
+    (6, 16, "2025-07-27"),
+    (6, 18, "2025-11-30"),
+];
+
+fn date(day: &str) -> u64 {
+    parse_utc(day, "00:00:00").unwrap_or(0)
+}
+
+// Support status of the installed release, if it's in the table
+pub struct Support {
+    pub eol: bool,
+    // A newer release of the same distro is already out (e.g. Debian oldstable)
+    pub superseded: bool,
+}
+
+pub fn support(os: &OsRelease, now: u64) -> Option<Support> {
+    let (_, _, released, eol) = RELEASES
+        .iter()
+        .find(|(id, version, _, _)| *id == os.id && *version == os.version_id)?;
+    let superseded = RELEASES
+        .iter()
+        .any(|(id, _, other, _)| *id == os.id && date(other) > date(released) && date(other) <= now);
+    Some(Support {
+        eol: date(eol) <= now,
+        superseded,
+    })
+}
+
+// Age of the running kernel's series in seconds, from a "6.1.0-18-amd64" style version
+pub fn kernel_age(kernel_version: &str, now: u64) -> Option<u64> {
+    let mut parts = kernel_version.split(|c: char| !c.is_ascii_digit());
+    let major: u32 = parts.next()?.parse().ok()?;
+    let minor: u32 = parts.next()?.parse().ok()?;
+    let (_, _, released) = KERNELS
+        .iter()
+        .rev()
+        .find(|(kmajor, kminor, _)| (*kmajor, *kminor) <= (major, minor))?;
+    Some(now.saturating_sub(date(released)))
+}
+
+pub fn kernel_age_years(age: u64) -> u64 {
+    age / YEAR
+}
+
+// Picks the distro roast pool (name and built-in lines): by ID, then ID_LIKE,
+// then rolling vs fixed release
+pub fn roast_pool(os: &OsRelease, support: Option<&Support>) -> (&'static str, &'static [Roast]) {
+    if let Some(support) = support {
+        if support.eol {
+            return ("os.eol", EOL_ROASTS);
+        }
+        if support.superseded && os.id == "debian" {
+            return ("os.debian_oldstable", DEBIAN_OLDSTABLE_ROASTS);
+        }
+    }
+    if os.is_ubuntu_lts() {
+        return ("os.ubuntu_lts", UBUNTU_LTS_ROASTS);
+    }
+    for id in os.lineage() {
+        let pool = match id {
+            "arch" => ("os.arch", ARCH_ROASTS),
+            "ubuntu" => ("os.ubuntu", UBUNTU_ROASTS),
+            "debian" => ("os.debian", DEBIAN_ROASTS),
+            "gentoo" => ("os.gentoo", GENTOO_ROASTS),
+            "nixos" => ("os.nixos", NIXOS_ROASTS),
+            "fedora" | "rhel" | "centos" => ("os.fedora", FEDORA_ROASTS),
+            _ => continue,
+        };
+        return pool;
+    }
+    if os.is_rolling() {
+        ("os.rolling", ROLLING_ROASTS)
+    } else {
+        ("os.fixed", FIXED_ROASTS)
+    }
+}
+
+const EOL_ROASTS: &[Roast] = &[
+    Roast::savage("{distro} reached end of life. Your OS is legally a ghost."),
+    Roast::standard("Running {distro}? Security updates stopped, but the vulnerabilities didn't."),
+    Roast::standard("{distro} is end-of-life. So is any hope of a clean security audit."),
+    Roast::gentle("Your distro's support ended and you just... kept going. Respect the commitment, fear the exploits."),
+];
+
+const DEBIAN_OLDSTABLE_ROASTS: &[Roast] = &[
+    Roast::standard("Debian oldstable. For when regular stable feels too reckless."),
+    Roast::gentle("Your packages are so old they qualify for a pension."),
+    Roast::standard("{distro}: a newer Debian is out, but you're waiting for it to be battle-tested. For a few more years."),
+];
+
+const UBUNTU_LTS_ROASTS: &[Roast] = &[
+    Roast::standard("Ubuntu LTS. You like your software like your bread: a little stale but safe."),
+    Roast::standard("{distro}. Long Term Support, short term excitement."),
+    Roast::gentle("LTS: Long Time, Still-waiting-for-new-packages."),
+];
+
+const UBUNTU_ROASTS: &[Roast] = &[
+    Roast::savage("Ubuntu: Linux with training wheels, and snaps bolted onto them."),
+    Roast::gentle("You run {distro}. I bet you still pronounce it 'you-BUN-too' in your head."),
+    Roast::standard("Ubuntu, for people who want to use Linux without having to talk about it."),
+];
+
+const DEBIAN_ROASTS: &[Roast] = &[
+    Roast::gentle("Debian: where 'new' means two years old and 'stable' means unchanging."),
+    Roast::savage("You run {distro}. Your kernel is older than some of your coworkers' careers."),
+    Roast::standard("Debian user spotted. Let me guess, you still think systemd was a mistake."),
+];
+
+const ARCH_ROASTS: &[Roast] = &[
+    Roast::gentle("I use Arch, btw. We know. You told us. Again."),
+    Roast::standard("Arch user, huh? How many hours did it take to get Wi-Fi working this time?"),
+    Roast::savage("Your system is so bleeding edge, it's mostly bleeding."),
+    Roast::standard("A pacman -Syu away from a weekend of fixing things."),
+];
+
+const GENTOO_ROASTS: &[Roast] = &[
+    Roast::standard("Gentoo: because your CPU wasn't busy enough compiling your ego."),
+    Roast::gentle("Still compiling Firefox? See you next week."),
+    Roast::savage("You optimized your USE flags for a 2% speedup and lost 200 hours of your life.").personal(),
+];
+
+const NIXOS_ROASTS: &[Roast] = &[
+    Roast::standard("NixOS: a reproducible system for reproducibly explaining to people what NixOS is."),
+    Roast::savage("Your OS config is a functional program. Your social life is not.").personal(),
+    Roast::gentle("You can roll back any change on NixOS, except the decision to install NixOS."),
+];
+
+const FEDORA_ROASTS: &[Roast] = &[
+    Roast::savage("Fedora: the beta test Red Hat doesn't pay you for."),
+    Roast::gentle("{distro}. Bleeding edge, but with a corporate badge."),
+    Roast::standard("Fedora user, huh? Upgrading every six months is a lifestyle, not a hobby."),
+];
+
+const ROLLING_ROASTS: &[Roast] = &[
+    Roast::gentle("A rolling release. Rolling downhill, mostly."),
+    Roast::standard("Running {distro}? Every update is a coin flip with your bootloader."),
+    Roast::standard("You chose a rolling release so you'd never be bored. Congrats, you're never not fixing something."),
+];
+
+const FIXED_ROASTS: &[Roast] = &[
+    Roast::gentle("{distro}. The OS equivalent of ordering the house salad."),
+    Roast::standard("Your distro is so fixed-release, it fixed itself in 2019 and never moved."),
+    Roast::savage("{distro}: stable, predictable, and about as exciting as a beige cardigan."),
+];
+
+pub const OLD_KERNEL_ROASTS: &[Roast] = &[
+    Roast::gentle("Your kernel is {kernel_age} years old. It's been patched more times than a pair of hiking socks."),
+    Roast::standard("A kernel from {kernel_age} years ago? Half of today's hardware hadn't been invented yet."),
+    Roast::savage("Linux {kernel}? Linus has shipped dozens of kernels since. You've shipped zero upgrades.").personal(),
+    Roast::standard("Your kernel is old enough to remember when people were excited about systemd."),
+];
+
+// The pools above, by name
+pub const POOLS: &[(&str, &[Roast])] = &[
+    ("os.eol", EOL_ROASTS),
+    ("os.debian_oldstable", DEBIAN_OLDSTABLE_ROASTS),
+    ("os.ubuntu_lts", UBUNTU_LTS_ROASTS),
+    ("os.ubuntu", UBUNTU_ROASTS),
+    ("os.debian", DEBIAN_ROASTS),
+    ("os.arch", ARCH_ROASTS),
+    ("os.gentoo", GENTOO_ROASTS),
+    ("os.nixos", NIXOS_ROASTS),
+    ("os.fedora", FEDORA_ROASTS),
+    ("os.rolling", ROLLING_ROASTS),
+    ("os.fixed", FIXED_ROASTS),
+    ("os.kernel", OLD_KERNEL_ROASTS),
+];
+
+// Fills the {distro}, {kernel} and {kernel_age} placeholders
+pub fn fill(roast: &str, os: &OsRelease, kernel: &str, kernel_age: Option<u64>) -> String {
+    let distro = if os.pretty_name.is_empty() { &os.name } else { &os.pretty_name };
+    roast
+        .replace("{distro}", distro)
+        .replace("{kernel}", kernel)
+        .replace("{kernel_age}", &kernel_age.map(kernel_age_years).unwrap_or(0).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEBIAN_11: &str = r#"# Shipped by base-files
+PRETTY_NAME="Debian GNU/Linux 11 (bullseye)"
+NAME="Debian GNU/Linux"
+VERSION_ID="11"
+VERSION="11 (bullseye)"
+VERSION_CODENAME=bullseye
+ID=debian
+HOME_URL="https://www.debian.org/"
+"#;
+
+    const MINT: &str = "NAME='Linux Mint'\nVERSION=\"21.3 (Virginia)\"\nID=LinuxMint\nID_LIKE=\"ubuntu debian\"\nVERSION_ID=\"21\"\nPRETTY_NAME=\"Linux \\\"Mint\\\" 21.3\"\n";
+
+    const ARCH: &str = "NAME=\"Arch Linux\"\nPRETTY_NAME=\"Arch Linux\"\nID=arch\nBUILD_ID=rolling\n";
+
+    #[test]
+    fn parses_os_release() {
+        let os = parse_os_release(DEBIAN_11);
+        assert_eq!(os.id, "debian");
+        assert_eq!(os.name, "Debian GNU/Linux");
+        assert_eq!(os.pretty_name, "Debian GNU/Linux 11 (bullseye)");
+        assert_eq!(os.version_id, "11");
+        assert_eq!(os.version_codename, "bullseye");
+        assert!(os.id_like.is_empty());
+        assert!(!os.is_rolling());
+
+        // Single quotes, escaped quotes, a mixed-case ID and ID_LIKE parents
+        let mint = parse_os_release(MINT);
+        assert_eq!(mint.name, "Linux Mint");
+        assert_eq!(mint.pretty_name, "Linux \"Mint\" 21.3");
+        assert_eq!(mint.lineage().collect::<Vec<_>>(), ["linuxmint", "ubuntu", "debian"]);
+
+        // No VERSION_ID means a rolling release
+        let arch = parse_os_release(ARCH);
+        assert!(arch.is_rolling());
+        assert_eq!(roast_pool(&arch, None).0, "os.arch");
+    }
+
+    #[test]
+    fn support_follows_the_release_table() {
+        let os = parse_os_release(DEBIAN_11);
+        // Before Debian 12 came out
+        let early = support(&os, date("2022-01-01")).unwrap();
+        assert!(!early.eol && !early.superseded);
+        // Debian 12 is out, 11 is still supported: oldstable
+        let oldstable = support(&os, date("2024-01-01")).unwrap();
+        assert!(!oldstable.eol && oldstable.superseded);
+        assert_eq!(roast_pool(&os, Some(&oldstable)).0, "os.debian_oldstable");
+        let eol = support(&os, date("2024-08-14")).unwrap();
+        assert!(eol.eol);
+        assert_eq!(roast_pool(&os, Some(&eol)).0, "os.eol");
+        // Releases that aren't in the table have no known support
+        assert!(support(&parse_os_release(ARCH), date("2024-01-01")).is_none());
+    }
+
+    #[test]
+    fn kernel_age_uses_the_series() {
+        let now = date("2025-12-11");
+        // 6.1 came out on 2022-12-11, three years before
+        assert_eq!(kernel_age("6.1.0-18-amd64", now).map(kernel_age_years), Some(3));
+        // Point releases between table entries count from the series before them
+        assert_eq!(kernel_age("6.3.7-arch1-1", now), kernel_age("6.1.0", now));
+        assert_eq!(kernel_age("4.19.0", date("2018-10-22")), Some(0));
+        // Older than the table, or not a version at all
+        assert_eq!(kernel_age("2.4.37", now), None);
+        assert_eq!(kernel_age("unknown", now), None);
+    }
+}
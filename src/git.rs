@@ -0,0 +1,335 @@
+use crate::config::GitConfig;
+use crate::roast::Roast;
+use crate::snapshot::Snapshot;
+use git2::{BranchType, Commit, ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Subjects that say nothing at all about the change
+const LAZY_SUBJECTS: &[&str] = &[
+    "wip", "fix", "fixes", "fixed", "update", "updates", "baseline", "changes", "stuff", "asdf", "test", "tmp", "temp",
+    "minor", "misc", "cleanup", "oops", "done", "commit", "save", "more", "again", ".", "...",
+];
+
+pub struct RepoStats {
+    pub root: PathBuf,
+    pub commits: usize,
+    // Commits whose subject is in LAZY_SUBJECTS or shorter than 8 characters
+    pub lazy_commits: usize,
+    // The most common lazy subject and how often it was used
+    pub favourite_lazy: Option<(String, usize)>,
+    // Commits per hour of day, in the author's timezone
+    pub hours: [usize; 24],
+    pub branches: usize,
+    // Largest blob anywhere in history: (path, bytes)
+    pub largest_blob: Option<(String, u64)>,
+    // "forced-update" entries in remote-tracking reflogs
+    pub forced_updates: usize,
+    // Resets and rebases in the HEAD reflog
+    pub rewrites: usize,
+}
+
+impl RepoStats {
+    pub fn lazy_share(&self) -> f64 {
+        self.lazy_commits as f64 / self.commits.max(1) as f64
+    }
+
+    // Share of commits made between midnight and 5am
+    pub fn night_share(&self) -> f64 {
+        self.hours[..5].iter().sum::<usize>() as f64 / self.commits.max(1) as f64
+    }
+
+    // One bar per hour, e.g. "▁▁▁▁▁▁▂▄▆█▇▅▃▂▁▁▁▁▁▁▁▁▁▁"
+    pub fn histogram(&self) -> String {
+        const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        let max = self.hours.iter().copied().max().unwrap_or(0).max(1);
+        self.hours.iter().map(|&count| BARS[count * 7 / max]).collect()
+    }
+}
+
+pub fn inspect(dir: &Path) -> Result<RepoStats, String> {
+    let repo = Repository::discover(dir).map_err(|e| format!("{}: {}", dir.display(), e.message()))?;
+    let git_dir = repo.path().to_path_buf();
+    // A bare repository has no working tree, so it stands for itself. The
+    // components drop the trailing slash libgit2 leaves on.
+    let root = repo.workdir().unwrap_or(&git_dir).components().collect();
+
+    let mut hours = [0; 24];
+    let mut commits = 0;
+    let mut lazy_counts: HashMap<String, usize> = HashMap::new();
+    for commit in history(&repo, Walk::Head) {
+        commits += 1;
+        // The author's local time: seconds since the epoch plus their UTC offset
+        let when = commit.author().when();
+        let local = when.seconds() + i64::from(when.offset_minutes()) * 60;
+        hours[(local.rem_euclid(86_400) / 3_600) as usize] += 1;
+        let subject = String::from_utf8_lossy(commit.summary_bytes().unwrap_or_default()).trim().to_lowercase();
+        if is_lazy(&subject) {
+            *lazy_counts.entry(subject).or_default() += 1;
+        }
+    }
+    let lazy_commits = lazy_counts.values().sum();
+    let favourite_lazy = lazy_counts.into_iter().max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)));
+
+    let branches = repo.branches(Some(BranchType::Local)).map_or(0, |branches| branches.count());
+
+    Ok(RepoStats {
+        root,
+        commits,
+        lazy_commits,
+        favourite_lazy,
+        hours,
+        branches,
+        largest_blob: largest_blob(&repo),
+        forced_updates: reflog_matches(&git_dir.join("logs/refs/remotes"), "forced-update"),
+        rewrites: reflog_matches(&git_dir.join("logs/HEAD"), "reset: moving to")
+            + reflog_matches(&git_dir.join("logs/HEAD"), "rebase (finish)"),
+    })
+}
+
+// A subject in LAZY_SUBJECTS, or one too short to say anything (counted in
+// characters, so "修正" is as short as it looks)
+fn is_lazy(subject: &str) -> bool {
+    subject.chars().count() < 8 || LAZY_SUBJECTS.contains(&subject)
+}
+
+enum Walk {
+    // What `git log` shows
+    Head,
+    // Everything reachable from any ref
+    AllRefs,
+}
+
+// The commits of a walk, newest first. An empty repository has no HEAD and
+// so no commits.
+fn history(repo: &Repository, walk: Walk) -> Vec<Commit<'_>> {
+    let Ok(mut revwalk) = repo.revwalk() else { return Vec::new() };
+    let pushed = match walk {
+        Walk::Head => revwalk.push_head(),
+        Walk::AllRefs => revwalk.push_glob("*"),
+    };
+    if pushed.is_err() {
+        return Vec::new();
+    }
+    revwalk.filter_map(|id| repo.find_commit(id.ok()?).ok()).collect()
+}
+
+// Largest blob reachable from any ref, with the first path it was seen at.
+// Trees already walked are skipped, so each one is only read once.
+fn largest_blob(repo: &Repository) -> Option<(String, u64)> {
+    let odb = repo.odb().ok()?;
+    let mut seen: HashSet<Oid> = HashSet::new();
+    let mut largest: Option<(String, u64)> = None;
+    for commit in history(repo, Walk::AllRefs) {
+        let Ok(tree) = commit.tree() else { continue };
+        if !seen.insert(tree.id()) {
+            continue;
+        }
+        let _ = tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+            if !seen.insert(entry.id()) {
+                return TreeWalkResult::Skip;
+            }
+            if entry.kind() == Some(ObjectType::Blob) {
+                if let Ok((size, _)) = odb.read_header(entry.id()) {
+                    let size = size as u64;
+                    if largest.as_ref().is_none_or(|(_, largest)| size > *largest) {
+                        largest = Some((format!("{}{}", dir, String::from_utf8_lossy(entry.name_bytes())), size));
+                    }
+                }
+            }
+            TreeWalkResult::Ok
+        });
+    }
+    largest
+}
+
+// Counts reflog lines containing `needle` in a reflog file, or in every reflog below a directory
+fn reflog_matches(path: &Path, needle: &str) -> usize {
+    if path.is_file() {
+        return fs::read_to_string(path).map_or(0, |text| text.lines().filter(|l| l.contains(needle)).count());
+    }
+    fs::read_dir(path)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| reflog_matches(&e.path(), needle)).sum())
+        .unwrap_or(0)
+}
+
+// Names of the pools that apply to this repository, each with its built-in lines
+pub fn roast_pools(stats: &RepoStats, config: &GitConfig) -> Vec<(&'static str, &'static [Roast])> {
+    let mut pools: Vec<(&'static str, &'static [Roast])> = Vec::new();
+    if stats.commits == 0 {
+        pools.push(("git.empty", EMPTY_ROASTS));
+        return pools;
+    }
+    if stats.lazy_share() >= config.lazy_share {
+        pools.push(("git.messages", MESSAGE_ROASTS));
+    }
+    if stats.night_share() >= config.night_share {
+        pools.push(("git.night", NIGHT_ROASTS));
+    }
+    if stats.largest_blob.as_ref().is_some_and(|(_, size)| *size >= config.big_blob_mb * 1024 * 1024) {
+        pools.push(("git.blob", BLOB_ROASTS));
+    }
+    if stats.branches >= config.branches {
+        pools.push(("git.branches", BRANCH_ROASTS));
+    }
+    if stats.forced_updates > 0 || stats.rewrites >= config.rewrites {
+        pools.push(("git.force", FORCE_ROASTS));
+    }
+    if pools.is_empty() {
+        pools.push(("git.clean", CLEAN_ROASTS));
+    }
+    pools
+}
+
+const EMPTY_ROASTS: &[Roast] = &[
+    Roast::savage("A git repository with zero commits. Ambitious plans, no follow-through.").personal(),
+    Roast::standard("No commits yet. The hardest part of any project is starting, and you haven't."),
+    Roast::gentle("An empty repository. Clean history, at least."),
+];
+
+const MESSAGE_ROASTS: &[Roast] = &[
+    Roast::gentle("{lazy_share} of your commits say things like \"{lazy}\". Future you is going to love `git blame`."),
+    Roast::gentle("\"{lazy}\" appears {lazy_count} times in your history. That's not a commit message, that's a shrug."),
+    Roast::savage("Your commit log reads like a ransom note: short, vague and threatening."),
+    Roast::standard("{lazy_commits} commits with messages shorter than a tweet's first word. Bold documentation strategy."),
+];
+
+const NIGHT_ROASTS: &[Roast] = &[
+    Roast::standard("{night_share} of your commits land between midnight and 5am. Your code has the bugs to prove it."),
+    Roast::gentle("Peak commit hour: {peak_hour}:00. Sleep is a feature you haven't shipped."),
+    Roast::savage("Most of your commits happen after midnight. That's not a schedule, it's a cry for help.").personal().when("git.night_share > 0.5"),
+    Roast::standard("Judging by your commit times, you're either a vampire or on call. Possibly both.").personal(),
+];
+
+const BLOB_ROASTS: &[Roast] = &[
+    Roast::gentle("{blob} is {blob_size} and lives in your history forever. Git LFS sends its regards."),
+    Roast::standard("Someone committed a {blob_size} {blob}. Every clone pays for that mistake."),
+    Roast::savage("{blob_size} blob in git. This isn't a repository, it's a storage unit."),
+];
+
+const BRANCH_ROASTS: &[Roast] = &[
+    Roast::gentle("{branches} local branches. You don't have a branching strategy, you have a branching hobby."),
+    Roast::standard("{branches} branches and most of them are called some variant of 'test'. Delete some, it's fine."),
+];
+
+const FORCE_ROASTS: &[Roast] = &[
+    Roast::savage("Your reflog shows {forced_updates} forced updates. Somewhere a teammate lost a day of work."),
+    Roast::gentle("{rewrites} resets and rebases in the reflog. History is written by the victors, and rewritten by you."),
+    Roast::savage("`git push --force` is not a personality trait.").personal(),
+];
+
+const CLEAN_ROASTS: &[Roast] = &[
+    Roast::gentle("Descriptive commits, sane hours, no giant blobs. Are you sure this is your repo?"),
+    Roast::standard("Nothing to roast in this history. Either you squash everything or you're a robot."),
+];
+
+// The pools above, by name
+pub const POOLS: &[(&str, &[Roast])] = &[
+    ("git.empty", EMPTY_ROASTS),
+    ("git.messages", MESSAGE_ROASTS),
+    ("git.night", NIGHT_ROASTS),
+    ("git.blob", BLOB_ROASTS),
+    ("git.branches", BRANCH_ROASTS),
+    ("git.force", FORCE_ROASTS),
+    ("git.clean", CLEAN_ROASTS),
+];
+
+// Facts for roast conditions, e.g. "git.night_share > 0.5 && git.branches > 50"
+pub fn snapshot(stats: &RepoStats) -> Snapshot {
+    let mut snapshot = Snapshot::default();
+    snapshot.set("git.commits", stats.commits);
+    snapshot.set("git.lazy_commits", stats.lazy_commits);
+    snapshot.set("git.lazy_share", stats.lazy_share());
+    snapshot.set("git.night_share", stats.night_share());
+    snapshot.set("git.branches", stats.branches);
+    snapshot.set("git.forced_updates", stats.forced_updates);
+    snapshot.set("git.rewrites", stats.rewrites);
+    if let Some((_, size)) = &stats.largest_blob {
+        snapshot.set("git.largest_blob_mb", *size as f64 / 1024.0 / 1024.0);
+    }
+    snapshot
+}
+
+// Fills the git placeholders ({lazy}, {night_share}, {blob}, ...)
+pub fn fill(roast: &str, stats: &RepoStats) -> String {
+    let (lazy, lazy_count) = stats.favourite_lazy.clone().unwrap_or_else(|| ("wip".to_string(), 0));
+    let (blob, blob_size) = stats.largest_blob.clone().unwrap_or_default();
+    let peak_hour = (0..24).max_by_key(|&h| stats.hours[h]).unwrap_or(0);
+    roast
+        .replace("{lazy_share}", &format!("{:.0}%", stats.lazy_share() * 100.0))
+        .replace("{lazy_commits}", &stats.lazy_commits.to_string())
+        .replace("{lazy_count}", &lazy_count.to_string())
+        .replace("{lazy}", &lazy)
+        .replace("{night_share}", &format!("{:.0}%", stats.night_share() * 100.0))
+        .replace("{peak_hour}", &format!("{:02}", peak_hour))
+        .replace("{blob_size}", &crate::devenv::format_size(blob_size))
+        .replace("{blob}", &blob)
+        .replace("{branches}", &stats.branches.to_string())
+        .replace("{forced_updates}", &stats.forced_updates.to_string())
+        .replace("{rewrites}", &stats.rewrites.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Signature, Time};
+
+    #[test]
+    fn short_subjects_count_characters() {
+        assert!(is_lazy("wip"));
+        assert!(is_lazy("cleanup"));
+        assert!(is_lazy("修正"));
+        // Eight characters, but 24 bytes
+        assert!(!is_lazy("バグを直しました"));
+        assert!(!is_lazy("add a parser"));
+    }
+
+    // A throwaway repository with a commit per (subject, hour in UTC+2)
+    fn fixture(name: &str, commits: &[(&str, i64)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rust_roast-git-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let repo = Repository::init(&dir).unwrap();
+        let mut parent: Option<Oid> = None;
+        for (index, (subject, hour)) in commits.iter().enumerate() {
+            let mut index_file = repo.index().unwrap();
+            let content = "x".repeat((index + 1) * 1000);
+            fs::write(dir.join(format!("file{}.txt", index)), content).unwrap();
+            index_file.add_path(Path::new(&format!("file{}.txt", index))).unwrap();
+            index_file.write().unwrap();
+            let tree = repo.find_tree(index_file.write_tree().unwrap()).unwrap();
+            // 2026-01-01 at `hour` local time, two hours ahead of UTC
+            let time = Time::new(1_767_225_600 + (hour - 2) * 3_600, 120);
+            let signature = Signature::new("Test", "test@example.com", &time).unwrap();
+            let parents: Vec<Commit> = parent.iter().map(|id| repo.find_commit(*id).unwrap()).collect();
+            let parents: Vec<&Commit> = parents.iter().collect();
+            parent = Some(repo.commit(Some("HEAD"), &signature, &signature, subject, &tree, &parents).unwrap());
+        }
+        dir
+    }
+
+    #[test]
+    fn inspects_a_repository() {
+        let dir = fixture("inspect", &[("wip", 3), ("Add the parser for conditions", 3), ("wip", 14)]);
+        let stats = inspect(&dir).unwrap();
+        assert_eq!(stats.commits, 3);
+        assert_eq!(stats.lazy_commits, 2);
+        assert_eq!(stats.favourite_lazy, Some(("wip".to_string(), 2)));
+        assert_eq!(stats.hours[3], 2);
+        assert_eq!(stats.hours[14], 1);
+        assert_eq!(stats.branches, 1);
+        assert_eq!(stats.largest_blob, Some(("file2.txt".to_string(), 3000)));
+        assert!(!stats.root.to_string_lossy().ends_with('/'));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn empty_repository_has_no_commits() {
+        let dir = fixture("empty", &[]);
+        let stats = inspect(&dir).unwrap();
+        assert_eq!(stats.commits, 0);
+        assert_eq!(stats.largest_blob, None);
+        let _ = fs::remove_dir_all(&dir);
+        assert!(inspect(&std::env::temp_dir().join("rust_roast-git-nowhere")).is_err());
+    }
+}
@@ -0,0 +1,222 @@
+use crate::pack::RoastPack;
+use crate::roast::{self, Roast, Severity, Tone};
+use crate::snapshot::Snapshot;
+use rand::Rng;
+use std::collections::HashMap;
+
+// Deeper nesting than this is treated as a loop in the grammar
+const MAX_DEPTH: usize = 12;
+
+// Modifiers that can follow a symbol name: #noun.a#, #verb.capitalize#
+const MODIFIERS: &[&str] = &["capitalize", "a", "s"];
+
+// Tracery-style grammars from the packs' [grammar] tables. Each symbol maps to
+// alternatives, and "#symbol#" inside an alternative expands to one of that
+// symbol's alternatives; a snapshot fact like "#cpu.cores#" expands to its
+// value. A symbol named after a pool ("cpu.low") or a category ("cpu") is where
+// a roast for that pool starts with --backend grammar.
+#[derive(Debug, Default)]
+pub struct Grammar {
+    english: HashMap<String, Vec<Roast>>,
+    // Symbols from translation packs, preferred over the English ones
+    localized: HashMap<String, Vec<Roast>>,
+}
+
+impl Grammar {
+    pub fn from_packs(packs: &[RoastPack]) -> Grammar {
+        let mut grammar = Grammar::default();
+        for pack in packs {
+            let symbols = if pack.lang.is_some() { &mut grammar.localized } else { &mut grammar.english };
+            for (symbol, rules) in &pack.grammar {
+                symbols.entry(symbol.clone()).or_default().extend(rules.iter().map(|rule| rule.to_roast()));
+            }
+        }
+        grammar
+    }
+
+    fn rules(&self, symbol: &str) -> Option<&[Roast]> {
+        self.localized.get(symbol).or_else(|| self.english.get(symbol)).map(Vec::as_slice)
+    }
+
+    pub fn has(&self, symbol: &str) -> bool {
+        self.rules(symbol).is_some()
+    }
+
+    // Expands `symbol` into a roast. The rule chosen for the symbol itself
+    // follows the tone like any pool line; the symbols inside it only follow
+    // conditions, weights and workplace-safe, so a template decides how harsh
+    // its pieces are by which symbols it uses. None if some symbol has no
+    // rule that fits.
+    pub fn expand<R: Rng>(&self, symbol: &str, tone: &Tone, snapshot: &Snapshot, rng: &mut R) -> Option<String> {
+        let rule = roast::pick(self.rules(symbol)?, tone, snapshot, rng)?;
+        let inner = Tone { severity: Severity::Savage, ..*tone };
+        self.expand_text(&rule.text, &inner, snapshot, rng, 0)
+    }
+
+    fn expand_text<R: Rng>(&self, text: &str, tone: &Tone, snapshot: &Snapshot, rng: &mut R, depth: usize) -> Option<String> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let mut out = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find('#') {
+            let end = start + 1 + rest[start + 1..].find('#')?;
+            out.push_str(&rest[..start]);
+            let (symbol, modifiers) = split_modifiers(&rest[start + 1..end]);
+            let mut expansion = match self.rules(symbol) {
+                Some(rules) => {
+                    let rule = roast::pick(rules, tone, snapshot, rng)?;
+                    self.expand_text(&rule.text, tone, snapshot, rng, depth + 1)?
+                }
+                None => snapshot.get(symbol)?.to_string(),
+            };
+            for modifier in modifiers {
+                expansion = apply(modifier, &expansion);
+            }
+            out.push_str(&expansion);
+            rest = &rest[end + 1..];
+        }
+        out.push_str(rest);
+        Some(out)
+    }
+}
+
+// "noun.a.capitalize" -> ("noun", ["a", "capitalize"]). Symbol names can
+// contain dots themselves, so only known modifiers are split off the end.
+fn split_modifiers(reference: &str) -> (&str, Vec<&str>) {
+    let mut symbol = reference;
+    let mut modifiers = Vec::new();
+    while let Some((head, last)) = symbol.rsplit_once('.') {
+        if !MODIFIERS.contains(&last) {
+            break;
+        }
+        modifiers.insert(0, last);
+        symbol = head;
+    }
+    (symbol, modifiers)
+}
+
+fn apply(modifier: &str, text: &str) -> String {
+    match modifier {
+        "capitalize" => {
+            let mut chars = text.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        }
+        "a" => {
+            let vowel = text.chars().next().is_some_and(|c| "aeiouAEIOU".contains(c));
+            format!("{} {}", if vowel { "an" } else { "a" }, text)
+        }
+        "s" => {
+            if text.ends_with('s') || text.ends_with("sh") || text.ends_with("ch") || text.ends_with('x') {
+                format!("{}es", text)
+            } else if text.ends_with('y') && !text.ends_with("ay") && !text.ends_with("ey") && !text.ends_with("oy") {
+                format!("{}ies", &text[..text.len() - 1])
+            } else {
+                format!("{}s", text)
+            }
+        }
+        _ => text.to_string(),
+    }
+}
+
+// Symbols referenced as #name# in a rule, without modifiers
+pub fn references(text: &str) -> Vec<&str> {
+    text.split('#').skip(1).step_by(2).map(|reference| split_modifiers(reference).0).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn parse(text: &str) -> Grammar {
+        let pack: RoastPack = toml::from_str(text).unwrap();
+        Grammar::from_packs(&[pack])
+    }
+
+    fn expand(grammar: &Grammar, symbol: &str, snapshot: &Snapshot) -> Option<String> {
+        grammar.expand(symbol, &Tone::default(), snapshot, &mut StdRng::seed_from_u64(1))
+    }
+
+    #[test]
+    fn expands_symbols_and_facts() {
+        let grammar = parse(
+            r##"
+            [grammar]
+            "cpu.low" = ["#cpu.cores# cores and #verdict#."]
+            verdict = ["#adjective# #noun#"]
+            adjective = ["slow"]
+            noun = ["progress"]
+            "##,
+        );
+        let mut snapshot = Snapshot::default();
+        snapshot.set("cpu.cores", 2u64);
+        assert_eq!(expand(&grammar, "cpu.low", &snapshot).as_deref(), Some("2 cores and slow progress."));
+        // A fact the snapshot doesn't have leaves nothing to say
+        assert_eq!(expand(&grammar, "cpu.low", &Snapshot::default()), None);
+    }
+
+    #[test]
+    fn applies_modifiers() {
+        let grammar = parse(
+            r##"
+            [grammar]
+            start = ["#fan.a.capitalize# and #fan.s#, #box.s#, #story.s#, #toy.s#, #elbow.a#."]
+            fan = ["fan"]
+            box = ["box"]
+            story = ["story"]
+            toy = ["toy"]
+            elbow = ["elbow"]
+            "##,
+        );
+        assert_eq!(
+            expand(&grammar, "start", &Snapshot::default()).as_deref(),
+            Some("A fan and fans, boxes, stories, toys, an elbow.")
+        );
+        // Dots that aren't modifiers belong to the symbol's name
+        assert_eq!(split_modifiers("cpu.low.a.capitalize"), ("cpu.low", vec!["a", "capitalize"]));
+        assert_eq!(split_modifiers("cpu.low"), ("cpu.low", vec![]));
+    }
+
+    #[test]
+    fn recursive_rules_stop_at_max_depth() {
+        let grammar = parse(
+            r##"
+            [grammar]
+            forever = ["and #forever#"]
+            nested = ["#level#"]
+            level = ["#level2#"]
+            level2 = ["deep enough"]
+            "##,
+        );
+        assert_eq!(expand(&grammar, "forever", &Snapshot::default()), None);
+        assert_eq!(expand(&grammar, "nested", &Snapshot::default()).as_deref(), Some("deep enough"));
+        // The start rule is level 0, so a rule MAX_DEPTH levels below it is the deepest that expands
+        let chain = |levels: usize| {
+            let rules: String = (0..levels).map(|level| format!("s{} = [\"#s{}#\"]\n", level, level + 1)).collect();
+            parse(&format!("[grammar]\nstart = [\"#s0#\"]\n{}s{} = [\"bottom\"]\n", rules, levels))
+        };
+        assert_eq!(expand(&chain(MAX_DEPTH - 1), "start", &Snapshot::default()).as_deref(), Some("bottom"));
+        assert_eq!(expand(&chain(MAX_DEPTH), "start", &Snapshot::default()), None);
+    }
+
+    #[test]
+    fn unknown_symbols_fail() {
+        let grammar = parse(
+            r##"
+            [grammar]
+            start = ["Your #nothing_here# is slow."]
+            unclosed = ["Your #cpu is slow."]
+            "##,
+        );
+        assert!(!grammar.has("missing"));
+        assert_eq!(expand(&grammar, "missing", &Snapshot::default()), None);
+        assert_eq!(expand(&grammar, "start", &Snapshot::default()), None);
+        assert_eq!(expand(&grammar, "unclosed", &Snapshot::default()), None);
+        assert_eq!(references("#a.capitalize# and #b#"), ["a", "b"]);
+    }
+}
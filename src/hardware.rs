@@ -0,0 +1,104 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// Odds and ends about the machine that the combo roasts need
+pub struct Hardware {
+    // "desktop", "laptop", "server", "tablet" or "mini", from the DMI chassis type
+    pub chassis: Option<String>,
+    // Whether any internal disk spins / doesn't spin
+    pub has_hdd: bool,
+    pub has_ssd: bool,
+    // "power-saver", "balanced" or "performance"
+    pub power_profile: Option<String>,
+    pub on_battery: bool,
+    // Charge of the first battery, in percent
+    pub battery_percent: Option<u32>,
+}
+
+pub fn gather() -> Hardware {
+    let (has_hdd, has_ssd) = disk_kinds();
+    let battery = battery();
+    Hardware {
+        chassis: chassis(),
+        has_hdd,
+        has_ssd,
+        power_profile: power_profile(),
+        on_battery: battery.as_ref().is_some_and(|(status, _)| status == "Discharging"),
+        battery_percent: battery.and_then(|(_, percent)| percent),
+    }
+}
+
+fn read_trimmed(path: impl AsRef<Path>) -> Option<String> {
+    fs::read_to_string(path).ok().map(|text| text.trim().to_string())
+}
+
+// SMBIOS chassis types, folded into a few buckets
+fn chassis() -> Option<String> {
+    let kind: u32 = read_trimmed("/sys/class/dmi/id/chassis_type")?.parse().ok()?;
+    let chassis = match kind {
+        3..=7 | 13 | 15 | 16 | 24 => "desktop",
+        8..=10 | 14 | 31 | 32 => "laptop",
+        17 | 23 | 25 | 28 | 29 => "server",
+        11 | 30 => "tablet",
+        35 | 36 => "mini",
+        _ => return None,
+    };
+    Some(chassis.to_string())
+}
+
+// (spinning disk present, solid-state disk present), ignoring loop, ram and
+// removable devices
+fn disk_kinds() -> (bool, bool) {
+    let entries = match fs::read_dir("/sys/block") {
+        Ok(entries) => entries,
+        Err(_) => return (false, false),
+    };
+    let (mut hdd, mut ssd) = (false, false);
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if ["loop", "ram", "zram", "sr", "fd", "dm-", "md"].iter().any(|prefix| name.starts_with(prefix)) {
+            continue;
+        }
+        let dir = entry.path();
+        if read_trimmed(dir.join("removable")).as_deref() == Some("1") {
+            continue;
+        }
+        match read_trimmed(dir.join("queue/rotational")).as_deref() {
+            Some("1") => hdd = true,
+            Some("0") => ssd = true,
+            _ => {}
+        }
+    }
+    (hdd, ssd)
+}
+
+// The ACPI platform profile if the firmware has one, else power-profiles-daemon
+fn power_profile() -> Option<String> {
+    if let Some(profile) = read_trimmed("/sys/firmware/acpi/platform_profile") {
+        let profile = match profile.as_str() {
+            "low-power" | "quiet" | "cool" => "power-saver",
+            "performance" => "performance",
+            _ => "balanced",
+        };
+        return Some(profile.to_string());
+    }
+    let output = Command::new("powerprofilesctl").arg("get").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string()).filter(|p| !p.is_empty())
+}
+
+// (status, charge percent) of the first battery
+pub fn battery() -> Option<(String, Option<u32>)> {
+    let entries = fs::read_dir("/sys/class/power_supply").ok()?;
+    let mut batteries: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|path| read_trimmed(path.join("type")).as_deref() == Some("Battery"))
+        .collect();
+    batteries.sort();
+    let battery = batteries.first()?;
+    let status = read_trimmed(battery.join("status")).unwrap_or_default();
+    Some((status, read_trimmed(battery.join("capacity")).and_then(|c| c.parse().ok())))
+}
@@ -0,0 +1,126 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+
+// Roasts shown in the last few runs, kept in $XDG_STATE_HOME/rust_roast/history.json.
+// Lines are stored before placeholders are filled, so "{packages} packages" is one
+// entry no matter how many packages you had that day.
+pub struct History {
+    path: Option<PathBuf>,
+    // Oldest run first; each run is the list of lines it showed
+    runs: VecDeque<Vec<String>>,
+    current: Vec<String>,
+    seen: HashSet<String>,
+    keep: usize,
+}
+
+// Directory for state that should survive runs (XDG_STATE_HOME or ~/.local/state)
+pub fn state_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_STATE_HOME").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join("rust_roast"));
+    }
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state/rust_roast"))
+}
+
+impl History {
+    // Loads the last `keep` runs. A missing or unreadable file is an empty history.
+    pub fn load(keep: usize) -> History {
+        if keep == 0 {
+            return History::disabled();
+        }
+        History::load_from(state_dir().map(|dir| dir.join("history.json")), keep)
+    }
+
+    fn load_from(path: Option<PathBuf>, keep: usize) -> History {
+        let runs: VecDeque<Vec<String>> = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        let skip = runs.len().saturating_sub(keep);
+        let runs: VecDeque<Vec<String>> = runs.into_iter().skip(skip).collect();
+        let seen = runs.iter().flatten().cloned().collect();
+        History { path, runs, current: Vec::new(), seen, keep }
+    }
+
+    // Remembers nothing across runs, but still avoids repeats within one
+    pub fn disabled() -> History {
+        History { path: None, runs: VecDeque::new(), current: Vec::new(), seen: HashSet::new(), keep: 0 }
+    }
+
+    pub fn seen(&self, line: &str) -> bool {
+        self.seen.contains(line)
+    }
+
+    pub fn record(&mut self, line: &str) {
+        self.current.push(line.to_string());
+        self.seen.insert(line.to_string());
+    }
+
+    // Appends this run and writes the file back. Failing to save only costs variety.
+    pub fn save(mut self) {
+        let path = match self.path.take() {
+            Some(path) => path,
+            None => return,
+        };
+        if self.current.is_empty() {
+            return;
+        }
+        self.runs.push_back(std::mem::take(&mut self.current));
+        while self.runs.len() > self.keep {
+            self.runs.pop_front();
+        }
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(text) = serde_json::to_string(&self.runs) {
+            let _ = fs::write(&path, text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_what_was_seen() {
+        let mut history = History::disabled();
+        assert!(!history.seen("{packages} packages"));
+        history.record("{packages} packages");
+        assert!(history.seen("{packages} packages"));
+        assert!(!history.seen("{packages} packages!"));
+    }
+
+    #[test]
+    fn keeps_the_last_runs() {
+        let path = std::env::temp_dir().join(format!("rust_roast-history-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        for line in ["first", "second", "third"] {
+            let mut history = History::load_from(Some(path.clone()), 2);
+            history.record(line);
+            history.save();
+        }
+        let history = History::load_from(Some(path.clone()), 2);
+        assert!(!history.seen("first"));
+        assert!(history.seen("second"));
+        assert!(history.seen("third"));
+        // Keeping fewer runs than the file has drops the oldest
+        let history = History::load_from(Some(path.clone()), 1);
+        assert!(!history.seen("second"));
+        assert!(history.seen("third"));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn a_run_without_roasts_isnt_saved() {
+        let path = std::env::temp_dir().join(format!("rust_roast-history-empty-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        History::load_from(Some(path.clone()), 3).save();
+        assert!(!path.exists());
+        // A broken file is an empty history
+        fs::write(&path, "not json").unwrap();
+        assert!(!History::load_from(Some(path.clone()), 3).seen("not json"));
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,193 @@
+use crate::card::{DEFAULT_BACKGROUND, DEFAULT_FOREGROUND};
+use crate::i18n::{self, tr};
+use crate::render::Report;
+use crate::roast::Severity;
+use crate::score;
+use crate::theme::{Style, Theme};
+
+// Text for an element's content, where only &, < and > mean anything
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+// CSS declarations for a role
+fn css(style: &Style) -> String {
+    let mut declarations = Vec::new();
+    if let Some(fg) = style.fg {
+        declarations.push(format!("color: {};", fg.hex()));
+    }
+    if let Some(bg) = style.bg {
+        declarations.push(format!("background: {};", bg.hex()));
+    }
+    if style.bold {
+        declarations.push("font-weight: bold;".to_string());
+    }
+    if style.italic {
+        declarations.push("font-style: italic;".to_string());
+    }
+    if style.underline {
+        declarations.push("text-decoration: underline;".to_string());
+    }
+    if style.dim {
+        declarations.push("opacity: 0.6;".to_string());
+    }
+    declarations.join(" ")
+}
+
+// The class of a roast's <li>, which picks its color
+fn severity_class(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Gentle => "gentle",
+        Severity::Standard => "standard",
+        Severity::Savage => "savage",
+    }
+}
+
+// The heading for a roast category ("cpu" -> "CPU"); categories without a
+// translation, like ones only a pack uses, show as they are
+fn category_title(category: &str) -> String {
+    let key = format!("category.{}", category);
+    match tr(&key) {
+        title if title == key => category.to_string(),
+        title => title.to_string(),
+    }
+}
+
+// The report as a web page with everything inline: the spec tables, the score
+// and grade, the roasts grouped by category, and the raw snapshot as JSON
+// under a fold. Like cards, the same report always gives the same bytes.
+pub fn page(report: &Report, theme: &Theme) -> String {
+    // As a language tag: "pt_br" -> "pt-br"
+    let lang: String = i18n::languages()
+        .first()
+        .map_or("en", String::as_str)
+        .chars()
+        .map(|c| if c == '_' { '-' } else { c })
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
+    let title = report.sections.first().map_or("rust_roast", |section| section.title.as_str());
+    let background = theme.card.bg.unwrap_or(DEFAULT_BACKGROUND).hex();
+    let foreground = theme.card.fg.unwrap_or(DEFAULT_FOREGROUND).hex();
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n");
+    html.push_str(&format!("<html lang=\"{}\">\n<head>\n", lang));
+    html.push_str("<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str(&format!("<title>rust_roast: {}</title>\n", escape(title)));
+    html.push_str("<style>\n");
+    html.push_str(&format!(
+        "body {{ margin: 0; padding: 2rem 1rem; background: {}; color: {}; font-family: \"DejaVu Sans Mono\", ui-monospace, monospace; line-height: 1.5; }}\n",
+        background, foreground
+    ));
+    html.push_str(&format!("main {{ max-width: 48rem; margin: 0 auto; padding: 1.5rem 2rem; border: 1px solid {}; border-radius: 12px; }}\n", foreground));
+    html.push_str(&format!("h2 {{ font-size: 1rem; margin: 1.5rem 0 0.5rem; {} }}\n", css(&theme.header)));
+    html.push_str("h2:first-child { margin-top: 0; }\n");
+    html.push_str("table { border-collapse: collapse; margin-left: 1rem; }\n");
+    html.push_str(&format!("th {{ text-align: left; vertical-align: top; font-weight: normal; padding: 0 2rem 0 0; {} }}\n", css(&theme.label)));
+    html.push_str(&format!("td {{ padding: 0; {} }}\n", css(&theme.value)));
+    html.push_str(".score { display: flex; align-items: center; gap: 1rem; margin: 1.5rem 0 0 1rem; }\n");
+    html.push_str(&format!(".score .label {{ {} }}\n", css(&theme.label)));
+    html.push_str(&format!(".score .value {{ {} }}\n", css(&theme.value)));
+    html.push_str(&format!(".bar {{ width: 12rem; height: 0.75rem; border-radius: 3px; background: {}40; overflow: hidden; }}\n", foreground));
+    html.push_str(".bar div { height: 100%; border-radius: 3px; }\n");
+    html.push_str(".grade { font-size: 1.5rem; font-weight: bold; }\n");
+    html.push_str(&format!("h2.roast {{ {} }}\n", css(&theme.roast)));
+    html.push_str("h3 { font-size: 0.9rem; margin: 0.75rem 0 0.25rem 1rem; opacity: 0.7; }\n");
+    html.push_str("ul { margin: 0; padding-left: 2.5rem; }\n");
+    // Each roast takes the color of the severity it was written at
+    for severity in [Severity::Gentle, Severity::Standard, Severity::Savage] {
+        html.push_str(&format!("li.{} {{ {} }}\n", severity_class(severity), css(theme.severity(severity))));
+    }
+    html.push_str("details { margin-top: 1.5rem; }\n");
+    html.push_str("summary { cursor: pointer; opacity: 0.7; }\n");
+    html.push_str("pre { overflow-x: auto; font-size: 0.85rem; }\n");
+    html.push_str("</style>\n</head>\n<body>\n<main>\n");
+
+    for section in &report.sections {
+        html.push_str(&format!("<h2>{}</h2>\n<table>\n", escape(&section.title)));
+        for (label, value) in &section.specs {
+            html.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", escape(label), escape(value)));
+        }
+        html.push_str("</table>\n");
+    }
+
+    if let Some(score) = report.score {
+        // The bar and the grade take the color roasts at the matching severity would have
+        let style = match score {
+            70.. => &theme.gentle,
+            40.. => &theme.standard,
+            _ => &theme.savage,
+        };
+        let color = style.fg.unwrap_or(DEFAULT_FOREGROUND).hex();
+        html.push_str("<div class=\"score\">\n");
+        html.push_str(&format!("<span class=\"label\">{}</span>\n", escape(tr("label.score"))));
+        html.push_str(&format!("<div class=\"bar\"><div style=\"width: {}%; background: {};\"></div></div>\n", score, color));
+        html.push_str(&format!("<span class=\"value\">{}/100</span>\n", score));
+        html.push_str(&format!("<span class=\"label\">{}</span>\n", escape(tr("label.grade"))));
+        html.push_str(&format!("<span class=\"grade\" style=\"color: {};\">{}</span>\n", color, score::grade(score)));
+        html.push_str("</div>\n");
+    }
+
+    if !report.heading.is_empty() {
+        html.push_str(&format!("<h2 class=\"roast\">{}</h2>\n", escape(&report.heading)));
+    }
+    // Categories in the order their first roast came up
+    let mut categories: Vec<&str> = Vec::new();
+    for (category, _) in &report.roasts {
+        if !categories.contains(&category.as_str()) {
+            categories.push(category);
+        }
+    }
+    for category in categories {
+        if !category.is_empty() {
+            html.push_str(&format!("<h3>{}</h3>\n", escape(&category_title(category))));
+        }
+        html.push_str("<ul>\n");
+        for (_, roast) in report.roasts.iter().filter(|(other, _)| other == category) {
+            html.push_str(&format!("<li class=\"{}\">{}</li>\n", severity_class(roast.severity), escape(&roast.text)));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    if !report.snapshot.is_null() {
+        let json = serde_json::to_string_pretty(&report.snapshot).unwrap_or_default();
+        html.push_str(&format!("<details>\n<summary>{}</summary>\n", escape(tr("header.snapshot"))));
+        html.push_str(&format!("<pre><code>{}</code></pre>\n</details>\n", escape(&json)));
+    }
+    html.push_str("</main>\n</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Section;
+    use crate::roast::Picked;
+
+    #[test]
+    fn escapes_markup() {
+        assert_eq!(escape("<full> & \"fast\""), "&lt;full&gt; &amp; \"fast\"");
+        // Already escaped text is escaped again, not left alone
+        assert_eq!(escape("&amp;"), "&amp;amp;");
+        assert_eq!(escape("plain"), "plain");
+    }
+
+    #[test]
+    fn page_escapes_everything_it_shows() {
+        let report = Report {
+            sections: vec![Section { title: "<Specs>".to_string(), specs: vec![("CPU & co".to_string(), "<b>fast</b>".to_string())] }],
+            heading: "Roast <3".to_string(),
+            roasts: vec![("<cat>".to_string(), Picked { text: "RAM </li><script>".to_string(), severity: Severity::Savage })],
+            snapshot: serde_json::json!({ "gpu.name": "</code></pre><h1>" }),
+            ..Report::default()
+        };
+        let page = page(&report, &Theme::default());
+        assert!(page.contains("<title>rust_roast: &lt;Specs&gt;</title>"));
+        assert!(page.contains("<tr><th>CPU &amp; co</th><td>&lt;b&gt;fast&lt;/b&gt;</td></tr>"));
+        assert!(page.contains("<h2 class=\"roast\">Roast &lt;3</h2>"));
+        assert!(page.contains("<h3>&lt;cat&gt;</h3>"));
+        assert!(page.contains("<li class=\"savage\">RAM &lt;/li&gt;&lt;script&gt;</li>"));
+        assert!(page.contains("&lt;/code&gt;&lt;/pre&gt;&lt;h1&gt;"));
+        assert!(!page.contains("<script>") && !page.contains("<b>") && !page.contains("<h1>"));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::config::config_dir;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+// UI string catalogs shipped with the binary. English is the fallback for every key.
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.toml")),
+    ("de", include_str!("../locales/de.toml")),
+    ("pt", include_str!("../locales/pt.toml")),
+    ("ja", include_str!("../locales/ja.toml")),
+];
+
+struct Locale {
+    // Language tags to use, most specific first, e.g. ["pt-br", "pt"]. Empty for English.
+    languages: Vec<String>,
+    strings: HashMap<String, String>,
+}
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+// "pt_BR.UTF-8" -> ["pt-br", "pt"]. English and the C locale give no tags.
+pub fn language_tags(locale: &str) -> Vec<String> {
+    let tag = locale.split(['.', '@']).next().unwrap_or("").trim().to_lowercase().replace('_', "-");
+    if tag.is_empty() || tag == "c" || tag == "posix" || tag == "en" || tag.starts_with("en-") {
+        return Vec::new();
+    }
+    let mut tags = vec![tag.clone()];
+    if let Some((language, _)) = tag.split_once('-') {
+        tags.push(language.to_string());
+    }
+    tags
+}
+
+// The language to use: --lang, then the config file, then LC_ALL, LC_MESSAGES and LANG
+pub fn detect(explicit: Option<&str>) -> Vec<String> {
+    detect_with(explicit, |name| std::env::var(name).ok())
+}
+
+fn detect_with(explicit: Option<&str>, var: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    if let Some(lang) = explicit.filter(|l| !l.is_empty()) {
+        return language_tags(lang);
+    }
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| var(name))
+        .find(|value| !value.is_empty())
+        .map(|value| language_tags(&value))
+        .unwrap_or_default()
+}
+
+// Flattens nested tables into dotted keys: [header] system = ".." -> "header.system"
+fn flatten(prefix: &str, table: &toml::Table, out: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match value {
+            toml::Value::String(text) => {
+                out.insert(key, text.clone());
+            }
+            toml::Value::Table(inner) => flatten(&key, inner, out),
+            _ => {}
+        }
+    }
+}
+
+fn load_catalog(text: &str, out: &mut HashMap<String, String>) -> Result<(), String> {
+    let table: toml::Table = text.parse().map_err(|e: toml::de::Error| e.to_string())?;
+    flatten("", &table, out);
+    Ok(())
+}
+
+// Picks the language for this run. English first, then the shipped catalog and
+// ~/.config/rust_roast/locales/<lang>.toml, generic before specific, so every
+// layer only needs the keys it changes.
+pub fn init(languages: Vec<String>) {
+    let strings = layered(&languages, config_dir().map(|dir| dir.join("locales")).as_deref());
+    let _ = LOCALE.set(Locale { languages, strings });
+}
+
+// The strings for `languages`, each layer over the one before, with user
+// catalogs from `user_dir` over the shipped ones
+fn layered(languages: &[String], user_dir: Option<&Path>) -> HashMap<String, String> {
+    let mut strings = HashMap::new();
+    for lang in std::iter::once("en").chain(languages.iter().rev().map(String::as_str)) {
+        if let Some((_, text)) = CATALOGS.iter().find(|(name, _)| *name == lang) {
+            let _ = load_catalog(text, &mut strings);
+        }
+        if lang == "en" {
+            continue;
+        }
+        if let Some(path) = user_dir.map(|dir| dir.join(format!("{}.toml", lang))) {
+            if let Ok(text) = fs::read_to_string(&path) {
+                if let Err(err) = load_catalog(&text, &mut strings) {
+                    eprintln!("rust_roast: ignoring {}: {}", path.display(), err.trim());
+                }
+            }
+        }
+    }
+    strings
+}
+
+fn locale() -> &'static Locale {
+    LOCALE.get_or_init(|| {
+        let mut strings = HashMap::new();
+        let _ = load_catalog(CATALOGS[0].1, &mut strings);
+        Locale { languages: Vec::new(), strings }
+    })
+}
+
+pub fn languages() -> &'static [String] {
+    &locale().languages
+}
+
+// The UI string for `key`, or the key itself if no catalog has it
+pub fn tr(key: &str) -> &str {
+    locale().strings.get(key).map(String::as_str).unwrap_or(key)
+}
+
+// tr() with {placeholders} filled in
+pub fn trf(key: &str, args: &[(&str, &str)]) -> String {
+    let mut text = tr(key).to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(list: &[&str]) -> Vec<String> {
+        list.iter().map(|tag| tag.to_string()).collect()
+    }
+
+    #[test]
+    fn language_tags_from_locales() {
+        assert_eq!(language_tags("pt_BR.UTF-8"), tags(&["pt-br", "pt"]));
+        assert_eq!(language_tags("de_DE@euro"), tags(&["de-de", "de"]));
+        assert_eq!(language_tags("ja"), tags(&["ja"]));
+        for english in ["C", "C.UTF-8", "POSIX", "en", "en_US.UTF-8", ""] {
+            assert!(language_tags(english).is_empty(), "{:?}", english);
+        }
+    }
+
+    #[test]
+    fn locale_variables_in_order() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(var, _)| *var == name).map(|(_, value)| value.to_string())
+        };
+        let all = env(&[("LC_ALL", "de_DE.UTF-8"), ("LC_MESSAGES", "ja_JP.UTF-8"), ("LANG", "pt_BR.UTF-8")]);
+        assert_eq!(detect_with(None, all), tags(&["de-de", "de"]));
+        assert_eq!(detect_with(Some("ja"), all), tags(&["ja"]));
+        // An empty --lang or variable is skipped
+        assert_eq!(detect_with(Some(""), all), tags(&["de-de", "de"]));
+        let messages = env(&[("LC_ALL", ""), ("LC_MESSAGES", "ja_JP.UTF-8"), ("LANG", "pt_BR.UTF-8")]);
+        assert_eq!(detect_with(None, messages), tags(&["ja-jp", "ja"]));
+        assert_eq!(detect_with(None, env(&[("LANG", "pt_BR.UTF-8")])), tags(&["pt-br", "pt"]));
+        assert!(detect_with(None, env(&[])).is_empty());
+    }
+
+    #[test]
+    fn missing_keys_fall_back_to_english() {
+        for lang in ["de", "ja"] {
+            let strings = layered(&tags(&[lang]), None);
+            assert_eq!(strings["category.cpu"], "CPU");
+            assert_eq!(strings["category.gpu"], "GPU");
+        }
+        assert_eq!(layered(&tags(&["de"]), None)["category.uptime"], "Laufzeit");
+        assert_eq!(layered(&tags(&["xx"]), None)["category.uptime"], "Uptime");
+    }
+
+    #[test]
+    fn user_catalogs_go_over_shipped_ones() {
+        let dir = std::env::temp_dir().join(format!("rust_roast-locales-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("pt-br.toml"), "[category]\nuptime = \"Tempo ligado\"\n").unwrap();
+        let strings = layered(&tags(&["pt-br", "pt"]), Some(&dir));
+        assert_eq!(strings["category.uptime"], "Tempo ligado");
+        assert_eq!(strings["category.apps"], layered(&tags(&["pt"]), None)["category.apps"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn catalogs_only_translate_known_keys() {
+        let mut english = HashMap::new();
+        load_catalog(CATALOGS[0].1, &mut english).unwrap();
+        for (lang, text) in &CATALOGS[1..] {
+            let mut strings = HashMap::new();
+            load_catalog(text, &mut strings).unwrap();
+            for key in strings.keys() {
+                assert!(english.contains_key(key), "{} has unknown key {}", lang, key);
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+pub mod card;
+pub mod cli;
+pub mod combo;
+pub mod config;
+pub mod desktop;
+pub mod devenv;
+pub mod distro;
+pub mod git;
+pub mod grammar;
+pub mod hardware;
+pub mod history;
+pub mod html;
+pub mod i18n;
+pub mod lint;
+pub mod llm;
+pub mod live;
+pub mod logo;
+pub mod markdown;
+pub mod markov;
+pub mod pack;
+pub mod packages;
+pub mod pools;
+pub mod processes;
+pub mod project;
+pub mod render;
+pub mod roast;
+pub mod score;
+pub mod snapshot;
+pub mod theme;
+pub mod tui;
+pub mod uptime;
+pub mod watch;
@@ -0,0 +1,277 @@
+use crate::combo;
+use crate::grammar;
+use crate::pack::{self, RoastPack};
+use crate::pools;
+use crate::processes;
+use crate::roast::Roast;
+use crate::snapshot;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+// Word overlap above which two lines count as near-duplicates
+pub const NEAR_DUPLICATE: f64 = 0.8;
+
+pub struct Finding {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+// Lowercase words without punctuation, so "Bold." and "bold!" compare equal
+pub fn words(line: &str) -> Vec<String> {
+    line.split(|c: char| !c.is_alphanumeric() && c != '{' && c != '}' && c != '\'')
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+// Jaccard similarity of the word sets of two lines (1.0 = same words)
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a: HashSet<String> = words(a).into_iter().collect();
+    let b: HashSet<String> = words(b).into_iter().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    a.intersection(&b).count() as f64 / a.union(&b).count() as f64
+}
+
+// Every line in a pack, keyed by the pool it extends ("apps.<id>" and
+// "combos.<id>" for app and combo entries, "grammar.<symbol>" for grammar rules)
+fn pack_lines(pack: &RoastPack) -> Vec<(String, Roast)> {
+    let mut lines: Vec<(String, Roast)> = Vec::new();
+    let mut pools: Vec<_> = pack.roasts.iter().collect();
+    pools.sort_by(|a, b| a.0.cmp(b.0));
+    for (pool, roasts) in pools {
+        lines.extend(roasts.iter().map(|roast| (pool.clone(), roast.to_roast())));
+    }
+    for app in &pack.apps {
+        lines.extend(app.roasts.iter().map(|roast| (format!("apps.{}", app.id), roast.to_roast())));
+    }
+    for combo in &pack.combos {
+        lines.extend(combo.roasts.iter().map(|roast| (format!("combos.{}", combo.id), roast.to_roast())));
+    }
+    let mut symbols: Vec<_> = pack.grammar.iter().collect();
+    symbols.sort_by(|a, b| a.0.cmp(b.0));
+    for (symbol, rules) in symbols {
+        lines.extend(rules.iter().map(|rule| (format!("grammar.{}", symbol), rule.to_roast())));
+    }
+    lines
+}
+
+// Every line built into the binaries: the named pools, and the app and combo roasts
+fn builtin_lines() -> Vec<(String, Roast)> {
+    let mut lines: Vec<(String, Roast)> = Vec::new();
+    for (pool, roasts) in pools::builtin() {
+        lines.extend(roasts.iter().map(|roast| (pool.to_string(), roast.clone())));
+    }
+    for rule in processes::app_rules(&[]) {
+        let pool = format!("apps.{}", rule.id);
+        lines.extend(rule.roasts.into_iter().map(|roast| (pool.clone(), roast)));
+    }
+    for rule in combo::combo_rules(&[]) {
+        let pool = format!("combos.{}", rule.id);
+        lines.extend(rule.roasts.into_iter().map(|roast| (pool.clone(), roast)));
+    }
+    lines
+}
+
+// Grammar symbols defined by `packs`
+fn grammar_symbols(packs: &[(Option<String>, PathBuf, RoastPack)]) -> HashSet<String> {
+    packs.iter().flat_map(|(_, _, pack)| pack.grammar.keys().cloned()).collect()
+}
+
+// "grammar.<symbol>" pools hold fragments, which may well repeat across symbols
+fn is_grammar(pool: &str) -> bool {
+    pool.rsplit(':').next().unwrap_or(pool).starts_with("grammar.")
+}
+
+// The language part of a pool key ("de" for "de:cpu.low"), None for English
+fn language(pool: &str) -> Option<&str> {
+    pool.split_once(':').map(|(lang, _)| lang)
+}
+
+// Checks the conditions of `lines` and compares each with the lines seen so
+// far: near-duplicates within a pool, and the very same line in another pool
+fn check_lines(findings: &mut Vec<Finding>, seen: &mut Vec<(PathBuf, String, String)>, path: &Path, lang: Option<&str>, lines: Vec<(String, Roast)>) {
+    for (pool, roast) in lines {
+        let pool = match lang {
+            Some(lang) => format!("{}:{}", lang, pool),
+            None => pool,
+        };
+        if let Some(Err(err)) = roast.condition.as_deref().map(snapshot::validate) {
+            findings.push(Finding {
+                path: path.to_path_buf(),
+                message: format!("bad condition in \"{}\": \"{}\": {}", pool, roast.text, err),
+            });
+        }
+        let line = roast.text.into_owned();
+        let earlier = seen
+            .iter()
+            .filter(|(_, other_pool, _)| *other_pool == pool)
+            .map(|(other_path, _, other)| (other_path, other, similarity(&line, other)))
+            .filter(|(_, _, score)| *score >= NEAR_DUPLICATE)
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+        let elsewhere = || {
+            seen.iter().find(|(_, other_pool, other)| {
+                !is_grammar(&pool) && !is_grammar(other_pool) && language(other_pool) == language(&pool) && words(other) == words(&line)
+            })
+        };
+        if let Some((other_path, other, score)) = earlier {
+            let kind = if words(&line) == words(other) { "duplicate" } else { "near-duplicate" };
+            findings.push(Finding {
+                path: path.to_path_buf(),
+                message: format!(
+                    "{} in \"{}\": \"{}\" ({:.0}% like \"{}\"{})",
+                    kind,
+                    pool,
+                    line,
+                    score * 100.0,
+                    other,
+                    same_file_note(path, other_path)
+                ),
+            });
+        } else if let Some((other_path, other_pool, _)) = elsewhere() {
+            findings.push(Finding {
+                path: path.to_path_buf(),
+                message: format!("duplicate in \"{}\": \"{}\" (also in \"{}\"{})", pool, line, other_pool, same_file_note(path, other_path)),
+            });
+        }
+        seen.push((path.to_path_buf(), pool, line));
+    }
+}
+
+// Checks the built-in pools and packs and every user pack for parse errors,
+// broken conditions, grammar rules that use undefined symbols, duplicate or
+// near-duplicate lines within a pool and lines repeated in another pool.
+// Translations are only compared with other translations into the same
+// language. The built-in sources come first, so a pack line that repeats a
+// built-in one is the one reported.
+pub fn lint_packs() -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let builtin_packs = pack::BUILTIN_PACKS.iter().map(|(name, text)| (None, builtin_path(&format!("{} pack", name)), Some(*text)));
+    let builtin_locale_packs = pack::BUILTIN_LOCALE_PACKS
+        .iter()
+        .map(|(lang, text)| (Some(lang.to_string()), builtin_path(&format!("{} pack", lang)), Some(*text)));
+    let files = pack::pack_files().into_iter().map(|path| (None, path, None));
+    let locale_files = pack::locale_dirs()
+        .into_iter()
+        .flat_map(|lang| pack::locale_pack_files(&lang).into_iter().map(move |path| (Some(lang.clone()), path, None)));
+    let mut packs = Vec::new();
+    for (lang, path, text) in builtin_packs.chain(builtin_locale_packs).chain(files).chain(locale_files) {
+        // Built-in packs come as text, the others are read from disk
+        let parsed = match text {
+            Some(text) => toml::from_str(text).map_err(|err| err.to_string().trim().to_string()),
+            None => pack::load_pack(&path),
+        };
+        match parsed {
+            Ok(pack) => packs.push((lang, path, pack)),
+            Err(err) => findings.push(Finding { path, message: format!("doesn't parse: {}", err) }),
+        }
+    }
+    let symbols = grammar_symbols(&packs);
+    // (path, pool, line) for every line seen so far
+    let mut seen: Vec<(PathBuf, String, String)> = Vec::new();
+    let builtin = builtin_path("pools");
+    for rule in combo::combo_rules(&[]) {
+        if let Err(err) = snapshot::validate(&rule.condition) {
+            findings.push(Finding { path: builtin.clone(), message: format!("bad condition in combo \"{}\": {}", rule.id, err) });
+        }
+    }
+    check_lines(&mut findings, &mut seen, &builtin, None, builtin_lines());
+    for (lang, path, pack) in packs {
+        for (pool, roast) in pack_lines(&pack) {
+            let Some(symbol) = pool.strip_prefix("grammar.") else { continue };
+            // Anything that isn't a symbol has to be a fact
+            let unknown = grammar::references(&roast.text)
+                .into_iter()
+                .find(|reference| !symbols.contains(*reference) && !snapshot::is_fact(reference));
+            if let Some(reference) = unknown {
+                findings.push(Finding {
+                    path: path.clone(),
+                    message: format!("unknown symbol #{}# in grammar \"{}\": \"{}\"", reference, symbol, roast.text),
+                });
+            }
+        }
+        for combo in &pack.combos {
+            if let Some(Err(err)) = combo.when.as_deref().map(snapshot::validate) {
+                findings.push(Finding { path: path.clone(), message: format!("bad condition in combo \"{}\": {}", combo.id, err) });
+            }
+        }
+        check_lines(&mut findings, &mut seen, &path, lang.as_deref(), pack_lines(&pack));
+    }
+    findings
+}
+
+// Stands in for a file name in findings about what's built into the binaries
+fn builtin_path(name: &str) -> PathBuf {
+    PathBuf::from(format!("built-in {}", name))
+}
+
+fn same_file_note(path: &Path, other: &Path) -> String {
+    if path == other {
+        String::new()
+    } else {
+        format!(" in {}", other.display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pack::PackRoast;
+
+    #[test]
+    fn words_ignore_case_and_punctuation() {
+        assert_eq!(words("Bold. BOLD! it's {ram}"), ["bold", "bold", "it's", "{ram}"]);
+        assert_eq!(words("...  --"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn similarity_is_word_set_overlap() {
+        assert_eq!(similarity("Your RAM is tiny.", "your ram is TINY"), 1.0);
+        assert_eq!(similarity("", ""), 1.0);
+        assert_eq!(similarity("slow cpu", "fast gpu"), 0.0);
+        // 3 shared words out of 5
+        assert_eq!(similarity("your cpu is slow", "your cpu is fast"), 0.6);
+        // Repeats don't count twice
+        assert_eq!(similarity("slow slow slow", "slow"), 1.0);
+    }
+
+    fn check(lines: &[(&str, &str)], lang: Option<&str>, seen: &mut Vec<(PathBuf, String, String)>) -> Vec<String> {
+        let lines = lines.iter().map(|(pool, text)| (pool.to_string(), PackRoast::Text(text.to_string()).to_roast())).collect();
+        let mut findings = Vec::new();
+        check_lines(&mut findings, seen, Path::new("test.toml"), lang, lines);
+        findings.into_iter().map(|finding| finding.message).collect()
+    }
+
+    #[test]
+    fn finds_repeats() {
+        let mut seen = Vec::new();
+        let findings = check(
+            &[
+                ("cpu.low", "Your CPU is slow and so is everything else"),
+                ("cpu.low", "Your CPU is slow, and so is everything else!"),
+                ("cpu.low", "Your CPU is slow and so is everything else, sadly"),
+                ("ram.low", "Your CPU is slow and so is everything else"),
+                ("grammar.slow", "slow"),
+                ("grammar.sluggish", "slow"),
+            ],
+            None,
+            &mut seen,
+        );
+        assert_eq!(findings.len(), 3, "{:?}", findings);
+        assert!(findings[0].starts_with("duplicate in \"cpu.low\""));
+        assert!(findings[1].starts_with("near-duplicate in \"cpu.low\""));
+        assert!(findings[2].starts_with("duplicate in \"ram.low\""));
+        assert!(findings[2].ends_with("(also in \"cpu.low\")"));
+        // A translation isn't compared with English
+        assert!(check(&[("cpu.low", "Your CPU is slow and so is everything else")], Some("de"), &mut seen).is_empty());
+    }
+
+    #[test]
+    fn shipped_lines_are_clean() {
+        let mut findings = Vec::new();
+        check_lines(&mut findings, &mut Vec::new(), Path::new("built-in pools"), None, builtin_lines());
+        let messages: Vec<String> = findings.into_iter().map(|finding| finding.message).collect();
+        assert!(messages.is_empty(), "{:#?}", messages);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::hardware;
+use crate::snapshot::Snapshot;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use sysinfo::{ComponentExt, CpuExt, ProcessExt, ProcessRefreshKind, System, SystemExt};
+
+// Where GPU load comes from: amdgpu (and some Intel drivers) report it in
+// sysfs; NVIDIA's only through nvidia-smi
+enum GpuSource {
+    Sysfs(PathBuf),
+    NvidiaSmi,
+}
+
+fn gpu_source() -> Option<GpuSource> {
+    let cards = fs::read_dir("/sys/class/drm").ok()?;
+    let mut paths: Vec<PathBuf> = cards.filter_map(|entry| entry.ok()).map(|entry| entry.path().join("device/gpu_busy_percent")).filter(|path| path.is_file()).collect();
+    paths.sort();
+    if let Some(path) = paths.into_iter().next() {
+        return Some(GpuSource::Sysfs(path));
+    }
+    let smi = Command::new("nvidia-smi").arg("--query-gpu=utilization.gpu").arg("--format=csv,noheader,nounits").output().ok()?;
+    smi.status.success().then_some(GpuSource::NvidiaSmi)
+}
+
+// One reading of the machine's load
+#[derive(Debug, Clone, Default)]
+pub struct Sample {
+    // Across all cores, in percent
+    pub cpu: f32,
+    // In bytes
+    pub ram_used: u64,
+    pub ram_total: u64,
+    pub swap_used: u64,
+    pub swap_total: u64,
+    // In percent; None when the driver doesn't say
+    pub gpu: Option<f32>,
+    // The hottest sensor, in °C; None without sensors
+    pub temperature: Option<f32>,
+    pub on_battery: bool,
+    // Charge of the first battery, in percent; None without a battery
+    pub battery: Option<u32>,
+}
+
+impl Sample {
+    pub fn ram_percent(&self) -> f32 {
+        if self.ram_total == 0 {
+            return 0.0;
+        }
+        self.ram_used as f32 / self.ram_total as f32 * 100.0
+    }
+
+    // The readings as facts, so pack conditions can use them
+    pub fn record(&self, snapshot: &mut Snapshot) {
+        snapshot.set("live.cpu", self.cpu as f64);
+        snapshot.set("live.ram", self.ram_percent() as f64);
+        snapshot.set("live.swap_gb", self.swap_used as f64 / 1024.0 / 1024.0 / 1024.0);
+        if let Some(gpu) = self.gpu {
+            snapshot.set("live.gpu", gpu as f64);
+        }
+        if let Some(temperature) = self.temperature {
+            snapshot.set("live.temperature", temperature as f64);
+        }
+        snapshot.set("power.on_battery", self.on_battery);
+        if let Some(percent) = self.battery {
+            snapshot.set("power.battery_percent", percent as u64);
+        }
+    }
+}
+
+// Reads the machine's load over and over. The System lives as long as the
+// sampler and only the parts that are shown get refreshed, since CPU usage
+// is measured between two refreshes and a full refresh every second would
+// cost more than everything else together.
+pub struct Sampler {
+    sys: System,
+    gpu: Option<GpuSource>,
+}
+
+impl Sampler {
+    pub fn new() -> Sampler {
+        let mut sys = System::new();
+        sys.refresh_cpu();
+        sys.refresh_components_list();
+        // CPU usage needs two refreshes some time apart, so the first sample means something
+        std::thread::sleep(System::MINIMUM_CPU_UPDATE_INTERVAL);
+        Sampler { sys, gpu: gpu_source() }
+    }
+
+    pub fn sample(&mut self) -> Sample {
+        self.sys.refresh_cpu();
+        self.sys.refresh_memory();
+        self.sys.refresh_components();
+        let temperature = self
+            .sys
+            .components()
+            .iter()
+            .map(|component| component.temperature())
+            .filter(|celsius| celsius.is_finite() && *celsius > 0.0)
+            .reduce(f32::max);
+        let battery = hardware::battery();
+        Sample {
+            cpu: self.sys.global_cpu_info().cpu_usage(),
+            ram_used: self.sys.used_memory(),
+            ram_total: self.sys.total_memory(),
+            swap_used: self.sys.used_swap(),
+            swap_total: self.sys.total_swap(),
+            gpu: self.gpu_load(),
+            temperature,
+            on_battery: battery.as_ref().is_some_and(|(status, _)| status == "Discharging"),
+            battery: battery.and_then(|(_, percent)| percent),
+        }
+    }
+
+    // Memory per program, processes with the same name counted together,
+    // heaviest first. Only memory is refreshed, which is cheap next to a
+    // full process refresh.
+    pub fn programs(&mut self) -> Vec<(String, u64)> {
+        self.sys.refresh_processes_specifics(ProcessRefreshKind::new());
+        let mut memory: HashMap<&str, u64> = HashMap::new();
+        for process in self.sys.processes().values() {
+            *memory.entry(process.name()).or_default() += process.memory();
+        }
+        let mut programs: Vec<(String, u64)> = memory.into_iter().map(|(name, bytes)| (name.to_string(), bytes)).collect();
+        programs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        programs
+    }
+
+    fn gpu_load(&self) -> Option<f32> {
+        let text = match self.gpu.as_ref()? {
+            GpuSource::Sysfs(path) => fs::read_to_string(path).ok()?,
+            GpuSource::NvidiaSmi => {
+                let output = Command::new("nvidia-smi").arg("--query-gpu=utilization.gpu").arg("--format=csv,noheader,nounits").output().ok()?;
+                String::from_utf8_lossy(&output.stdout).to_string()
+            }
+        };
+        parse_gpu_load(&text)
+    }
+}
+
+// nvidia-smi prints a line per GPU; the first is the one that's shown
+fn parse_gpu_load(text: &str) -> Option<f32> {
+    text.lines().next()?.trim().parse().ok()
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Sampler::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::Fact;
+
+    #[test]
+    fn ram_percent_of_total() {
+        let sample = Sample { ram_used: 3 << 30, ram_total: 4 << 30, ..Sample::default() };
+        assert_eq!(sample.ram_percent(), 75.0);
+        assert_eq!(Sample::default().ram_percent(), 0.0);
+    }
+
+    #[test]
+    fn records_readings_it_has() {
+        let sample = Sample { cpu: 42.0, ram_used: 1 << 30, ram_total: 4 << 30, swap_used: 2 << 30, gpu: Some(10.0), on_battery: true, ..Sample::default() };
+        let mut snapshot = Snapshot::default();
+        sample.record(&mut snapshot);
+        assert_eq!(snapshot.get("live.cpu"), Some(&Fact::Number(42.0)));
+        assert_eq!(snapshot.get("live.ram"), Some(&Fact::Number(25.0)));
+        assert_eq!(snapshot.get("live.swap_gb"), Some(&Fact::Number(2.0)));
+        assert_eq!(snapshot.get("live.gpu"), Some(&Fact::Number(10.0)));
+        assert_eq!(snapshot.get("power.on_battery"), Some(&Fact::Bool(true)));
+        // No sensor or battery, no fact
+        assert_eq!(snapshot.get("live.temperature"), None);
+        assert_eq!(snapshot.get("power.battery_percent"), None);
+    }
+
+    #[test]
+    fn gpu_load_from_the_first_line() {
+        assert_eq!(parse_gpu_load("37\n"), Some(37.0));
+        assert_eq!(parse_gpu_load(" 12 \n98\n"), Some(12.0));
+        assert_eq!(parse_gpu_load("[N/A]\n"), None);
+        assert_eq!(parse_gpu_load(""), None);
+    }
+}
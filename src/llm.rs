@@ -0,0 +1,92 @@
+use crate::config::LlmConfig;
+use crate::roast::{Mode, Tone};
+use crate::snapshot::Snapshot;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+const SYSTEM_PROMPT: &str = "You are rust_roast, a command-line tool that roasts people's computers. \
+You get a JSON object with facts about a machine (or a Rust project, or a git repository) under \"snapshot\". \
+Write short, funny one-liners about those facts. \"severity\" is how harsh to be: gentle is friendly teasing, \
+standard is a proper roast, savage holds nothing back. When \"workplace_safe\" is true, only joke about the \
+hardware and software, never about the person. When \"mode\" is \"toast\", write sincere compliments instead; \
+when it is \"mixed\", follow each roast with a backhanded compliment on the same line. Write in the language \
+given by \"language\". Reply with exactly \"count\" lines, one per line, with no numbering, bullets or other text.";
+
+// What the model is told about the run
+pub struct Request<'a> {
+    pub snapshot: &'a Snapshot,
+    pub tone: Tone,
+    pub mode: Mode,
+    // Language tag, e.g. "de"; English when None
+    pub language: Option<&'a str>,
+    pub seed: Option<u64>,
+}
+
+// "http://localhost:8080/v1" -> "http://localhost:8080/v1/chat/completions"
+fn completions_url(endpoint: &str) -> String {
+    format!("{}/chat/completions", endpoint.trim_end_matches('/'))
+}
+
+// Chat completion request body for an OpenAI-compatible API
+pub fn body(config: &LlmConfig, request: &Request) -> Value {
+    let facts = json!({
+        "severity": request.tone.severity,
+        "workplace_safe": request.tone.workplace_safe,
+        "mode": request.mode,
+        "language": request.language.unwrap_or("en"),
+        "count": config.roasts,
+        "snapshot": request.snapshot,
+    });
+    let mut body = json!({
+        "model": config.model,
+        "messages": [
+            { "role": "system", "content": SYSTEM_PROMPT },
+            { "role": "user", "content": facts.to_string() },
+        ],
+    });
+    if let Some(seed) = request.seed {
+        body["seed"] = json!(seed);
+        body["temperature"] = json!(0);
+    }
+    body
+}
+
+// "1. text", "2) text", "- text" and "* text" -> "text"
+fn strip_marker(line: &str) -> &str {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let rest = if digits > 0 { line[digits..].strip_prefix(['.', ')']) } else { line.strip_prefix(['-', '*']) };
+    match rest {
+        Some(rest) if rest.starts_with(' ') => rest.trim_start(),
+        _ => line,
+    }
+}
+
+// The lines of the first choice, without list markers or wrapping quotes
+pub fn parse_reply(reply: &Value, count: usize) -> Result<Vec<String>, String> {
+    let content = reply["choices"][0]["message"]["content"]
+        .as_str()
+        .ok_or_else(|| "reply has no choices[0].message.content".to_string())?;
+    let roasts: Vec<String> = content
+        .lines()
+        .map(|line| strip_marker(line.trim()).trim_matches('"').trim().to_string())
+        .filter(|line| !line.is_empty())
+        .take(count)
+        .collect();
+    if roasts.is_empty() {
+        return Err("reply was empty".to_string());
+    }
+    Ok(roasts)
+}
+
+// Asks the model for this run's roasts
+pub fn roasts(config: &LlmConfig, request: &Request) -> Result<Vec<String>, String> {
+    let endpoint = config.endpoint.as_deref().ok_or_else(|| "no endpoint set (--endpoint or [llm] endpoint)".to_string())?;
+    let agent = ureq::AgentBuilder::new().timeout(Duration::from_secs(config.timeout_secs)).build();
+    let reply: Value = agent
+        .post(&completions_url(endpoint))
+        .send_json(body(config, request))
+        .map_err(|err| err.to_string())?
+        .into_json()
+        .map_err(|err| format!("reply isn't JSON: {}", err))?;
+    parse_reply(&reply, config.roasts)
+}
@@ -0,0 +1,150 @@
+use crate::config::config_dir;
+use crate::distro::OsRelease;
+use crate::theme::{self, Color, Style};
+use serde::Deserialize;
+use std::fs;
+use unicode_width::UnicodeWidthStr;
+
+// Logos shipped with the binary, by name: distros by os-release ID, then CPU
+// vendors, then Tux for everything else
+pub const BUILTIN_LOGOS: &[(&str, &str)] = &[
+    ("alpine", include_str!("../logos/alpine.toml")),
+    ("arch", include_str!("../logos/arch.toml")),
+    ("debian", include_str!("../logos/debian.toml")),
+    ("fedora", include_str!("../logos/fedora.toml")),
+    ("gentoo", include_str!("../logos/gentoo.toml")),
+    ("linuxmint", include_str!("../logos/linuxmint.toml")),
+    ("manjaro", include_str!("../logos/manjaro.toml")),
+    ("nixos", include_str!("../logos/nixos.toml")),
+    ("ubuntu", include_str!("../logos/ubuntu.toml")),
+    ("amd", include_str!("../logos/amd.toml")),
+    ("apple", include_str!("../logos/apple.toml")),
+    ("arm", include_str!("../logos/arm.toml")),
+    ("intel", include_str!("../logos/intel.toml")),
+    ("tux", include_str!("../logos/tux.toml")),
+];
+
+// A logo file: the art, with "${1}", "${2}", ... switching to that entry of
+// `colors`. Raw ANSI escapes in the art are passed through as well.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LogoFile {
+    #[serde(default)]
+    colors: Vec<String>,
+    art: String,
+}
+
+// A logo ready to print: each line with its escape codes, and the widest
+// line's width in columns
+#[derive(Debug, Clone, Default)]
+pub struct Logo {
+    pub lines: Vec<(String, usize)>,
+    pub width: usize,
+}
+
+// `text` without ANSI escape sequences
+pub fn strip_ansi(text: &str) -> String {
+    let mut plain = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            plain.push(c);
+            continue;
+        }
+        // ESC [ parameters final-byte; the final byte is in @..~
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    plain
+}
+
+fn parse(text: &str) -> Result<Logo, String> {
+    let file: LogoFile = toml::from_str(text).map_err(|e| e.to_string().trim().to_string())?;
+    let styles = file
+        .colors
+        .iter()
+        .map(|color| Color::parse(color).map(|color| Style { fg: Some(color), bold: true, ..Style::default() }))
+        .collect::<Result<Vec<Style>, String>>()?;
+    let enabled = theme::current().enabled;
+    let mut logo = Logo::default();
+    let mut style = styles.first().cloned().unwrap_or_default();
+    for line in file.art.trim_end_matches('\n').lines() {
+        let line = if enabled { line.to_string() } else { strip_ansi(line) };
+        let mut painted = String::new();
+        let mut plain = String::new();
+        let mut rest = line.as_str();
+        // Colors carry over from one line to the next, like in the file
+        while let Some(start) = rest.find("${") {
+            let Some(end) = rest[start..].find('}') else { break };
+            let index: usize = rest[start + 2..start + end].parse().map_err(|_| format!("bad color marker \"{}\"", &rest[start..=start + end]))?;
+            let next = styles.get(index.wrapping_sub(1)).ok_or_else(|| format!("no color {} (the logo has {})", index, styles.len()))?;
+            painted.push_str(&style.paint(&rest[..start]));
+            plain.push_str(&rest[..start]);
+            style = next.clone();
+            rest = &rest[start + end + 1..];
+        }
+        painted.push_str(&style.paint(rest));
+        plain.push_str(rest);
+        let width = strip_ansi(&plain).width();
+        logo.width = logo.width.max(width);
+        logo.lines.push((painted, width));
+    }
+    Ok(logo)
+}
+
+fn builtin(name: &str) -> Option<&'static str> {
+    BUILTIN_LOGOS.iter().find(|(builtin, _)| *builtin == name).map(|(_, text)| *text)
+}
+
+// A logo by name: ~/.config/rust_roast/logos/<name>.toml, then the built-in
+// one, or a path to a logo file
+pub fn load(name: &str) -> Result<Logo, String> {
+    let user = config_dir().map(|dir| dir.join("logos").join(format!("{}.toml", name)));
+    let path = if name.ends_with(".toml") { Some(name.into()) } else { user.filter(|path| path.is_file()) };
+    if let Some(path) = path {
+        let text = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        return parse(&text).map_err(|e| format!("{}: {}", path.display(), e));
+    }
+    match builtin(name) {
+        Some(text) => parse(text),
+        None => Err(format!("unknown logo \"{}\"", name)),
+    }
+}
+
+// The logo for this machine: the distro's, or a parent distro's from
+// ID_LIKE, then the CPU vendor's, then Tux. Logos in the config directory
+// count, so a user can add one for a distro without a built-in logo.
+pub fn detect(os: Option<&OsRelease>, cpu_vendor: &str, cpu_brand: &str) -> String {
+    let has_logo = |name: &str| builtin(name).is_some() || config_dir().is_some_and(|dir| dir.join("logos").join(format!("{}.toml", name)).is_file());
+    if let Some(id) = os.and_then(|os| os.lineage().find(|id| !id.is_empty() && has_logo(id))) {
+        return id.to_string();
+    }
+    let cpu = format!("{} {}", cpu_vendor, cpu_brand).to_lowercase();
+    let vendor = if cpu.contains("intel") {
+        "intel"
+    } else if cpu.contains("amd") {
+        "amd"
+    } else if cpu.contains("apple") {
+        "apple"
+    } else if cpu.contains("arm") || cpu.contains("cortex") || std::env::consts::ARCH == "aarch64" {
+        "arm"
+    } else {
+        "tux"
+    };
+    vendor.to_string()
+}
+
+// The logo named `name`, or the detected one for "auto"; Tux (with a warning)
+// if it can't be loaded
+pub fn pick(name: &str, os: Option<&OsRelease>, cpu_vendor: &str, cpu_brand: &str) -> Logo {
+    let name = if name == "auto" { detect(os, cpu_vendor, cpu_brand) } else { name.to_string() };
+    load(&name).unwrap_or_else(|err| {
+        eprintln!("rust_roast: using the tux logo: {}", err);
+        load("tux").unwrap_or_default()
+    })
+}
@@ -1,7 +1,52 @@
 use sysinfo::{CpuExt, System, SystemExt};
-use colored::*;
+use clap::{Parser, Subcommand};
 use rand::seq::SliceRandom;
+use std::collections::HashSet;
+use std::path::PathBuf;
 use std::process::Command;
+use rust_roast::cli::RoastArgs;
+use rust_roast::logo::{self, Logo};
+use rust_roast::render::{Format, Layout};
+use rust_roast::roast::Roaster;
+use rust_roast::i18n::{tr, trf};
+use rust_roast::snapshot::Snapshot;
+use rust_roast::{combo, config, desktop, devenv, distro, git, hardware, lint, packages, pools, processes, render, score, tui, uptime, watch};
+
+#[derive(Parser)]
+#[command(name = "rust_roast", version, about = "Roasts your system specs")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+
+    #[command(flatten)]
+    roast: RoastArgs,
+
+    /// Output layout: plain, or fetch for specs and roasts beside a distro or CPU logo
+    #[arg(long, value_enum, global = true)]
+    layout: Option<Layout>,
+
+    /// Logo for --layout fetch: auto, a distro (arch, debian, ubuntu, ...), a CPU vendor (intel, amd, apple, arm), tux, or one of your own
+    #[arg(long, global = true)]
+    logo: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Roast the git repository in the current (or given) directory
+    Git {
+        path: Option<PathBuf>,
+    },
+    /// Check roast packs for broken files and duplicate or near-duplicate lines
+    Lint,
+    /// Live gauges for CPU, RAM, GPU and temperature, with a roast about whatever is maxed out
+    Tui,
+    /// Keep running and roast low battery, swapping, temperature spikes and RAM-hungry programs as they happen
+    Watch {
+        /// Print each event as a line of JSON instead of just the roast
+        #[arg(long)]
+        json: bool,
+    },
+}
 
 // Function to get GPU name (Linux specific, attempts lspci parsing)
 fn get_gpu_name() -> String {
@@ -37,415 +82,425 @@ fn is_laptop() -> bool {
     std::path::Path::new("/sys/class/power_supply/BAT1").exists() // Check BAT0 or BAT1
 }
 
+#[derive(PartialEq)]
+enum GpuTier {
+    Integrated,
+    LowEnd,
+    HighEnd,
+}
+
+// Rough GPU class from its name. Unknown GPUs count as low-end.
+fn gpu_tier(gpu_name: &str) -> GpuTier {
+    if gpu_name.contains("Integrated") || gpu_name.contains("Intel") || gpu_name.contains("AMD Radeon Graphics") && !gpu_name.contains("RX") {
+        GpuTier::Integrated
+    } else if gpu_name.contains("NVIDIA GeForce") || gpu_name.contains("AMD Radeon") {
+        // More nuanced check for dedicated GPUs
+        if gpu_name.contains("GT") || gpu_name.contains("RX 5") || gpu_name.contains("RX 4") {
+            GpuTier::LowEnd
+        } else {
+            GpuTier::HighEnd
+        }
+    } else {
+        GpuTier::LowEnd
+    }
+}
+
 fn main() {
+    let cli = Cli::parse();
+    let config = config::load_config();
+    if let Some(Commands::Lint) = cli.command {
+        lint_packs();
+        return;
+    }
+    cli.roast.init(&config);
+    let mut roaster = cli.roast.roaster(&config);
+    let format = cli.roast.format();
+    match cli.command {
+        Some(Commands::Git { path }) => roast_git(path.unwrap_or_else(|| PathBuf::from(".")), &config, &mut roaster),
+        Some(Commands::Tui) => {
+            if let Err(err) = tui::run(&mut roaster, &config.tui) {
+                eprintln!("rust_roast: {}", err);
+                std::process::exit(1);
+            }
+            roaster.history.save();
+            return;
+        }
+        Some(Commands::Watch { json }) => {
+            let result = watch::run(&mut roaster, &config.watch, json);
+            roaster.history.save();
+            if let Err(err) = result {
+                eprintln!("rust_roast: {}", err);
+                std::process::exit(1);
+            }
+            return;
+        }
+        _ => {
+            if format == Format::Text && cli.layout.unwrap_or(config.output.layout) == Layout::Fetch {
+                render::begin_fetch(system_logo(cli.logo.as_deref().unwrap_or(&config.output.logo)));
+            }
+            roast_system(&config, &mut roaster);
+        }
+    }
+    render::finish();
+    roaster.history.save();
+}
+
+// The logo named `name`, or for "auto" the one for this machine's distro or CPU
+fn system_logo(name: &str) -> Logo {
+    let mut sys = System::new();
+    sys.refresh_cpu();
+    let cpu = sys.cpus().first();
+    let vendor = cpu.map_or("", |cpu| cpu.vendor_id());
+    let brand = cpu.map_or("", |cpu| cpu.brand());
+    logo::pick(name, distro::read_os_release().as_ref(), vendor, brand)
+}
+
+fn lint_packs() {
+    let findings = lint::lint_packs();
+    for finding in &findings {
+        println!("{}: {}", finding.path.display(), finding.message);
+    }
+    if !findings.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+fn roast_git(path: PathBuf, config: &config::Config, roaster: &mut Roaster) {
+    let stats = match git::inspect(&path) {
+        Ok(stats) => stats,
+        Err(err) => {
+            eprintln!("rust_roast: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    render::specs_header(tr("header.repository"));
+    render::spec(tr("label.repository"), &stats.root.display().to_string());
+    render::spec(tr("label.commits"), &trf("value.commits", &[("commits", &stats.commits.to_string()), ("lazy", &stats.lazy_commits.to_string())]));
+    render::spec(tr("label.commit_hours"), &trf("value.commit_hours", &[("histogram", &stats.histogram())]));
+    render::spec(tr("label.branches"), &stats.branches.to_string());
+    if let Some((path, size)) = &stats.largest_blob {
+        render::spec(tr("label.largest_blob"), &format!("{} ({})", path, devenv::format_size(*size)));
+    }
+    render::spec(tr("label.rewrites"), &trf("value.rewrites", &[("forced", &stats.forced_updates.to_string()), ("rewrites", &stats.rewrites.to_string())]));
+
+    roaster.snapshot = git::snapshot(&stats);
+    if let Some(roasts) = roaster.llm_roasts() {
+        render::roasts(roaster, &roasts);
+        return;
+    }
+    let mut roasts = Vec::new();
+    for (name, builtin) in git::roast_pools(&stats, &config.git) {
+        roasts.extend(roaster.pick(name, builtin).map(|roast| roast.map(|text| git::fill(text, &stats))));
+    }
+    render::roasts(roaster, &roasts);
+}
+
+fn roast_system(config: &config::Config, roaster: &mut Roaster) {
     let mut sys = System::new_all();
     sys.refresh_all();
 
-    println!("{}", "\n--- System Specs ---".cyan().bold());
+    render::specs_header(tr("header.system"));
+    let mut snapshot = Snapshot::default();
 
     // --- CPU Info ---
     let cpu_count = sys.cpus().len();
     let cpu_brand = sys.cpus()[0].brand();
     let cpu_frequency = sys.cpus()[0].frequency(); // in MHz
-    println!("  {}: {} ({} cores @ {} MHz)", "CPU".green().bold(), cpu_brand.white(), cpu_count.to_string().white(), cpu_frequency.to_string().white());
+    render::spec(tr("label.cpu"), &trf("value.cpu", &[("brand", cpu_brand), ("cores", &cpu_count.to_string()), ("mhz", &cpu_frequency.to_string())]));
+    snapshot.set("cpu.brand", cpu_brand);
+    snapshot.set("cpu.cores", cpu_count);
+    snapshot.set("cpu.mhz", cpu_frequency);
 
     // --- RAM Info ---
     let total_ram_gb = sys.total_memory() as f64 / 1024.0 / 1024.0 / 1024.0;
     let used_ram_gb = sys.used_memory() as f64 / 1024.0 / 1024.0 / 1024.0;
-    println!("  {}: {{:.2}} GB total, {{:.2}} GB used", "RAM".green().bold(), total_ram_gb.to_string().white(), used_ram_gb.to_string().white());
+    render::spec(tr("label.ram"), &trf("value.ram", &[("total", &format!("{:.2}", total_ram_gb)), ("used", &format!("{:.2}", used_ram_gb))]));
+    snapshot.set("ram.total_gb", total_ram_gb);
+    snapshot.set("ram.used_gb", used_ram_gb);
 
     // --- GPU Info ---
     let gpu_name = get_gpu_name();
-    println!("  {}: {}", "GPU".green().bold(), gpu_name.white());
+    render::spec(tr("label.gpu"), &gpu_name);
+    snapshot.set("gpu.name", gpu_name.as_str());
+    snapshot.set("gpu.tier", match gpu_tier(&gpu_name) {
+        GpuTier::Integrated => "integrated",
+        GpuTier::LowEnd => "low_end",
+        GpuTier::HighEnd => "high_end",
+    });
 
     // --- OS Info ---
-    let os_name = sys.name().unwrap_or_else(|| "Unknown OS".to_string());
-    let os_version = sys.os_version().unwrap_or_else(|| "Unknown Version".to_string());
-    println!("  {}: {} {}", "OS".green().bold(), os_name.white(), os_version.white());
+    let now = uptime::now_secs();
+    let os_release = distro::read_os_release();
+    let os_support = os_release.as_ref().and_then(|os| distro::support(os, now));
+    let kernel = sys.kernel_version().unwrap_or_else(|| "unknown".to_string());
+    let kernel_age = distro::kernel_age(&kernel, now);
+    let os_name = match &os_release {
+        Some(os) if !os.pretty_name.is_empty() => os.pretty_name.clone(),
+        _ => {
+            let name = sys.name().unwrap_or_else(|| "Unknown OS".to_string());
+            let version = sys.os_version().unwrap_or_else(|| "Unknown Version".to_string());
+            format!("{} {}", name, version)
+        }
+    };
+    let eol_note = if os_support.as_ref().is_some_and(|support| support.eol) { tr("value.end_of_life") } else { "" };
+    render::spec(tr("label.os"), &trf("value.os", &[("name", &os_name), ("eol", eol_note), ("kernel", &kernel)]));
+    if let Some(os) = &os_release {
+        snapshot.set("os.id", os.id.as_str());
+        snapshot.set("os.version", os.version_id.as_str());
+        snapshot.set("os.rolling", os.is_rolling());
+    }
+    snapshot.set("os.eol", os_support.as_ref().is_some_and(|support| support.eol));
+    snapshot.set("kernel.version", kernel.as_str());
+    if let Some(age) = kernel_age {
+        snapshot.set("kernel.age_years", distro::kernel_age_years(age));
+    }
 
     // --- Form Factor ---
-    let form_factor = if is_laptop() { "Laptop" } else { "Desktop" };
-    println!("  {}: {}", "Form Factor".green().bold(), form_factor.white());
+    let form_factor = if is_laptop() { "laptop" } else { "desktop" };
+    render::spec(tr("label.form_factor"), tr(&format!("value.{}", form_factor)));
+    snapshot.set("form_factor", form_factor);
+
+    // --- Storage and Power ---
+    let hardware = hardware::gather();
+    let disks: Vec<&str> = [(hardware.has_ssd, "SSD"), (hardware.has_hdd, "HDD")].iter().filter(|(has, _)| *has).map(|(_, kind)| *kind).collect();
+    if !disks.is_empty() {
+        render::spec(tr("label.storage"), &disks.join(" + "));
+    }
+    if let Some(profile) = &hardware.power_profile {
+        let battery = match (hardware.on_battery, hardware.battery_percent) {
+            (true, Some(percent)) => trf("value.on_battery_percent", &[("percent", &percent.to_string())]),
+            (true, None) => tr("value.on_battery").to_string(),
+            _ => String::new(),
+        };
+        render::spec(tr("label.power"), &format!("{}{}", profile, battery));
+    }
+    if let Some(chassis) = &hardware.chassis {
+        snapshot.set("chassis", chassis.as_str());
+    }
+    snapshot.set("storage.hdd", hardware.has_hdd);
+    snapshot.set("storage.ssd", hardware.has_ssd);
+    if let Some(profile) = &hardware.power_profile {
+        snapshot.set("power.profile", profile.as_str());
+    }
+    snapshot.set("power.on_battery", hardware.on_battery);
+    if let Some(percent) = hardware.battery_percent {
+        snapshot.set("power.battery_percent", percent as u64);
+    }
 
-    println!("{}", "\n--- The Roast ---".red().bold());
+    // --- Packages ---
+    let inventory = packages::gather();
+    if !inventory.counts.is_empty() {
+        render::spec(tr("label.packages"), &inventory.summary());
+    }
+    snapshot.set("packages.total", inventory.total());
+    for (manager, count) in &inventory.counts {
+        snapshot.set(&format!("packages.{}", manager), *count);
+    }
+    snapshot.set("packages.cargo_bins", inventory.cargo_bins);
+
+    // --- Dev Environment ---
+    let dev_env = devenv::gather();
+    let shell_editor: Vec<&str> = [dev_env.shell.as_deref(), dev_env.editor.as_deref()].into_iter().flatten().collect();
+    if !shell_editor.is_empty() {
+        render::spec(tr("label.shell_editor"), &shell_editor.join(" / "));
+    }
+    let toolchains = dev_env.toolchain_summary();
+    if !toolchains.is_empty() {
+        render::spec(tr("label.toolchains"), &toolchains);
+    }
+    if let Some(shell) = &dev_env.shell {
+        snapshot.set("dev.shell", shell.as_str());
+    }
+    if let Some(editor) = &dev_env.editor {
+        snapshot.set("dev.editor", editor.as_str());
+    }
+    snapshot.set("dev.toolchains", dev_env.rust_toolchains.len());
+    snapshot.set("dev.nightly", dev_env.rust_toolchains.iter().any(|t| t.starts_with("nightly")));
+    snapshot.set("dev.cargo_registry_gb", dev_env.cargo_registry as f64 / 1024.0 / 1024.0 / 1024.0);
+
+    // --- Desktop Info ---
+    let desktop_info = desktop::gather(&sys);
+    let de_wm: Vec<&str> = [desktop_info.desktop.as_deref(), desktop_info.window_manager.as_deref()].into_iter().flatten().collect();
+    if !de_wm.is_empty() {
+        render::spec(tr("label.de_wm"), &de_wm.join(" / "));
+    }
+    if let Some(server) = &desktop_info.display_server {
+        render::spec(tr("label.display_server"), server);
+    }
+    for display in &desktop_info.displays {
+        render::spec(tr("label.display"), &format!("{} ({})", display, display.connector));
+    }
+    if let Some(desktop) = &desktop_info.desktop {
+        snapshot.set("desktop.name", desktop.as_str());
+    }
+    if let Some(wm) = &desktop_info.window_manager {
+        snapshot.set("desktop.wm", wm.as_str());
+    }
+    if let Some(server) = &desktop_info.display_server {
+        snapshot.set("desktop.server", server.to_lowercase());
+    }
+    snapshot.set("desktop.tiling", desktop_info.tiling);
+    snapshot.set("desktop.displays", desktop_info.displays.len());
+    if let Some(hz) = desktop_info.max_refresh_hz() {
+        snapshot.set("desktop.max_hz", hz);
+    }
+
+    // --- Uptime ---
+    let uptime_info = uptime::gather(&sys);
+    let uptime_value = trf("value.uptime", &[
+        ("uptime", &uptime::format_duration(uptime_info.uptime_secs)),
+        ("boots", &uptime_info.boots_last_day(now).to_string()),
+    ]);
+    render::spec(tr("label.uptime"), &uptime_value);
+    snapshot.set("uptime.days", uptime_info.uptime_days());
+    snapshot.set("uptime.boots_today", uptime_info.boots_last_day(now));
+
+    // --- Running Apps ---
+    let app_rules = processes::app_rules(&roaster.packs);
+    let running_apps = processes::inspect(&sys, &app_rules);
+    if let Some(app) = running_apps.first() {
+        let top_app = trf("value.top_app", &[
+            ("app", &app.label),
+            ("processes", &app.processes.to_string()),
+            ("share", &format!("{:.1}", app.mem_share * 100.0)),
+        ]);
+        render::spec(tr("label.top_app"), &top_app);
+        snapshot.set("app.top", app.id.as_str());
+        snapshot.set("app.mem_share", app.mem_share);
+        snapshot.set("app.cpu_share", app.cpu_share);
+    }
+    for app in &running_apps {
+        snapshot.set(&format!("app.{}", app.id), true);
+    }
+    roaster.snapshot = snapshot;
+    render::score(score::score(&roaster.snapshot));
+    if let Some(roasts) = roaster.llm_roasts() {
+        render::roasts(roaster, &roasts);
+        return;
+    }
 
     let mut roasts = Vec::new();
-    let mut rng = rand::thread_rng();
+
+    // --- Combo Roasts ---
+    // Mismatches between components go first and replace the roasts they cover
+    let combo_rules = combo::combo_rules(&roaster.packs);
+    let mut covered: HashSet<String> = HashSet::new();
+    for rule in combo::firing(&combo_rules, &roaster.snapshot) {
+        if let Some(roast) = roaster.pick_entry(&format!("combos.{}", rule.id), &rule.localized, &rule.roasts) {
+            roasts.push(roast.map(|text| combo::fill(text, &roaster.snapshot)));
+            covered.extend(rule.covers.iter().cloned());
+        }
+    }
 
     // --- CPU Roasts ---
-    let cpu_roasts_low = vec![
-        "Your CPU is so slow, it thinks \"loading screen\" is a feature, not a bug.",
-        "Did you find your CPU in a cereal box?",
-        "Your processor is still running on dial-up speed.",
-        "Is your CPU powered by a hamster on a wheel?",
-        "I've seen calculators with more processing power.",
-        "Your CPU is so old, it remembers when \"megahertz\" was impressive.",
-        "Does your CPU come with a built-in coffee break every time you open a tab?",
-        "It's not a CPU, it's a paperweight that occasionally computes.",
-        "Your CPU is the reason \"patience is a virtue\" was invented.",
-        "I bet your CPU still thinks Windows XP is cutting edge.",
-        "Your CPU is so bad, it makes a snail look like a cheetah.",
-        "Are you sure that's a CPU and not a potato with wires?",
-        "Your CPU's performance is a cry for help.",
-        "It's not throttling, it's just taking a very long nap.",
-        "Your CPU is the digital equivalent of waiting for paint to dry.",
-        "I heard your CPU is still trying to render the first frame of Pong.",
-        "Does your CPU need a nap after opening Notepad?",
-        "Your CPU is so outdated, it probably runs on steam.",
-        "The only thing fast about your CPU is how quickly it disappoints.",
-        "Your CPU is a master of procrastination.",
-        "It's not a bug, it's a feature... of your slow CPU.",
-        "Your CPU is the reason we have progress bars.",
-        "I've seen faster calculations on an abacus.",
-        "Your CPU is a monument to \"almost there\".",
-        "The only thing your CPU is good at is generating heat.",
-    ];
-    let cpu_roasts_mid = vec![
-        "Your CPU is trying its best, bless its little silicon heart. Almost there!",
-        "It's not the fastest, but at least it's not actively trying to sabotage you.",
-        "Your CPU is the definition of \"gets the job done... eventually.\"",
-        "A solid B- for effort, your CPU.",
-        "Your CPU is like a reliable old car: it runs, but don't ask it to win any races.",
-        "It's not a powerhouse, but it's not a complete embarrassment either.",
-        "Your CPU is the middle child of processors: always overlooked.",
-        "It's got enough cores to count your fingers, and maybe a few toes.",
-        "Your CPU is the reason \"good enough\" exists.",
-        "It's not breaking any records, but it's not breaking your bank either.",
-        "Your CPU is the beige of computing: functional, but unexciting.",
-        "It's got enough power for spreadsheets and existential dread.",
-        "Your CPU is the equivalent of a participation trophy.",
-        "It's not fast, it's not slow, it's just... there.",
-        "Your CPU is the background music of your computing life: always present, rarely noticed.",
-        "It's got enough oomph for basic tasks and questioning your life choices.",
-        "Your CPU is the definition of \"average.\"",
-        "It's not a beast, but it's not a total slouch either.",
-        "Your CPU is the reason you have time to make a sandwich while waiting.",
-        "It's got enough power to run your OS and a mild sense of regret.",
-        "Your CPU is the unsung hero of \"just barely.\"",
-        "It's not a Ferrari, but it's not a tricycle either.",
-        "Your CPU is the definition of \"meh.\"",
-        "It's got enough power to run your favorite retro games.",
-        "Your CPU is the reason you appreciate fast computers.",
-    ];
-    let cpu_roasts_high = vec![
-        "Your CPU is decent, but are you really pushing it, or just browsing memes?",
-        "You've got the power, but do you have the skills to use it?",
-        "Your CPU is so fast, it finishes tasks before you even think of them.",
-        "Did you buy that CPU just to flex on your friends?",
-        "Your processor is a beast, but are you taming it or just letting it nap?",
-        "I bet your CPU has its own fan club.",
-        "Your CPU is so powerful, it probably runs on pure ambition.",
-        "It's not a CPU, it's a supercomputer in disguise.",
-        "Your CPU is the reason \"lag\" is just a myth to you.",
-        "I heard your CPU can render the entire universe in 8K.",
-        "Your CPU is so good, it makes other CPUs cry.",
-        "Are you sure that's a CPU and not a quantum computer?",
-        "Your CPU's performance is a threat to national security.",
-        "It's not throttling, it's just taking a very short power nap.",
-        "Your CPU is the digital equivalent of a rocket ship.",
-        "I heard your CPU is still trying to render the first frame of the multiverse.",
-        "Does your CPU need a challenge after compiling the Linux kernel?",
-        "Your CPU is so advanced, it probably runs on dark matter.",
-        "The only thing slow about your CPU is how long it takes to find a worthy task.",
-        "Your CPU is a master of efficiency.",
-        "It's not a bug, it's a feature... of your lightning-fast CPU.",
-        "Your CPU is the reason we don't have progress bars.",
-        "I've seen slower calculations on a supercomputer.",
-        "Your CPU is a monument to \"overkill\".",
-        "The only thing your CPU is good at is making other CPUs jealous.",
-    ];
-
-    if cpu_count < 4 || cpu_frequency < 2000 {
-        roasts.push(cpu_roasts_low.choose(&mut rng).unwrap().to_string());
-    } else if cpu_count < 8 || cpu_frequency < 3000 {
-        roasts.push(cpu_roasts_mid.choose(&mut rng).unwrap().to_string());
-    } else {
-        roasts.push(cpu_roasts_high.choose(&mut rng).unwrap().to_string());
+    if !covered.contains("cpu") {
+        if cpu_count < 4 || cpu_frequency < 2000 {
+            roasts.extend(roaster.pick("cpu.low", pools::CPU_LOW_ROASTS));
+        } else if cpu_count < 8 || cpu_frequency < 3000 {
+            roasts.extend(roaster.pick("cpu.mid", pools::CPU_MID_ROASTS));
+        } else {
+            roasts.extend(roaster.pick("cpu.high", pools::CPU_HIGH_ROASTS));
+        }
     }
 
     // --- RAM Roasts ---
-    let ram_roasts_low = vec![
-        "Your RAM is so low, you probably have to close your browser to open a text editor.",
-        "Is your RAM powered by a single gerbil on a tiny treadmill?",
-        "I've seen more memory in a sticky note.",
-        "Your RAM is the reason \"out of memory\" is your favorite error message.",
-        "Does your computer run on hopes and dreams, because it's not running on RAM.",
-        "Your RAM is so small, it gets lost in a single tab.",
-        "I bet your RAM still thinks 256MB is a lot.",
-        "Your RAM is the digital equivalent of a goldfish's memory.",
-        "It's not multitasking, it's just desperately trying to remember one thing at a time.",
-        "Your RAM is the reason your computer sounds like a jet engine taking off.",
-        "I've seen faster data retrieval from a stone tablet.",
-        "Your RAM is a bottleneck so severe, it's practically a chokehold.",
-        "Does your RAM need a nap after opening the task manager?",
-        "Your RAM is the reason you're still using Internet Explorer.",
-        "The only thing fast about your RAM is how quickly it fills up.",
-        "Your RAM is a master of forgetting.",
-        "It's not a bug, it's a feature... of your tiny RAM.",
-        "Your RAM is the reason we have swap files.",
-        "I've seen more efficient memory management in a toddler's brain.",
-        "Your RAM is a monument to \"just barely not enough\".",
-        "The only thing your RAM is good at is making you upgrade.",
-        "Your RAM is so small, it can't even hold a single thought.",
-        "I bet your RAM is still trying to load the first pixel of your desktop.",
-        "Your RAM is the reason you have to restart your computer every hour.",
-        "The only thing your RAM is good at is making you frustrated.",
-    ];
-    let ram_roasts_mid = vec![
-        "Your RAM is like your memory of last week's tasks: barely enough to get by.",
-        "It's got enough memory for a few tabs and a mild existential crisis.",
-        "Your RAM is the definition of \"adequate.\"",
-        "A solid C+ for effort, your RAM.",
-        "Your RAM is like a small apartment: enough space, but you're always bumping into things.",
-        "It's not a lot, but it's not nothing either.",
-        "Your RAM is the middle child of memory: always overlooked.",
-        "It's got enough memory to run your OS and a few background apps.",
-        "Your RAM is the reason \"close some programs\" is your mantra.",
-        "It's not breaking any speed records, but it's not breaking your budget either.",
-        "Your RAM is the beige of memory: functional, but unexciting.",
-        "It's got enough memory for basic tasks and a few open documents.",
-        "Your RAM is the equivalent of a participation trophy in the memory Olympics.",
-        "It's not fast, it's not slow, it's just... there.",
-        "Your RAM is the background noise of your computing life: always present, rarely noticed.",
-        "It's got enough memory for a few browser tabs and a mild sense of regret.",
-        "Your RAM is the definition of \"average.\"",
-        "It's not a beast, but it's not a total slouch either.",
-        "Your RAM is the reason you have time to make a sandwich while waiting for apps to load.",
-        "It's got enough memory to run your OS and a mild sense of disappointment.",
-        "Your RAM is the unsung hero of \"just barely enough.\"",
-        "It's not a superhighway, but it's not a dirt road either.",
-        "Your RAM is the definition of \"meh.\"",
-        "It's got enough memory to run your favorite retro games.",
-        "Your RAM is the reason you appreciate fast memory.",
-    ];
-    let ram_roasts_high = vec![
-        "Plenty of RAM, but are you using it for anything productive, or just 50 Chrome tabs?",
-        "You've got the memory, but do you have the applications to fill it?",
-        "Your RAM is so vast, it probably has its own zip code.",
-        "Did you buy that RAM just to flex on your friends?",
-        "Your memory is a beast, but are you taming it or just letting it idle?",
-        "I bet your RAM has its own fan club.",
-        "Your RAM is so powerful, it probably runs on pure ambition.",
-        "It's not RAM, it's a data ocean.",
-        "Your RAM is the reason \"out of memory\" is just a legend to you.",
-        "I heard your RAM can store the entire internet.",
-        "Your RAM is so good, it makes other RAM modules cry.",
-        "Are you sure that's RAM and not a quantum storage device?",
-        "Your RAM's capacity is a threat to national security.",
-        "It's not filling up, it's just taking a very short data nap.",
-        "Your RAM is the digital equivalent of a black hole for data.",
-        "I heard your RAM is still trying to load the first byte of the multiverse.",
-        "Does your RAM need a challenge after opening every program you own?",
-        "Your RAM is so advanced, it probably runs on dark matter.",
-        "The only thing slow about your RAM is how long it takes to find a worthy task.",
-        "Your RAM is a master of retention.",
-        "It's not a bug, it's a feature... of your massive RAM.",
-        "Your RAM is the reason we don't have swap files.",
-        "I've seen slower data storage on a supercomputer.",
-        "Your RAM is a monument to \"overkill\".",
-        "The only thing your RAM is good at is making other RAM modules jealous.",
-    ];
-
-    if total_ram_gb < 8.0 {
-        roasts.push(ram_roasts_low.choose(&mut rng).unwrap().to_string());
-    } else if total_ram_gb < 16.0 {
-        roasts.push(ram_roasts_mid.choose(&mut rng).unwrap().to_string());
-    } else {
-        roasts.push(ram_roasts_high.choose(&mut rng).unwrap().to_string());
+    if !covered.contains("ram") {
+        if total_ram_gb < 8.0 {
+            roasts.extend(roaster.pick("ram.low", pools::RAM_LOW_ROASTS));
+        } else if total_ram_gb < 16.0 {
+            roasts.extend(roaster.pick("ram.mid", pools::RAM_MID_ROASTS));
+        } else {
+            roasts.extend(roaster.pick("ram.high", pools::RAM_HIGH_ROASTS));
+        }
     }
 
     // --- GPU Roasts ---
-    let gpu_roasts_integrated = vec![
-        "Your GPU is so weak, it struggles to render a single pixel in 4K. Maybe try ASCII art?",
-        "Is your GPU powered by a single AA battery?",
-        "I've seen more graphical fidelity in a flipbook.",
-        "Your GPU is the reason \"low settings\" is your default.",
-        "Does your computer run on hopes and dreams, because it's not running on a dedicated GPU.",
-        "Your GPU is so small, it gets lost in a single texture.",
-        "I bet your GPU still thinks 640x480 is high resolution.",
-        "Your GPU is the digital equivalent of a crayon drawing.",
-        "It's not gaming, it's just desperately trying to display one frame at a time.",
-        "Your GPU is the reason your computer sounds like a jet engine taking off when you open Solitaire.",
-        "I've seen faster rendering from a cave painting.",
-        "Your GPU is a bottleneck so severe, it's practically a chokehold on your pixels.",
-        "Does your GPU need a nap after rendering a static webpage?",
-        "Your GPU is the reason you're still playing games from the 90s.",
-        "The only thing fast about your GPU is how quickly it disappoints.",
-        "Your GPU is a master of pixelation.",
-        "It's not a bug, it's a feature... of your integrated graphics.",
-        "Your GPU is the reason we have \"minimum requirements\".",
-        "I've seen more efficient graphics processing in a toaster.",
-        "Your GPU is a monument to \"just barely not enough pixels\".",
-        "The only thing your GPU is good at is making you upgrade.",
-        "Your GPU is so weak, it can't even render a single thought.",
-        "I bet your GPU is still trying to load the first pixel of your desktop background.",
-        "Your GPU is the reason you have to restart your computer after watching a YouTube video.",
-        "The only thing your GPU is good at is making you frustrated.",
-    ];
-    let gpu_roasts_low_end = vec![
-        "Your GPU is trying its best, bless its little silicon heart. Almost there!",
-        "It's not the fastest, but at least it's not actively trying to sabotage your framerate.",
-        "Your GPU is the definition of \"gets the job done... eventually.\"",
-        "A solid C- for effort, your GPU.",
-        "Your GPU is like a reliable old car: it runs, but don't ask it to win any graphical races.",
-        "It's not a powerhouse, but it's not a complete embarrassment either.",
-        "Your GPU is the middle child of graphics cards: always overlooked.",
-        "It's got enough VRAM to count your fingers, and maybe a few toes.",
-        "Your GPU is the reason \"good enough\" exists for low settings.",
-        "It's not breaking any records, but it's not breaking your bank either.",
-        "Your GPU is the beige of graphics: functional, but unexciting.",
-        "It's got enough power for spreadsheets and mild graphical regret.",
-        "Your GPU is the equivalent of a participation trophy in the graphics Olympics.",
-        "It's not fast, it's not slow, it's just... there.",
-        "Your GPU is the background music of your gaming life: always present, rarely noticed.",
-        "It's got enough oomph for basic tasks and questioning your graphical choices.",
-        "Your GPU is the definition of \"average.\"",
-        "It's not a beast, but it's not a total slouch either.",
-        "Your GPU is the reason you have time to make a sandwich while waiting for textures to load.",
-        "It's got enough power to run your OS and a mild sense of disappointment in your framerate.",
-        "Your GPU is the unsung hero of \"just barely playable.\"",
-        "It's not a Ferrari, but it's not a tricycle either.",
-        "Your GPU is the definition of \"meh.\" for gaming.",
-        "It's got enough power to run your favorite retro games in glorious pixelation.",
-        "Your GPU is the reason you appreciate fast graphics cards.",
-    ];
-    let gpu_roasts_high_end = vec![
-        "Your GPU is probably fine, but let's be honest, you're not playing Cyberpunk on max settings, are you?",
-        "You've got the graphical power, but do you have the games to push it?",
-        "Your GPU is so fast, it renders frames before you even think of them.",
-        "Did you buy that GPU just to flex on your friends?",
-        "Your graphics card is a beast, but are you taming it or just letting it idle?",
-        "I bet your GPU has its own fan club.",
-        "Your GPU is so powerful, it probably runs on pure ambition and RGB.",
-        "It's not a GPU, it's a pixel-pushing supercomputer in disguise.",
-        "Your GPU is the reason \"lag\" is just a myth to you in games.",
-        "I heard your GPU can render the entire universe in 8K, with ray tracing.",
-        "Your GPU is so good, it makes other GPUs cry in envy.",
-        "Are you sure that's a GPU and not a quantum rendering device?",
-        "Your GPU's performance is a threat to national security for its sheer power.",
-        "It's not throttling, it's just taking a very short rendering nap.",
-        "Your GPU is the digital equivalent of a rocket ship for graphics.",
-        "I heard your GPU is still trying to render the first frame of the multiverse in real-time.",
-        "Does your GPU need a challenge after rendering every game you own at max settings?",
-        "Your GPU is so advanced, it probably runs on dark matter and unicorn tears.",
-        "The only thing slow about your GPU is how long it takes to find a worthy graphical task.",
-        "Your GPU is a master of visual fidelity.",
-        "It's not a bug, it's a feature... of your lightning-fast GPU.",
-        "Your GPU is the reason we don't have graphical limitations.",
-        "I've seen slower rendering on a supercomputer.",
-        "Your GPU is a monument to \"overkill\" in the best way possible.",
-        "The only thing your GPU is good at is making other GPUs jealous with its performance.",
-    ];
+    if !covered.contains("gpu") {
+        match gpu_tier(&gpu_name) {
+            GpuTier::Integrated => roasts.extend(roaster.pick("gpu.integrated", pools::GPU_INTEGRATED_ROASTS)),
+            GpuTier::LowEnd => roasts.extend(roaster.pick("gpu.low_end", pools::GPU_LOW_END_ROASTS)),
+            GpuTier::HighEnd => roasts.extend(roaster.pick("gpu.high_end", pools::GPU_HIGH_END_ROASTS)),
+        }
+    }
 
-    if gpu_name.contains("Integrated") || gpu_name.contains("Intel") || gpu_name.contains("AMD Radeon Graphics") && !gpu_name.contains("RX") {
-        roasts.push(gpu_roasts_integrated.choose(&mut rng).unwrap().to_string());
-    } else if gpu_name.contains("NVIDIA GeForce") || gpu_name.contains("AMD Radeon") {
-        // More nuanced check for dedicated GPUs
-        if gpu_name.contains("GT") || gpu_name.contains("RX 5") || gpu_name.contains("RX 4") {
-            roasts.push(gpu_roasts_low_end.choose(&mut rng).unwrap().to_string());
+    // --- Form Factor Roasts ---
+    if !covered.contains("form_factor") {
+        if form_factor == "laptop" {
+            roasts.extend(roaster.pick("form_factor.laptop", pools::LAPTOP_ROASTS));
         } else {
-            roasts.push(gpu_roasts_high_end.choose(&mut rng).unwrap().to_string());
+            roasts.extend(roaster.pick("form_factor.desktop", pools::DESKTOP_ROASTS));
         }
-    } else {
-        roasts.push(gpu_roasts_low_end.choose(&mut rng).unwrap().to_string()); // Default to low-end if unknown
     }
 
-    // --- Form Factor Roasts ---
-    let form_factor_roasts_laptop = vec![
-        "Ah, a laptop user. Enjoy your portable space heater.",
-        "Your laptop's battery life is shorter than your attention span.",
-        "I bet your laptop fan sounds like a jet engine taking off.",
-        "Portable power, portable problems.",
-        "Your laptop is great for gaming... if the game is Solitaire.",
-        "The only thing thinner than your laptop is your patience for its performance.",
-        "Your laptop is so light, it probably floats away when you open too many tabs.",
-        "I've seen more desk space on a postage stamp than you have with that laptop.",
-        "Your laptop's keyboard probably has more crumbs than keys.",
-        "The only thing your laptop is good at is burning your thighs.",
-        "Your laptop is a master of thermal throttling.",
-        "It's not a bug, it's a feature... of your laptop's overheating.",
-        "Your laptop is the reason we have cooling pads.",
-        "I've seen more efficient cooling in a desert.",
-        "Your laptop is a monument to \"almost a desktop\".",
-        "The only thing your laptop is good at is making you wish you had a desktop.",
-        "Your laptop is so small, it can't even hold a single thought.",
-        "I bet your laptop is still trying to load the first pixel of your desktop background.",
-        "Your laptop is the reason you have to restart your computer every hour.",
-        "The only thing your laptop is good at is making you frustrated.",
-        "Your laptop is the digital equivalent of a hot potato.",
-        "I heard your laptop can cook an egg on its keyboard.",
-        "Your laptop is so quiet, you can hear the dust bunnies breeding inside.",
-        "The only thing your laptop is good at is being a portable disappointment.",
-        "Your laptop is the reason you carry a power bank everywhere.",
-    ];
-    let form_factor_roasts_desktop = vec![
-        "A desktop user, I see. Enjoy being tethered to your desk, cave dweller.",
-        "Your desktop is so big, it probably has its own zip code.",
-        "I bet your desktop fan sounds like a wind tunnel.",
-        "Immovable power, immovable problems.",
-        "Your desktop is great for gaming... if the game is \"find the dust bunny\".",
-        "The only thing wider than your desktop is your waistline from sitting all day.",
-        "Your desktop is so heavy, it probably has its own gravitational pull.",
-        "I've seen more portability in a refrigerator than in your desktop.",
-        "Your desktop's cables probably have more knots than a sailor's convention.",
-        "The only thing your desktop is good at is collecting dust.",
-        "Your desktop is a master of cable management nightmares.",
-        "It's not a bug, it's a feature... of your desktop's massive footprint.",
-        "Your desktop is the reason we have bigger desks.",
-        "I've seen more efficient space utilization in a landfill.",
-        "Your desktop is a monument to \"overkill\".",
-        "The only thing your desktop is good at is making you wish you had a laptop.",
-        "Your desktop is so big, it can't even fit in a single thought.",
-        "I bet your desktop is still trying to load the first pixel of your desktop background.",
-        "Your desktop is the reason you have to restart your computer every hour.",
-        "The only thing your desktop is good at is making you frustrated.",
-        "Your desktop is the digital equivalent of a brick house.",
-        "I heard your desktop can heat an entire room in winter.",
-        "Your desktop is so loud, you can hear it from the next county.",
-        "The only thing your desktop is good at is being a stationary disappointment.",
-        "Your desktop is the reason you never leave your house.",
-    ];
-
-    if form_factor == "Laptop" {
-        roasts.push(form_factor_roasts_laptop.choose(&mut rng).unwrap().to_string());
-    } else {
-        roasts.push(form_factor_roasts_desktop.choose(&mut rng).unwrap().to_string());
+    // --- OS Roasts ---
+    if !covered.contains("os") {
+        if let Some(os) = &os_release {
+            let (name, pool) = distro::roast_pool(os, os_support.as_ref());
+            roasts.extend(roaster.pick(name, pool).map(|roast| roast.map(|text| distro::fill(text, os, &kernel, kernel_age))));
+            if kernel_age.is_some_and(|age| distro::kernel_age_years(age) >= config.os.old_kernel_years) {
+                roasts.extend(roaster.pick("os.kernel", distro::OLD_KERNEL_ROASTS).map(|roast| roast.map(|text| distro::fill(text, os, &kernel, kernel_age))));
+            }
+        }
+    }
+
+    // --- Package Roasts ---
+    if !covered.contains("packages") {
+        if inventory.count("snap") > 0 {
+            roasts.extend(roaster.pick("packages.snap", packages::SNAP_ROASTS).map(|roast| roast.map(|text| packages::fill(text, &inventory))));
+        }
+        if inventory.cargo_bins >= config.packages.cargo_bins {
+            roasts.extend(roaster.pick("packages.cargo", packages::CARGO_ROASTS).map(|roast| roast.map(|text| packages::fill(text, &inventory))));
+        } else if inventory.total() >= config.packages.bloat {
+            roasts.extend(roaster.pick("packages.bloat", packages::BLOAT_ROASTS).map(|roast| roast.map(|text| packages::fill(text, &inventory))));
+        }
+    }
+
+    // --- Dev Roasts ---
+    // One roast from a random dev pool that has something at this severity
+    if !covered.contains("dev") {
+        let mut dev_pools = devenv::roast_pools(&dev_env, &config.dev);
+        dev_pools.shuffle(&mut roaster.rng);
+        let dev_roast = dev_pools.iter().find_map(|(name, pool)| roaster.pick(name, pool));
+        roasts.extend(dev_roast.map(|roast| roast.map(|text| devenv::fill(text, &dev_env))));
+    }
+
+    // --- Desktop Roasts ---
+    if !covered.contains("desktop") {
+        if desktop_info.tiling {
+            roasts.extend(roaster.pick("desktop.tiling", desktop::TILING_WM_ROASTS).map(|roast| roast.map(|text| desktop::fill(text, &desktop_info))));
+        }
+        if desktop_info.has_low_res_panel() {
+            roasts.extend(roaster.pick("desktop.low_res", desktop::LOW_RES_ROASTS).map(|roast| roast.map(|text| desktop::fill(text, &desktop_info))));
+        }
+        let gamer = gpu_tier(&gpu_name) == GpuTier::HighEnd || running_apps.iter().any(|app| app.id == "steam" || app.id == "launcher");
+        if gamer && desktop_info.max_refresh_hz().is_some_and(|hz| hz < 61.0) {
+            roasts.extend(roaster.pick("desktop.sixty_hz", desktop::SIXTY_HZ_GAMER_ROASTS).map(|roast| roast.map(|text| desktop::fill(text, &desktop_info))));
+        }
+    }
+
+    // --- Uptime Roasts ---
+    if !covered.contains("uptime") {
+        if let Some((name, pool)) = uptime::roast_pool(&uptime_info, &config.uptime, now) {
+            roasts.extend(roaster.pick(name, pool).map(|roast| roast.map(|text| uptime::fill(text, &uptime_info, now))));
+        }
+    }
+
+    // --- Running App Roasts ---
+    if !covered.contains("app") {
+        if let Some(app) = running_apps.first() {
+            if let Some(rule) = app_rules.iter().find(|rule| rule.id == app.id) {
+                if let Some(roast) = roaster.pick_entry(&format!("apps.{}", rule.id), &rule.localized, &rule.roasts) {
+                    roasts.push(roast.map(|text| processes::fill(text, app)));
+                }
+            }
+        }
     }
 
     // --- General Roasts (if not enough specific roasts) ---
     if roasts.len() < 3 {
-        let general_roasts = vec![
-            "Overall, your system is a testament to \"it works, mostly.\" Don't worry, we've all been there.",
-            "Your system is... adequate. Just like your social life.",
-            "I've seen better specs on a toaster. Just kidding... mostly.",
-            "Your computer is like a fine wine: it gets slower with age.",
-            "The only thing fast about your system is how quickly it disappoints.",
-            "Your system is a master of procrastination.",
-            "It's not a bug, it's a feature... of your unique setup.",
-            "Your system is the reason we have progress bars.",
-            "I've seen faster calculations on an abacus.",
-            "Your system is a monument to \"almost there\".",
-            "The only thing your system is good at is generating heat.",
-            "Your computer is like a bad relationship: constantly letting you down.",
-            "I bet your system still thinks Windows 95 is cutting edge.",
-            "Your system is the digital equivalent of waiting for paint to dry.",
-            "It's not slow, it's just taking a very long nap.",
-            "Your system is the reason you have time to make a sandwich while waiting.",
-            "I heard your system is still trying to render the first frame of Pong.",
-            "Does your system need a nap after opening Notepad?",
-            "Your system is so outdated, it probably runs on steam.",
-            "The only thing fast about your system is how quickly it disappoints.",
-            "Your system is a master of procrastination.",
-            "It's not a bug, it's a feature... of your slow system.",
-            "Your system is the reason we have progress bars.",
-            "I've seen faster calculations on an abacus.",
-            "Your system is a monument to \"almost there\".",
-        ];
-        roasts.push(general_roasts.choose(&mut rng).unwrap().to_string());
-    }
-
-    for roast in roasts {
-        println!("  {}", roast.yellow());
-    }
-    println!("");
+        roasts.extend(roaster.pick("general", pools::GENERAL_ROASTS));
+    }
+
+    render::roasts(roaster, &roasts);
 }
\ No newline at end of file
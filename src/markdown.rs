@@ -0,0 +1,98 @@
+use crate::html;
+use crate::i18n::tr;
+use crate::render::Report;
+use crate::score;
+
+// Backslashes before the characters Markdown would read as formatting, so
+// roasts like "<full> & *fast*" come out as written. Underscores inside a
+// word, like in "rust_roast", are never emphasis and stay as they are.
+fn escape(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut escaped = String::new();
+    for (index, &c) in chars.iter().enumerate() {
+        let in_word = index > 0 && chars[index - 1].is_alphanumeric() && chars.get(index + 1).is_some_and(|next| next.is_alphanumeric());
+        if matches!(c, '\\' | '`' | '*' | '[' | ']' | '<' | '>' | '|' | '#' | '~') || (c == '_' && !in_word) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// The report as GitHub-flavored Markdown, to paste into issues and PR
+// comments: each spec section as a table, the score and grade, the roasts as
+// a list, and with `details`, the raw snapshot as JSON under a fold
+pub fn page(report: &Report, details: bool) -> String {
+    let mut markdown = String::new();
+    for section in &report.sections {
+        // A GFM table needs a header row, so the section title is it
+        markdown.push_str(&format!("| {} | |\n| --- | --- |\n", escape(&section.title)));
+        for (label, value) in &section.specs {
+            markdown.push_str(&format!("| {} | {} |\n", escape(label), escape(value)));
+        }
+        markdown.push('\n');
+    }
+
+    if let Some(score) = report.score {
+        markdown.push_str(&format!("**{}:** {}/100 · **{}:** {}\n\n", escape(tr("label.score")), score, escape(tr("label.grade")), score::grade(score)));
+    }
+
+    if !report.heading.is_empty() {
+        markdown.push_str(&format!("### {}\n\n", escape(&report.heading)));
+    }
+    for (_, roast) in &report.roasts {
+        markdown.push_str(&format!("- {}\n", escape(&roast.text)));
+    }
+
+    if details && !report.snapshot.is_null() {
+        let json = serde_json::to_string_pretty(&report.snapshot).unwrap_or_default();
+        markdown.push_str(&fold(tr("header.snapshot"), &json));
+    }
+    markdown
+}
+
+// JSON under a <details> fold. The fold is HTML, so its summary is escaped
+// as HTML rather than Markdown.
+fn fold(summary: &str, json: &str) -> String {
+    format!("\n<details>\n<summary>{}</summary>\n\n```json\n{}\n```\n\n</details>\n", html::escape(summary), json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Section;
+    use crate::roast::{Picked, Severity};
+
+    #[test]
+    fn escapes_formatting() {
+        assert_eq!(escape("<full> & *fast*"), "\\<full\\> & \\*fast\\*");
+        assert_eq!(escape("`rm -rf` [link](x) a|b #1 ~~gone~~ \\"), "\\`rm -rf\\` \\[link\\](x) a\\|b \\#1 \\~\\~gone\\~\\~ \\\\");
+        // Underscores inside a word stay, ones that could start emphasis don't
+        assert_eq!(escape("rust_roast _slow_ snake_"), "rust_roast \\_slow\\_ snake\\_");
+        assert_eq!(escape("日本語_テキスト"), "日本語_テキスト");
+    }
+
+    #[test]
+    fn page_escapes_cells_roasts_and_the_fold() {
+        let report = Report {
+            sections: vec![Section { title: "Specs".to_string(), specs: vec![("GPU".to_string(), "A|B *Ti*".to_string())] }],
+            heading: "The Roast".to_string(),
+            roasts: vec![("ram".to_string(), Picked { text: "4 GB <RAM> & `tabs`".to_string(), severity: Severity::Standard })],
+            snapshot: serde_json::json!({ "ram.total_gb": 4.0 }),
+            ..Report::default()
+        };
+        let markdown = page(&report, true);
+        assert!(markdown.contains("| GPU | A\\|B \\*Ti\\* |\n"));
+        assert!(markdown.contains("- 4 GB \\<RAM\\> & \\`tabs\\`\n"));
+        assert!(markdown.ends_with(&fold(tr("header.snapshot"), "{\n  \"ram.total_gb\": 4.0\n}")));
+        assert!(!page(&report, false).contains("<details>"));
+    }
+
+    #[test]
+    fn summary_is_escaped_as_html() {
+        // Whatever a translation puts in the summary shows as text
+        let fold = fold("Snapshot <raw> & *more*", "{}");
+        assert!(fold.contains("<summary>Snapshot &lt;raw&gt; &amp; *more*</summary>"));
+        assert!(fold.contains("```json\n{}\n```"));
+    }
+}
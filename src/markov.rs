@@ -0,0 +1,207 @@
+use crate::combo;
+use crate::lint;
+use crate::pack::RoastPack;
+use crate::pools;
+use crate::processes;
+use crate::roast::{Roast, Tone};
+use crate::snapshot::Snapshot;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashMap;
+
+// Words of context for choosing the next word
+const ORDER: usize = 2;
+
+// Remixes sharing this much of their vocabulary with any training line are
+// just that line again, not a new roast
+pub const MAX_SIMILARITY: f64 = 0.6;
+
+// Longer remixes have usually lost the thread
+pub const MAX_CHARS: usize = 160;
+
+// Walks per remix before giving up on the filters
+const ATTEMPTS: usize = 32;
+
+// None marks the end of a line
+type Transitions = HashMap<Vec<String>, Vec<Option<String>>>;
+
+// A word-level Markov chain over every roast line that suits the run
+pub struct Markov {
+    lines: Vec<String>,
+    next: Transitions,
+}
+
+fn learn(next: &mut Transitions, line: &str) {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    if words.len() <= ORDER {
+        return;
+    }
+    for window in words.windows(ORDER + 1) {
+        let state = window[..ORDER].iter().map(|word| word.to_string()).collect();
+        next.entry(state).or_default().push(Some(window[ORDER].to_string()));
+    }
+    let end = words[words.len() - ORDER..].iter().map(|word| word.to_string()).collect();
+    next.entry(end).or_default().push(None);
+}
+
+// Lines the tone allows and whose condition holds for the snapshot
+pub fn usable(pool: &[Roast], tone: &Tone, snapshot: &Snapshot) -> Vec<String> {
+    pool.iter()
+        .filter(|roast| tone.allows(roast) && roast.specificity(snapshot).is_some())
+        .map(|roast| roast.text.to_string())
+        .collect()
+}
+
+// The training lines: every built-in pool, every English pack pool except the
+// compliments, and the built-in and pack app and combo roasts. Lines with
+// {placeholders} are left out, since a remix can end up in a pool that doesn't
+// fill them.
+pub fn corpus(packs: &[RoastPack], tone: &Tone, snapshot: &Snapshot) -> Vec<String> {
+    let mut pool: Vec<Roast> = pools::builtin().into_iter().flat_map(|(_, roasts)| roasts.iter().cloned()).collect();
+    for pack in packs.iter().filter(|pack| pack.lang.is_none()) {
+        let mut names: Vec<&String> = pack.roasts.keys().filter(|name| !is_compliment(name)).collect();
+        names.sort();
+        for name in names {
+            pool.extend(pack.roasts[name].iter().map(|roast| roast.to_roast()));
+        }
+    }
+    for rule in processes::app_rules(packs) {
+        pool.extend(rule.roasts);
+    }
+    for rule in combo::combo_rules(packs) {
+        pool.extend(rule.roasts);
+    }
+    usable(&pool, tone, snapshot).into_iter().filter(|line| !line.contains('{')).collect()
+}
+
+// "toast.*" and "backhanded.*" pools, which aren't remixed
+pub fn is_compliment(pool: &str) -> bool {
+    pool.starts_with("toast.") || pool.starts_with("backhanded.")
+}
+
+impl Markov {
+    pub fn train(lines: Vec<String>) -> Markov {
+        let mut next = Transitions::new();
+        for line in &lines {
+            learn(&mut next, line);
+        }
+        Markov { lines, next }
+    }
+
+    // A new line that starts like one of `pool`'s lines and continues through
+    // the whole corpus (plus `pool` itself). None if every walk ends up too
+    // close to a training line or too long.
+    pub fn remix<R: Rng>(&self, pool: &[String], rng: &mut R) -> Option<String> {
+        let mut local = Transitions::new();
+        for line in pool {
+            learn(&mut local, line);
+        }
+        let starts: Vec<Vec<&str>> = pool
+            .iter()
+            .map(|line| line.split_whitespace().take(ORDER).collect::<Vec<&str>>())
+            .filter(|start| start.len() == ORDER)
+            .collect();
+        for _ in 0..ATTEMPTS {
+            let mut words: Vec<String> = starts.choose(rng)?.iter().map(|word| word.to_string()).collect();
+            let mut length = words.iter().map(|word| word.len() + 1).sum::<usize>();
+            while length <= MAX_CHARS {
+                let state = &words[words.len() - ORDER..];
+                let choices: Vec<&Option<String>> = self.next.get(state).into_iter().chain(local.get(state)).flatten().collect();
+                match choices.choose(rng) {
+                    Some(Some(word)) => {
+                        length += word.len() + 1;
+                        words.push(word.clone());
+                    }
+                    _ => break,
+                }
+            }
+            let text = words.join(" ");
+            if text.len() > MAX_CHARS {
+                continue;
+            }
+            if self.lines.iter().chain(pool).any(|line| lint::similarity(&text, line) >= MAX_SIMILARITY) {
+                continue;
+            }
+            return Some(text);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roast::{Backend, Roaster, Severity};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    // Lines that share "and your" in the middle, so a walk can cross from one to another
+    fn crossing() -> Vec<String> {
+        lines(&[
+            "the cpu crawls along slowly and your fans scream like a jet",
+            "memory runs out constantly here and your browser hoards every single byte",
+            "graphics stutter through each frame and your games become tiny slideshows",
+        ])
+    }
+
+    #[test]
+    fn same_seed_same_remix() {
+        let chain = Markov::train(crossing());
+        let pool = crossing()[..1].to_vec();
+        let first = chain.remix(&pool, &mut StdRng::seed_from_u64(7));
+        assert!(first.is_some());
+        assert_eq!(first, chain.remix(&pool, &mut StdRng::seed_from_u64(7)));
+    }
+
+    #[test]
+    fn remixes_are_new_lines() {
+        let chain = Markov::train(crossing());
+        let pool = crossing()[..1].to_vec();
+        for seed in 0..20 {
+            let remix = chain.remix(&pool, &mut StdRng::seed_from_u64(seed)).unwrap();
+            assert!(remix.starts_with("the cpu crawls along slowly and your "), "{}", remix);
+            for line in crossing() {
+                assert!(lint::similarity(&remix, &line) < MAX_SIMILARITY, "{} copies {}", remix, line);
+            }
+        }
+    }
+
+    #[test]
+    fn verbatim_copies_are_rejected() {
+        // Every walk through a single line is that line again
+        let only = lines(&["nothing here repeats so every walk is the line itself"]);
+        let chain = Markov::train(only.clone());
+        assert_eq!(chain.remix(&only, &mut StdRng::seed_from_u64(1)), None);
+    }
+
+    #[test]
+    fn corpus_has_builtin_pools() {
+        let tone = Tone { severity: Severity::Savage, workplace_safe: false };
+        let corpus = corpus(&[], &tone, &Snapshot::default());
+        assert!(corpus.iter().any(|line| line == "Did you find your CPU in a cereal box?"));
+        assert!(corpus.iter().all(|line| !line.contains('{')));
+        // Personal lines stay out in workplace-safe mode
+        let safe = Tone { workplace_safe: true, ..tone };
+        assert!(!corpus_has(&safe, "Your computer is like a bad relationship: constantly letting you down."));
+        assert!(corpus_has(&tone, "Your computer is like a bad relationship: constantly letting you down."));
+    }
+
+    fn corpus_has(tone: &Tone, line: &str) -> bool {
+        corpus(&[], tone, &Snapshot::default()).iter().any(|other| other == line)
+    }
+
+    #[test]
+    fn seeded_roasters_remix_alike() {
+        let remix = || {
+            let mut roaster = Roaster::builder(Vec::new()).backend(Backend::Markov).seed(Some(3)).build();
+            (0..3).map(|_| roaster.pick("cpu.low", pools::CPU_LOW_ROASTS)).collect::<Vec<_>>()
+        };
+        let first = remix();
+        assert!(first.iter().all(Option::is_some));
+        assert_eq!(first, remix());
+    }
+}
@@ -0,0 +1,203 @@
+use crate::config::config_dir;
+use crate::roast::{Roast, Severity};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// A roast pack is a TOML file dropped into ~/.config/rust_roast/packs/.
+// Packs extend the built-in tables; they never replace them. Packs in
+// packs/<lang>/ are translations: their pools are used instead of the English
+// ones when that language is active, and English fills whatever they lack.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct RoastPack {
+    // The language of a translation pack; None for the English (default) set
+    #[serde(skip)]
+    pub lang: Option<String>,
+    pub apps: Vec<AppEntry>,
+    pub combos: Vec<ComboEntry>,
+    // Extra lines for named built-in pools, e.g. "project.unwrap" = [...]
+    pub roasts: HashMap<String, Vec<PackRoast>>,
+    // Grammar symbols for --backend grammar, e.g. "insult_object" = [...]
+    pub grammar: HashMap<String, Vec<PackRoast>>,
+}
+
+// A pack line is either a plain string (standard, not personal, unconditional)
+// or a table like { text = "...", severity = "savage", personal = true,
+// when = "ram.total_gb < 4", weight = 2.0 }
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum PackRoast {
+    Text(String),
+    Tagged {
+        text: String,
+        #[serde(default)]
+        severity: Severity,
+        #[serde(default)]
+        personal: bool,
+        when: Option<String>,
+        weight: Option<f64>,
+    },
+}
+
+impl PackRoast {
+    pub fn to_roast(&self) -> Roast {
+        match self {
+            PackRoast::Text(text) => Roast {
+                text: Cow::Owned(text.clone()),
+                severity: Severity::Standard,
+                personal: false,
+                condition: None,
+                weight: 1.0,
+            },
+            PackRoast::Tagged { text, severity, personal, when, weight } => Roast {
+                text: Cow::Owned(text.clone()),
+                severity: *severity,
+                personal: *personal,
+                condition: when.clone().map(Cow::Owned),
+                weight: weight.unwrap_or(1.0),
+            },
+        }
+    }
+}
+
+// Maps executable names to an app identity and the roasts for it.
+// An entry whose id matches a built-in app adds names/roasts to that app.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AppEntry {
+    pub id: String,
+    pub label: Option<String>,
+    pub names: Vec<String>,
+    pub roasts: Vec<PackRoast>,
+}
+
+// A cross-category combo: roasted first when `when` holds, replacing the
+// single-category roasts listed in `covers`. An entry whose id matches a
+// built-in combo adds roasts to it (and overrides its condition or covers if given).
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ComboEntry {
+    pub id: String,
+    pub when: Option<String>,
+    pub covers: Option<Vec<String>>,
+    pub roasts: Vec<PackRoast>,
+}
+
+fn packs_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("packs"))
+}
+
+// Every *.toml file in the packs directory, in file name order
+pub fn pack_files() -> Vec<PathBuf> {
+    packs_dir().map(|dir| toml_files(&dir)).unwrap_or_default()
+}
+
+// Translation packs for one language, from packs/<lang>/
+pub fn locale_pack_files(lang: &str) -> Vec<PathBuf> {
+    packs_dir().map(|dir| toml_files(&dir.join(lang))).unwrap_or_default()
+}
+
+// Languages that have a packs/<lang>/ directory
+pub fn locale_dirs() -> Vec<String> {
+    let entries = match packs_dir().map(fs::read_dir) {
+        Some(Ok(entries)) => entries,
+        _ => return Vec::new(),
+    };
+    let mut langs: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    langs.sort();
+    langs
+}
+
+fn toml_files(dir: &Path) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+pub fn load_pack(path: &Path) -> Result<RoastPack, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    toml::from_str(&text).map_err(|e| e.to_string().trim().to_string())
+}
+
+// English packs shipped with the binary: the compliments for --toast and
+// --mixed, and the grammar for --backend grammar
+pub const BUILTIN_PACKS: &[(&str, &str)] = &[
+    ("toast", include_str!("../packs/toast.toml")),
+    ("grammar", include_str!("../packs/grammar.toml")),
+];
+
+// Translations shipped with the binary, by language
+pub const BUILTIN_LOCALE_PACKS: &[(&str, &str)] = &[
+    ("de", include_str!("../packs/de/system.toml")),
+    ("pt", include_str!("../packs/pt/system.toml")),
+    ("ja", include_str!("../packs/ja/system.toml")),
+];
+
+// Loads every pack, plus the translation packs for `languages` (most specific
+// first). Broken packs are reported and skipped.
+pub fn load_packs(languages: &[String]) -> Vec<RoastPack> {
+    let mut packs = Vec::new();
+    for (name, text) in BUILTIN_PACKS {
+        match toml::from_str::<RoastPack>(text) {
+            Ok(pack) => packs.push(pack),
+            Err(err) => eprintln!("rust_roast: skipping built-in {} pack: {}", name, err.to_string().trim()),
+        }
+    }
+    for path in pack_files() {
+        match load_pack(&path) {
+            Ok(pack) => packs.push(pack),
+            Err(err) => eprintln!("rust_roast: skipping pack {}: {}", path.display(), err),
+        }
+    }
+    for lang in languages {
+        for (_, text) in BUILTIN_LOCALE_PACKS.iter().filter(|(name, _)| name == lang) {
+            match toml::from_str::<RoastPack>(text) {
+                Ok(pack) => packs.push(RoastPack { lang: Some(lang.clone()), ..pack }),
+                Err(err) => eprintln!("rust_roast: skipping built-in {} pack: {}", lang, err.to_string().trim()),
+            }
+        }
+        for path in locale_pack_files(lang) {
+            match load_pack(&path) {
+                Ok(pack) => packs.push(RoastPack { lang: Some(lang.clone()), ..pack }),
+                Err(err) => eprintln!("rust_roast: skipping pack {}: {}", path.display(), err),
+            }
+        }
+    }
+    packs
+}
+
+// A built-in pool with the lines every English pack adds to it
+pub fn pool(name: &str, builtin: &[Roast], packs: &[RoastPack]) -> Vec<Roast> {
+    let mut lines: Vec<Roast> = builtin.to_vec();
+    for pack in packs.iter().filter(|pack| pack.lang.is_none()) {
+        if let Some(extra) = pack.roasts.get(name) {
+            lines.extend(extra.iter().map(PackRoast::to_roast));
+        }
+    }
+    lines
+}
+
+// The translated lines for a pool; empty if no translation pack has it
+pub fn localized_pool(name: &str, packs: &[RoastPack]) -> Vec<Roast> {
+    packs
+        .iter()
+        .filter(|pack| pack.lang.is_some())
+        .filter_map(|pack| pack.roasts.get(name))
+        .flatten()
+        .map(PackRoast::to_roast)
+        .collect()
+}
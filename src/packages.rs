@@ -0,0 +1,252 @@
+use crate::roast::Roast;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Installed package counts per package manager. Managers that aren't present are left out.
+pub struct PackageInventory {
+    pub counts: Vec<(&'static str, usize)>,
+    // Binaries installed with `cargo install`
+    pub cargo_bins: usize,
+}
+
+impl PackageInventory {
+    pub fn total(&self) -> usize {
+        self.counts.iter().map(|(_, count)| count).sum()
+    }
+
+    pub fn count(&self, manager: &str) -> usize {
+        self.counts.iter().find(|(name, _)| *name == manager).map_or(0, |(_, count)| *count)
+    }
+
+    // neofetch style: "1523 (dpkg), 12 (flatpak)"
+    pub fn summary(&self) -> String {
+        self.counts
+            .iter()
+            .map(|(manager, count)| format!("{} ({})", count, manager))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+pub fn gather() -> PackageInventory {
+    let home = home_dir();
+    let probes: [(&'static str, Option<usize>); 6] = [
+        ("dpkg", dpkg_count()),
+        ("rpm", rpm_count()),
+        ("pacman", pacman_count()),
+        ("flatpak", flatpak_count(home.as_deref())),
+        ("snap", snap_count(Path::new("/snap"))),
+        ("nix", home.as_deref().and_then(nix_profile_count)),
+    ];
+    let mut counts: Vec<(&'static str, usize)> = probes
+        .into_iter()
+        .filter_map(|(manager, count)| count.filter(|&c| c > 0).map(|c| (manager, c)))
+        .collect();
+
+    let (cargo_installs, cargo_bins) = home.as_deref().and_then(cargo_installs).unwrap_or((0, 0));
+    if cargo_installs > 0 {
+        counts.push(("cargo", cargo_installs));
+    }
+    PackageInventory { counts, cargo_bins }
+}
+
+fn dpkg_count() -> Option<usize> {
+    fs::read_to_string("/var/lib/dpkg/status").ok().map(|status| count_dpkg_installed(&status))
+}
+
+// Stanzas in a dpkg status file that are actually installed
+fn count_dpkg_installed(status: &str) -> usize {
+    status.lines().filter(|line| *line == "Status: install ok installed").count()
+}
+
+// The rpm database is SQLite or Berkeley DB depending on the distro, so ask rpm itself
+fn rpm_count() -> Option<usize> {
+    let has_db = ["/var/lib/rpm", "/usr/lib/sysimage/rpm"].iter().any(|dir| Path::new(dir).is_dir());
+    if !has_db {
+        return None;
+    }
+    let output = Command::new("rpm").arg("-qa").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).lines().count())
+}
+
+// One directory per package in /var/lib/pacman/local
+fn pacman_count() -> Option<usize> {
+    count_dirs(Path::new("/var/lib/pacman/local"))
+}
+
+// System and user installations, apps only (runtimes don't count)
+fn flatpak_count(home: Option<&Path>) -> Option<usize> {
+    let system = count_dirs(Path::new("/var/lib/flatpak/app"));
+    let user = home.and_then(|home| count_dirs(&home.join(".local/share/flatpak/app")));
+    match (system, user) {
+        (None, None) => None,
+        (system, user) => Some(system.unwrap_or(0) + user.unwrap_or(0)),
+    }
+}
+
+// Mounted snaps live in /snap/<name>; /snap/bin holds the wrappers. Like
+// flatpak, apps only: bases and runtimes don't count.
+fn snap_count(dir: &Path) -> Option<usize> {
+    let entries = fs::read_dir(dir).ok()?;
+    Some(
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir() && entry.file_name() != "bin")
+            .filter(|entry| !is_runtime_snap(&entry.file_name().to_string_lossy()))
+            .count(),
+    )
+}
+
+// core, core22, snapd, bare, gtk-common-themes, gnome-42-2204,
+// kde-frameworks-5-qt-5-15-core20 and so on, but not gnome-calculator
+fn is_runtime_snap(name: &str) -> bool {
+    let versioned = |prefix: &str| name.strip_prefix(prefix).is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()));
+    matches!(name, "snapd" | "bare" | "gtk-common-themes")
+        || name.strip_prefix("core").is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit()))
+        || versioned("gnome-")
+        || versioned("kde-frameworks-")
+}
+
+fn nix_profile_count(home: &Path) -> Option<usize> {
+    count_nix_elements(&fs::read_to_string(home.join(".nix-profile/manifest.json")).ok()?)
+}
+
+// Elements of a nix profile manifest.json (v1/v2 use a list, v3 a map)
+fn count_nix_elements(text: &str) -> Option<usize> {
+    let manifest: serde_json::Value = serde_json::from_str(text).ok()?;
+    match &manifest["elements"] {
+        serde_json::Value::Array(elements) => Some(elements.len()),
+        serde_json::Value::Object(elements) => Some(elements.len()),
+        _ => None,
+    }
+}
+
+fn cargo_installs(home: &Path) -> Option<(usize, usize)> {
+    let cargo_home = std::env::var_os("CARGO_HOME").map_or_else(|| home.join(".cargo"), PathBuf::from);
+    count_cargo_installs(&fs::read_to_string(cargo_home.join(".crates2.json")).ok()?)
+}
+
+// (crates, binaries) installed with `cargo install`, from a .crates2.json
+fn count_cargo_installs(text: &str) -> Option<(usize, usize)> {
+    let crates: serde_json::Value = serde_json::from_str(text).ok()?;
+    let installs = crates["installs"].as_object()?;
+    let bins = installs
+        .values()
+        .map(|install| install["bins"].as_array().map_or(0, |bins| bins.len()))
+        .sum();
+    Some((installs.len(), bins))
+}
+
+fn count_dirs(path: &Path) -> Option<usize> {
+    let entries = fs::read_dir(path).ok()?;
+    Some(entries.filter_map(|entry| entry.ok()).filter(|entry| entry.path().is_dir()).count())
+}
+
+pub const BLOAT_ROASTS: &[Roast] = &[
+    Roast::gentle("{packages} packages installed. Your package manager needs a package manager."),
+    Roast::savage("With {packages} packages, your system isn't an OS, it's a hoarding documentary.").personal(),
+    Roast::standard("You have {packages} packages and use maybe twelve of them."),
+    Roast::standard("{packages} packages. Somewhere in there is a dependency you installed in 2019 for a tutorial you never finished.").personal(),
+];
+
+pub const SNAP_ROASTS: &[Roast] = &[
+    Roast::savage("You use snaps. Enjoy your 14 copies of the same runtime and a 10-second cold start."),
+    Roast::standard("{snaps} snaps installed. Your /snap folder has more loop devices than your lsblk can handle."),
+    Roast::gentle("Snap user spotted. Canonical thanks you for your service."),
+    Roast::standard("Snaps: because waiting for an app to open is part of the experience."),
+];
+
+pub const CARGO_ROASTS: &[Roast] = &[
+    Roast::standard("You have {cargo_bins} cargo binaries and still use grep."),
+    Roast::gentle("{cargo_bins} binaries from `cargo install`. Rewriting coreutils one crate at a time, I see."),
+    Roast::standard("Your ~/.cargo/bin has more tools than your actual toolbox. You use two of them.").personal(),
+    Roast::standard("{cargo_bins} cargo binaries, each compiled from scratch for 4 minutes. Worth it? Don't answer."),
+];
+
+// The pools above, by name
+pub const POOLS: &[(&str, &[Roast])] = &[
+    ("packages.bloat", BLOAT_ROASTS),
+    ("packages.snap", SNAP_ROASTS),
+    ("packages.cargo", CARGO_ROASTS),
+];
+
+// Fills the {packages}, {snaps} and {cargo_bins} placeholders
+pub fn fill(roast: &str, inventory: &PackageInventory) -> String {
+    roast
+        .replace("{packages}", &inventory.total().to_string())
+        .replace("{snaps}", &inventory.count("snap").to_string())
+        .replace("{cargo_bins}", &inventory.cargo_bins.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_installed_dpkg_stanzas() {
+        let status = "\
+Package: bash
+Status: install ok installed
+Version: 5.2.15-2
+
+Package: old-kernel
+Status: deinstall ok config-files
+
+Package: half
+Status: install ok half-configured
+
+Package: zsh
+Status: install ok installed
+Description: Status: install ok installed
+";
+        assert_eq!(count_dpkg_installed(status), 2);
+        assert_eq!(count_dpkg_installed(""), 0);
+    }
+
+    #[test]
+    fn counts_nix_elements_in_every_manifest_version() {
+        assert_eq!(count_nix_elements(r#"{"version": 2, "elements": [{"storePaths": []}, {"storePaths": []}]}"#), Some(2));
+        assert_eq!(count_nix_elements(r#"{"version": 3, "elements": {"hello": {}, "ripgrep": {}, "jq": {}}}"#), Some(3));
+        assert_eq!(count_nix_elements(r#"{"version": 3}"#), None);
+        assert_eq!(count_nix_elements("not json"), None);
+    }
+
+    #[test]
+    fn counts_cargo_installs_and_their_binaries() {
+        let crates2 = r#"{"installs": {
+            "ripgrep 14.1.0 (registry+https://github.com/rust-lang/crates.io-index)": {"bins": ["rg"]},
+            "cargo-edit 0.12.2 (registry+https://github.com/rust-lang/crates.io-index)": {"bins": ["cargo-add", "cargo-rm", "cargo-set-version"]},
+            "broken 0.1.0 (path+file:///tmp/broken)": {}
+        }}"#;
+        assert_eq!(count_cargo_installs(crates2), Some((3, 4)));
+        assert_eq!(count_cargo_installs(r#"{"installs": {}}"#), Some((0, 0)));
+        assert_eq!(count_cargo_installs("{}"), None);
+    }
+
+    #[test]
+    fn snap_count_leaves_out_bases_and_runtimes() {
+        let dir = std::env::temp_dir().join(format!("rust_roast-snap-{}", std::process::id()));
+        for name in ["bin", "core", "core22", "snapd", "bare", "gtk-common-themes", "gnome-42-2204", "firefox", "gnome-calculator", "code"] {
+            fs::create_dir_all(dir.join(name)).unwrap();
+        }
+        fs::write(dir.join("README"), "").unwrap();
+        assert_eq!(snap_count(&dir), Some(3));
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(snap_count(&dir), None);
+    }
+
+    #[test]
+    fn fills_counts() {
+        let inventory = PackageInventory { counts: vec![("dpkg", 1500), ("snap", 3)], cargo_bins: 7 };
+        assert_eq!(fill("{packages}/{snaps}/{cargo_bins}", &inventory), "1503/3/7");
+        assert_eq!(inventory.summary(), "1500 (dpkg), 3 (snap)");
+    }
+}
@@ -0,0 +1,360 @@
+use crate::roast::Roast;
+use crate::{desktop, devenv, distro, git, packages, project, tui, uptime, watch};
+
+// The pools `rust_roast` picks from for the machine's CPU, RAM, GPU and form
+// factor, and the general ones for when those didn't say enough
+
+pub const CPU_LOW_ROASTS: &[Roast] = &[
+    Roast::standard("Your CPU is so slow, it thinks \"loading screen\" is a feature, not a bug."),
+    Roast::standard("Did you find your CPU in a cereal box?"),
+    Roast::standard("Your processor is still running on dial-up speed."),
+    Roast::standard("Is your CPU powered by a hamster on a wheel?"),
+    Roast::standard("I've seen calculators with more processing power."),
+    Roast::standard("Your CPU is so old, it remembers when \"megahertz\" was impressive."),
+    Roast::standard("Does your CPU come with a built-in coffee break every time you open a tab?"),
+    Roast::savage("It's not a CPU, it's a paperweight that occasionally computes."),
+    Roast::gentle("Your CPU is the reason \"patience is a virtue\" was invented."),
+    Roast::standard("I bet your CPU still thinks Windows XP is cutting edge."),
+    Roast::savage("Your CPU is so bad, it makes a snail look like a cheetah."),
+    Roast::savage("Are you sure that's a CPU and not a potato with wires?"),
+    Roast::savage("Your CPU's performance is a cry for help."),
+    Roast::gentle("It's not throttling, it's just taking a very long nap."),
+    Roast::standard("Your CPU is the digital equivalent of waiting for paint to dry."),
+    Roast::standard("I heard your CPU is still trying to render the first frame of Pong."),
+    Roast::standard("Does your CPU need a nap after opening Notepad?"),
+    Roast::standard("Your CPU is so outdated, it probably runs on steam."),
+    Roast::savage("The only thing fast about your CPU is how quickly it disappoints."),
+    Roast::standard("Your CPU is a master of procrastination."),
+    Roast::standard("It's not a bug, it's a feature... of your slow CPU."),
+    Roast::gentle("Your CPU is the reason we have progress bars."),
+    Roast::standard("I've seen faster calculations on an abacus."),
+    Roast::standard("Your CPU is a monument to \"almost there\"."),
+    Roast::savage("The only thing your CPU is good at is generating heat."),
+];
+
+pub const CPU_MID_ROASTS: &[Roast] = &[
+    Roast::gentle("Your CPU is trying its best, bless its little silicon heart. Almost there!"),
+    Roast::gentle("It's not the fastest, but at least it's not actively trying to sabotage you."),
+    Roast::standard("Your CPU is the definition of \"gets the job done... eventually.\""),
+    Roast::gentle("A solid B- for effort, your CPU."),
+    Roast::gentle("Your CPU is like a reliable old car: it runs, but don't ask it to win any races."),
+    Roast::gentle("It's not a powerhouse, but it's not a complete embarrassment either."),
+    Roast::standard("Your CPU is the middle child of processors: always overlooked."),
+    Roast::standard("It's got enough cores to count your fingers, and maybe a few toes."),
+    Roast::standard("Your CPU is the reason \"good enough\" exists."),
+    Roast::gentle("It's not breaking any records, but it's not breaking your bank either."),
+    Roast::standard("Your CPU is the beige of computing: functional, but unexciting."),
+    Roast::standard("It's got enough power for spreadsheets and existential dread.").personal(),
+    Roast::savage("Your CPU is the equivalent of a participation trophy."),
+    Roast::standard("It's not fast, it's not slow, it's just... there."),
+    Roast::standard("Your CPU is the background music of your computing life: always present, rarely noticed."),
+    Roast::standard("It's got enough oomph for basic tasks and questioning your life choices.").personal(),
+    Roast::standard("Your CPU is the definition of \"average.\""),
+    Roast::gentle("It's not a beast, but it's not a total slouch either."),
+    Roast::standard("Your CPU is the reason you have time to make a sandwich while waiting."),
+    Roast::standard("It's got enough power to run your OS and a mild sense of regret.").personal(),
+    Roast::standard("Your CPU is the unsung hero of \"just barely.\""),
+    Roast::gentle("It's not a Ferrari, but it's not a tricycle either."),
+    Roast::standard("Your CPU is the definition of \"meh.\""),
+    Roast::gentle("It's got enough power to run your favorite retro games."),
+    Roast::gentle("Your CPU is the reason you appreciate fast computers."),
+];
+
+pub const CPU_HIGH_ROASTS: &[Roast] = &[
+    Roast::standard("All those cores and the busiest thing on this machine is Chrome. It thanks you for the headroom.").when("cpu.cores >= 16 && app.top == chrome"),
+    Roast::standard("Your CPU is decent, but are you really pushing it, or just browsing memes?").personal(),
+    Roast::standard("You've got the power, but do you have the skills to use it?").personal(),
+    Roast::gentle("Your CPU is so fast, it finishes tasks before you even think of them."),
+    Roast::standard("Did you buy that CPU just to flex on your friends?").personal(),
+    Roast::standard("Your processor is a beast, but are you taming it or just letting it nap?"),
+    Roast::gentle("I bet your CPU has its own fan club."),
+    Roast::gentle("Your CPU is so powerful, it probably runs on pure ambition."),
+    Roast::gentle("It's not a CPU, it's a supercomputer in disguise."),
+    Roast::gentle("Your CPU is the reason \"lag\" is just a myth to you."),
+    Roast::gentle("I heard your CPU can render the entire universe in 8K."),
+    Roast::gentle("Your CPU is so good, it makes other CPUs cry."),
+    Roast::gentle("Are you sure that's a CPU and not a quantum computer?"),
+    Roast::standard("Your CPU's performance is a threat to national security."),
+    Roast::standard("It's not throttling, it's just taking a very short power nap."),
+    Roast::gentle("Your CPU is the digital equivalent of a rocket ship."),
+    Roast::standard("I heard your CPU is still trying to render the first frame of the multiverse."),
+    Roast::standard("Does your CPU need a challenge after compiling the Linux kernel?"),
+    Roast::gentle("Your CPU is so advanced, it probably runs on dark matter."),
+    Roast::standard("The only thing slow about your CPU is how long it takes to find a worthy task."),
+    Roast::gentle("Your CPU is a master of efficiency."),
+    Roast::gentle("It's not a bug, it's a feature... of your lightning-fast CPU."),
+    Roast::gentle("Your CPU is the reason we don't have progress bars."),
+    Roast::standard("I've seen slower calculations on a supercomputer."),
+    Roast::gentle("Your CPU is a monument to \"overkill\"."),
+    Roast::gentle("The only thing your CPU is good at is making other CPUs jealous."),
+];
+
+pub const RAM_LOW_ROASTS: &[Roast] = &[
+    Roast::standard("A laptop with under 4 GB of RAM. Every open tab is a tough life decision.").when("ram.total_gb < 4 && form_factor == laptop"),
+    Roast::standard("Your RAM is so low, you probably have to close your browser to open a text editor."),
+    Roast::standard("Is your RAM powered by a single gerbil on a tiny treadmill?"),
+    Roast::standard("I've seen more memory in a sticky note."),
+    Roast::standard("Your RAM is the reason \"out of memory\" is your favorite error message."),
+    Roast::standard("Does your computer run on hopes and dreams, because it's not running on RAM."),
+    Roast::standard("Your RAM is so small, it gets lost in a single tab."),
+    Roast::standard("I bet your RAM still thinks 256MB is a lot."),
+    Roast::savage("Your RAM is the digital equivalent of a goldfish's memory."),
+    Roast::gentle("It's not multitasking, it's just desperately trying to remember one thing at a time."),
+    Roast::standard("Your RAM is the reason your computer sounds like a jet engine taking off."),
+    Roast::standard("I've seen faster data retrieval from a stone tablet."),
+    Roast::savage("Your RAM is a bottleneck so severe, it's practically a chokehold."),
+    Roast::standard("Does your RAM need a nap after opening the task manager?"),
+    Roast::standard("Your RAM is the reason you're still using Internet Explorer."),
+    Roast::standard("The only thing fast about your RAM is how quickly it fills up."),
+    Roast::standard("Your RAM is a master of forgetting."),
+    Roast::standard("It's not a bug, it's a feature... of your tiny RAM."),
+    Roast::gentle("Your RAM is the reason we have swap files."),
+    Roast::savage("I've seen more efficient memory management in a toddler's brain."),
+    Roast::standard("Your RAM is a monument to \"just barely not enough\"."),
+    Roast::gentle("The only thing your RAM is good at is making you upgrade."),
+    Roast::savage("Your RAM is so small, it can't even hold a single thought."),
+    Roast::standard("I bet your RAM is still trying to load the first pixel of your desktop."),
+    Roast::standard("Your RAM is the reason you have to restart your computer every hour."),
+    Roast::savage("Your RAM has one talent: turning every click into a waiting game."),
+];
+
+pub const RAM_MID_ROASTS: &[Roast] = &[
+    Roast::standard("Your RAM is like your memory of last week's tasks: barely enough to get by.").personal(),
+    Roast::standard("It's got enough memory for a few tabs and a mild existential crisis.").personal(),
+    Roast::gentle("Your RAM is the definition of \"adequate.\""),
+    Roast::gentle("A solid C+ for effort, your RAM."),
+    Roast::gentle("Your RAM is like a small apartment: enough space, but you're always bumping into things."),
+    Roast::gentle("It's not a lot, but it's not nothing either."),
+    Roast::standard("Your RAM is the middle child of memory: always overlooked."),
+    Roast::gentle("It's got enough memory to run your OS and a few background apps."),
+    Roast::standard("Your RAM is the reason \"close some programs\" is your mantra."),
+    Roast::gentle("It's not breaking any speed records, but it's not breaking your budget either."),
+    Roast::standard("Your RAM is the beige of memory: functional, but unexciting."),
+    Roast::gentle("It's got enough memory for basic tasks and a few open documents."),
+    Roast::savage("Your RAM is the equivalent of a participation trophy in the memory Olympics."),
+    Roast::standard("It's not plenty, it's not scarce, it's just... enough. Barely."),
+    Roast::standard("Your RAM is the background noise of your computing life: always present, rarely noticed."),
+    Roast::standard("It's got enough memory for a few browser tabs and a mild sense of regret.").personal(),
+    Roast::standard("Your RAM is the definition of \"average.\""),
+    Roast::gentle("It's not a memory palace, but it's not a shoebox either."),
+    Roast::standard("Your RAM is the reason you have time to make a sandwich while waiting for apps to load."),
+    Roast::standard("It's got enough memory to run your OS and a mild sense of disappointment.").personal(),
+    Roast::standard("Your RAM is the unsung hero of \"just barely enough.\""),
+    Roast::gentle("It's not a superhighway, but it's not a dirt road either."),
+    Roast::standard("Your RAM is the definition of \"meh.\""),
+    Roast::gentle("It's got enough memory to run your favorite retro games."),
+    Roast::gentle("Your RAM is the reason you appreciate fast memory."),
+];
+
+pub const RAM_HIGH_ROASTS: &[Roast] = &[
+    Roast::standard("Plenty of RAM, but are you using it for anything productive, or just 50 Chrome tabs?").personal(),
+    Roast::standard("You've got the memory, but do you have the applications to fill it?"),
+    Roast::gentle("Your RAM is so vast, it probably has its own zip code."),
+    Roast::standard("Did you buy that RAM just to flex on your friends?").personal(),
+    Roast::standard("Your memory is a beast, but are you taming it or just letting it idle?"),
+    Roast::gentle("I bet your RAM has its own fan club."),
+    Roast::gentle("Your RAM is so powerful, it probably runs on pure ambition."),
+    Roast::gentle("It's not RAM, it's a data ocean."),
+    Roast::gentle("Your RAM is the reason \"out of memory\" is just a legend to you."),
+    Roast::gentle("I heard your RAM can store the entire internet."),
+    Roast::gentle("Your RAM is so good, it makes other RAM modules cry."),
+    Roast::gentle("Are you sure that's RAM and not a quantum storage device?"),
+    Roast::standard("Your RAM's capacity is a threat to national security."),
+    Roast::standard("It's not filling up, it's just taking a very short data nap."),
+    Roast::gentle("Your RAM is the digital equivalent of a black hole for data."),
+    Roast::standard("I heard your RAM is still trying to load the first byte of the multiverse."),
+    Roast::standard("Does your RAM need a challenge after opening every program you own?"),
+    Roast::gentle("Your RAM is so advanced, it probably runs on dark matter."),
+    Roast::standard("The only thing slow about your RAM is how long it takes to find a worthy task."),
+    Roast::gentle("Your RAM is a master of retention."),
+    Roast::gentle("It's not a bug, it's a feature... of your massive RAM."),
+    Roast::gentle("Your RAM is the reason we don't have swap files."),
+    Roast::standard("I've seen slower data storage on a supercomputer."),
+    Roast::gentle("Your RAM is a monument to \"overkill\"."),
+    Roast::gentle("The only thing your RAM is good at is making other RAM modules jealous."),
+];
+
+pub const GPU_INTEGRATED_ROASTS: &[Roast] = &[
+    Roast::standard("Your GPU is so weak, it struggles to render a single pixel in 4K. Maybe try ASCII art?"),
+    Roast::standard("Is your GPU powered by a single AA battery?"),
+    Roast::standard("I've seen more graphical fidelity in a flipbook."),
+    Roast::gentle("Your GPU is the reason \"low settings\" is your default."),
+    Roast::standard("Does your computer run on hopes and dreams, because it's not running on a dedicated GPU."),
+    Roast::standard("Your GPU is so small, it gets lost in a single texture."),
+    Roast::standard("I bet your GPU still thinks 640x480 is high resolution."),
+    Roast::savage("Your GPU is the digital equivalent of a crayon drawing."),
+    Roast::standard("It's not gaming, it's just desperately trying to display one frame at a time."),
+    Roast::standard("Your GPU is the reason your computer sounds like a jet engine taking off when you open Solitaire."),
+    Roast::savage("I've seen faster rendering from a cave painting."),
+    Roast::standard("Your GPU is a bottleneck so severe, it's practically a chokehold on your pixels."),
+    Roast::standard("Does your GPU need a nap after rendering a static webpage?"),
+    Roast::standard("Your GPU is the reason you're still playing games from the 90s."),
+    Roast::savage("The only thing fast about your GPU is how quickly it disappoints."),
+    Roast::standard("Your GPU is a master of pixelation."),
+    Roast::gentle("It's not a bug, it's a feature... of your integrated graphics."),
+    Roast::gentle("Your GPU is the reason we have \"minimum requirements\"."),
+    Roast::savage("I've seen more efficient graphics processing in a toaster."),
+    Roast::standard("Your GPU is a monument to \"just barely not enough pixels\"."),
+    Roast::standard("The only thing your GPU is good at is making you upgrade."),
+    Roast::savage("Your GPU is so weak, it can't even render a single thought."),
+    Roast::standard("I bet your GPU is still trying to load the first pixel of your desktop background."),
+    Roast::standard("Your GPU is the reason you have to restart your computer after watching a YouTube video."),
+    Roast::savage("Your GPU has one talent: turning every game into a slideshow."),
+];
+
+pub const GPU_LOW_END_ROASTS: &[Roast] = &[
+    Roast::gentle("Your GPU is trying its best, bless its little silicon heart. Almost there!"),
+    Roast::gentle("It's not the fastest, but at least it's not actively trying to sabotage your framerate."),
+    Roast::gentle("Your GPU is the definition of \"gets the job done... eventually.\""),
+    Roast::standard("A solid C- for effort, your GPU."),
+    Roast::gentle("Your GPU is like a reliable old car: it runs, but don't ask it to win any graphical races."),
+    Roast::gentle("It's not a graphics powerhouse, but it won't embarrass you at a LAN party either."),
+    Roast::standard("Your GPU is the middle child of graphics cards: always overlooked."),
+    Roast::standard("It's got enough VRAM to count your fingers, and maybe a few toes."),
+    Roast::standard("Your GPU is the reason \"good enough\" exists for low settings."),
+    Roast::gentle("It's not breaking any benchmarks, but it didn't break the bank either."),
+    Roast::standard("Your GPU is the beige of graphics: functional, but unexciting."),
+    Roast::standard("It's got enough power for spreadsheets and mild graphical regret."),
+    Roast::savage("Your GPU is the equivalent of a participation trophy in the graphics Olympics."),
+    Roast::standard("It's not smooth, it's not choppy, it's just... frames."),
+    Roast::standard("Your GPU is the background music of your gaming life: always present, rarely noticed."),
+    Roast::standard("It's got enough oomph for basic tasks and questioning your graphical choices.").personal(),
+    Roast::standard("Your GPU is the definition of \"average.\""),
+    Roast::gentle("It's not a beast, but it can still push a few polygons."),
+    Roast::standard("Your GPU is the reason you have time to make a sandwich while waiting for textures to load."),
+    Roast::standard("It's got enough power to run your OS and a mild sense of disappointment in your framerate."),
+    Roast::standard("Your GPU is the unsung hero of \"just barely playable.\""),
+    Roast::gentle("It's not a Ferrari, but it gets to 30 fps eventually."),
+    Roast::standard("Your GPU is the definition of \"meh.\" for gaming."),
+    Roast::gentle("It's got enough power to run your favorite retro games in glorious pixelation."),
+    Roast::gentle("Your GPU is the reason you appreciate fast graphics cards."),
+];
+
+pub const GPU_HIGH_END_ROASTS: &[Roast] = &[
+    Roast::standard("Your GPU is probably fine, but let's be honest, you're not playing Cyberpunk on max settings, are you?").personal(),
+    Roast::standard("You've got the graphical power, but do you have the games to push it?").personal(),
+    Roast::gentle("Your GPU is so fast, it renders frames before you even think of them."),
+    Roast::standard("Did you buy that GPU just to flex on your friends?").personal(),
+    Roast::standard("Your graphics card is a beast, but are you taming it or just letting it idle?"),
+    Roast::gentle("I bet your GPU has its own fan club."),
+    Roast::gentle("Your GPU is so powerful, it probably runs on pure ambition and RGB."),
+    Roast::gentle("It's not a GPU, it's a pixel-pushing supercomputer in disguise."),
+    Roast::gentle("Your GPU is the reason \"lag\" is just a myth to you in games."),
+    Roast::gentle("I heard your GPU can render the entire universe in 8K, with ray tracing."),
+    Roast::gentle("Your GPU is so good, it makes other GPUs cry in envy."),
+    Roast::gentle("Are you sure that's a GPU and not a quantum rendering device?"),
+    Roast::standard("Your GPU's performance is a threat to national security for its sheer power."),
+    Roast::standard("It's not throttling, it's just taking a very short rendering nap."),
+    Roast::gentle("Your GPU is the digital equivalent of a rocket ship for graphics."),
+    Roast::standard("I heard your GPU is still trying to render the first frame of the multiverse in real-time."),
+    Roast::standard("Does your GPU need a challenge after rendering every game you own at max settings?"),
+    Roast::gentle("Your GPU is so advanced, it probably runs on dark matter and unicorn tears."),
+    Roast::standard("The only thing slow about your GPU is how long it takes to find a worthy graphical task."),
+    Roast::gentle("Your GPU is a master of visual fidelity."),
+    Roast::gentle("It's not a bug, it's a feature... of your lightning-fast GPU."),
+    Roast::gentle("Your GPU is the reason we don't have graphical limitations."),
+    Roast::standard("I've seen slower rendering on a supercomputer."),
+    Roast::gentle("Your GPU is a monument to \"overkill\" in the best way possible."),
+    Roast::gentle("The only thing your GPU is good at is making other GPUs jealous with its performance."),
+];
+
+pub const LAPTOP_ROASTS: &[Roast] = &[
+    Roast::standard("A high-end GPU in a laptop. Enjoy your 40 minutes of battery and lap burns.").when("gpu.tier == high_end"),
+    Roast::gentle("Ah, a laptop user. Enjoy your portable space heater."),
+    Roast::standard("Your laptop's battery life is shorter than your attention span.").personal(),
+    Roast::standard("I bet your laptop fan sounds like a jet engine taking off."),
+    Roast::gentle("Portable power, portable problems."),
+    Roast::standard("Your laptop is great for gaming... if the game is Solitaire."),
+    Roast::standard("The only thing thinner than your laptop is your patience for its performance.").personal(),
+    Roast::standard("Your laptop is so light, it probably floats away when you open too many tabs."),
+    Roast::standard("I've seen more desk space on a postage stamp than you have with that laptop."),
+    Roast::standard("Your laptop's keyboard probably has more crumbs than keys.").personal(),
+    Roast::standard("The only thing your laptop is good at is burning your thighs.").personal(),
+    Roast::gentle("Your laptop is a master of thermal throttling."),
+    Roast::standard("It's not a bug, it's a feature... of your laptop's overheating."),
+    Roast::gentle("Your laptop is the reason we have cooling pads."),
+    Roast::standard("I've seen more efficient cooling in a desert."),
+    Roast::standard("Your laptop is a monument to \"almost a desktop\"."),
+    Roast::standard("The only thing your laptop is good at is making you wish you had a desktop."),
+    Roast::savage("Your laptop is so small, it can't even hold a single thought."),
+    Roast::standard("I bet your laptop is still trying to load the first pixel of your desktop background."),
+    Roast::standard("Your laptop is the reason you have to restart your computer every hour."),
+    Roast::standard("The only thing your laptop is good at is making you frustrated."),
+    Roast::standard("Your laptop is the digital equivalent of a hot potato."),
+    Roast::standard("I heard your laptop can cook an egg on its keyboard."),
+    Roast::standard("Your laptop is so quiet, you can hear the dust bunnies breeding inside."),
+    Roast::savage("The only thing your laptop is good at is being a portable disappointment."),
+    Roast::gentle("Your laptop is the reason you carry a power bank everywhere."),
+];
+
+pub const DESKTOP_ROASTS: &[Roast] = &[
+    Roast::standard("A desktop user, I see. Enjoy being tethered to your desk, cave dweller.").personal(),
+    Roast::standard("Your desktop is so big, it probably has its own zip code."),
+    Roast::gentle("I bet your desktop fan sounds like a wind tunnel."),
+    Roast::gentle("Immovable power, immovable problems."),
+    Roast::standard("Your desktop is great for gaming... if the game is \"find the dust bunny\"."),
+    Roast::savage("The only thing wider than your desktop is your waistline from sitting all day.").personal(),
+    Roast::gentle("Your desktop is so heavy, it probably has its own gravitational pull."),
+    Roast::standard("I've seen more portability in a refrigerator than in your desktop."),
+    Roast::standard("Your desktop's cables probably have more knots than a sailor's convention."),
+    Roast::standard("The only thing your desktop is good at is collecting dust."),
+    Roast::standard("Your desktop is a master of cable management nightmares."),
+    Roast::standard("It's not a bug, it's a feature... of your desktop's massive footprint."),
+    Roast::gentle("Your desktop is the reason we have bigger desks."),
+    Roast::savage("I've seen more efficient space utilization in a landfill."),
+    Roast::standard("Your desktop is a monument to \"overkill\"."),
+    Roast::standard("The only thing your desktop is good at is making you wish you had a laptop."),
+    Roast::standard("Your desktop is so big, it can't even fit in a single thought."),
+    Roast::standard("I bet your desktop is still trying to load the first pixel of your desktop background."),
+    Roast::standard("Your desktop is the reason you have to restart your computer every hour."),
+    Roast::standard("The only thing your desktop is good at is making you frustrated."),
+    Roast::standard("Your desktop is the digital equivalent of a brick house."),
+    Roast::gentle("I heard your desktop can heat an entire room in winter."),
+    Roast::standard("Your desktop is so loud, you can hear it from the next county."),
+    Roast::savage("The only thing your desktop is good at is being a stationary disappointment."),
+    Roast::standard("Your desktop is the reason you never leave your house.").personal(),
+];
+
+pub const GENERAL_ROASTS: &[Roast] = &[
+    Roast::gentle("Overall, your system is a testament to \"it works, mostly.\" Don't worry, we've all been there."),
+    Roast::standard("Your system is... adequate. Just like your social life.").personal(),
+    Roast::gentle("I've seen better specs on a toaster. Just kidding... mostly."),
+    Roast::gentle("Your computer is like a fine wine: it gets slower with age."),
+    Roast::savage("The only thing fast about your system is how quickly it disappoints."),
+    Roast::standard("Your system is a master of procrastination."),
+    Roast::gentle("It's not a bug, it's a feature... of your unique setup."),
+    Roast::standard("Your system is the reason we have progress bars."),
+    Roast::standard("I've seen snappier systems behind glass at a computer museum."),
+    Roast::standard("Your system is a monument to \"almost there\"."),
+    Roast::standard("The only thing your system is good at is generating heat."),
+    Roast::standard("Your computer is like a bad relationship: constantly letting you down.").personal(),
+    Roast::standard("I bet your system still thinks Windows 95 is cutting edge."),
+    Roast::standard("Your system is the digital equivalent of waiting for paint to dry."),
+    Roast::standard("It's not slow, it's just taking a very long nap."),
+    Roast::gentle("Your system is the reason you have time to make a sandwich while waiting."),
+    Roast::standard("I heard your system is still trying to render the first frame of Pong."),
+    Roast::standard("Does your system need a nap after opening Notepad?"),
+    Roast::standard("Your system is so outdated, it probably runs on steam."),
+];
+
+pub const POOLS: &[(&str, &[Roast])] = &[
+    ("cpu.low", CPU_LOW_ROASTS),
+    ("cpu.mid", CPU_MID_ROASTS),
+    ("cpu.high", CPU_HIGH_ROASTS),
+    ("ram.low", RAM_LOW_ROASTS),
+    ("ram.mid", RAM_MID_ROASTS),
+    ("ram.high", RAM_HIGH_ROASTS),
+    ("gpu.integrated", GPU_INTEGRATED_ROASTS),
+    ("gpu.low_end", GPU_LOW_END_ROASTS),
+    ("gpu.high_end", GPU_HIGH_END_ROASTS),
+    ("form_factor.laptop", LAPTOP_ROASTS),
+    ("form_factor.desktop", DESKTOP_ROASTS),
+    ("general", GENERAL_ROASTS),
+];
+
+// Every named pool built into the binaries, for what looks at all the lines
+// at once (the Markov chain, lint) rather than picking from one pool
+pub fn builtin() -> Vec<(&'static str, &'static [Roast])> {
+    [POOLS, distro::POOLS, packages::POOLS, devenv::POOLS, desktop::POOLS, uptime::POOLS, project::POOLS, git::POOLS, tui::POOLS, watch::POOLS]
+        .concat()
+}
@@ -0,0 +1,301 @@
+use crate::pack::{PackRoast, RoastPack};
+use crate::roast::Roast;
+use std::collections::HashMap;
+use std::path::Path;
+use sysinfo::{ProcessExt, System, SystemExt};
+
+// Apps using less than this share of RAM and CPU are not worth a roast
+const MIN_SHARE: f64 = 0.01;
+
+// An app identity: the executable names it runs under and its roast pool
+pub struct AppRule {
+    pub id: String,
+    pub label: String,
+    pub names: Vec<String>,
+    pub roasts: Vec<Roast>,
+    // Lines from translation packs, preferred over `roasts` when present
+    pub localized: Vec<Roast>,
+}
+
+// A recognised app and how much of the machine it is using right now
+pub struct RunningApp {
+    pub id: String,
+    pub label: String,
+    pub processes: usize,
+    pub mem_share: f64,
+    pub cpu_share: f64,
+}
+
+impl RunningApp {
+    pub fn share(&self) -> f64 {
+        self.mem_share.max(self.cpu_share)
+    }
+}
+
+fn builtin(id: &str, label: &str, names: &[&str], roasts: &[Roast]) -> AppRule {
+    AppRule {
+        id: id.to_string(),
+        label: label.to_string(),
+        names: names.iter().map(|n| n.to_string()).collect(),
+        roasts: roasts.to_vec(),
+        localized: Vec::new(),
+    }
+}
+
+// Built-in app table. {app} and {share} are filled in when the roast is picked.
+fn builtin_rules() -> Vec<AppRule> {
+    vec![
+        builtin("chrome", "Google Chrome", &["chrome", "google-chrome", "chromium", "chromium-browser"], &[
+            Roast::gentle("{app} is using {share} of your RAM. It's not a browser, it's a memory tenant that never pays rent."),
+            Roast::savage("Chrome has more processes running than you have open tabs of productivity.").personal(),
+            Roast::standard("Your RAM didn't disappear, {app} just adopted it."),
+            Roast::gentle("I see {app} is running. That explains the jet engine noises."),
+        ]),
+        builtin("firefox", "Firefox", &["firefox", "firefox-esr", "firefox-bin"], &[
+            Roast::savage("Firefox user, huh? Still telling everyone at parties about privacy?").personal(),
+            Roast::gentle("{app} is holding {share} of your RAM hostage, but at least it's open source hostage-taking."),
+            Roast::standard("You use Firefox so you can feel morally superior while your tabs crash.").personal(),
+        ]),
+        builtin("brave", "Brave", &["brave", "brave-browser"], &[
+            Roast::savage("Brave browser? Let me guess, you also have opinions about crypto.").personal(),
+            Roast::gentle("{app} blocks ads, but it can't block the {share} of RAM it's eating."),
+        ]),
+        builtin("edge", "Microsoft Edge", &["msedge", "microsoft-edge"], &[
+            Roast::gentle("You're running Edge on purpose? Bold."),
+            Roast::standard("{app} is using {share} of your RAM to ask you to make it the default browser again."),
+        ]),
+        builtin("slack", "Slack", &["slack"], &[
+            Roast::gentle("{app} is using {share} of your RAM to show you three unread messages."),
+            Roast::standard("Slack: because one chat app per gigabyte wasn't enough."),
+            Roast::standard("Your Slack client uses more memory than the server that sends the messages."),
+        ]),
+        builtin("discord", "Discord", &["discord", "discordcanary", "discordptb"], &[
+            Roast::savage("Discord is open. So that's where your productivity and {share} of your RAM went.").personal(),
+            Roast::savage("{app} is running. Touch grass, then come back.").personal(),
+            Roast::gentle("{app} is open in the background. Someone is definitely typing..."),
+        ]),
+        builtin("teams", "Microsoft Teams", &["teams", "teams-for-linux", "ms-teams"], &[
+            Roast::gentle("{app} is running. My condolences."),
+            Roast::gentle("Teams is using {share} of your machine to tell you you're on mute."),
+        ]),
+        builtin("spotify", "Spotify", &["spotify"], &[
+            Roast::standard("Spotify is eating {share} of your RAM. Those lo-fi beats aren't making you code faster.").personal(),
+            Roast::gentle("Your music player needs more memory than the Apollo guidance computer. Just saying."),
+        ]),
+        builtin("vscode", "VS Code", &["code", "code-oss", "codium", "vscodium"], &[
+            Roast::standard("{app}: a text editor that shipped a whole browser to highlight your syntax errors."),
+            Roast::savage("VS Code is using {share} of your RAM, and your code still doesn't compile.").personal(),
+            Roast::standard("How many extensions does it take to write a for loop? Ask your VS Code."),
+            Roast::gentle("{app} is open. Forty extensions, one theme you change every week."),
+        ]),
+        builtin("jetbrains", "JetBrains IDE", &["idea", "clion", "pycharm", "rustrover", "webstorm", "goland", "rider", "phpstorm"], &[
+            Roast::gentle("A JetBrains IDE using {share} of your machine. Still indexing, I assume?"),
+            Roast::standard("Your IDE has been indexing since the last leap year."),
+        ]),
+        builtin("eclipse", "Eclipse", &["eclipse"], &[
+            Roast::gentle("Eclipse? In this economy?"),
+            Roast::standard("Eclipse is running. Somewhere, a workspace is being rebuilt for no reason."),
+        ]),
+        builtin("steam", "Steam", &["steam", "steamwebhelper"], &[
+            Roast::standard("Steam is running. Your backlog has more unplayed games than your CPU has cycles.").personal(),
+            Roast::gentle("{app} is using {share} of your RAM to remind you of the summer sale."),
+        ]),
+        builtin("launcher", "Game Launcher", &["lutris", "heroic", "epicgameslauncher", "battle.net", "legendary", "bottles"], &[
+            Roast::savage("You have a launcher for your launcher. Ever considered launching yourself outside?").personal(),
+            Roast::standard("{app} is running, which is more exercise than your GPU has had all week."),
+            Roast::gentle("{app} is running. Three launchers deep and the game still needs an update."),
+        ]),
+        builtin("miner", "Crypto Miner", &["xmrig", "ethminer", "t-rex", "nbminer", "cgminer", "bfgminer", "lolminer", "phoenixminer", "gminer"], &[
+            Roast::savage("There's a crypto miner running. Either you're chasing the 2017 dream or someone else is mining on your dime."),
+            Roast::standard("{app} is using {share} of your machine. Your electricity bill called, it wants a word."),
+            Roast::gentle("Mining crypto on this hardware? You'll break even around the heat death of the universe."),
+        ]),
+        builtin("docker", "Docker", &["dockerd", "containerd", "com.docker.backend", "podman"], &[
+            Roast::gentle("Docker is running. It works on your machine, which is precisely the problem."),
+            Roast::standard("You containerized your problems, but they still use {share} of your RAM."),
+            Roast::standard("How many containers are running right now? Don't look, it'll just make you sad.").personal(),
+        ]),
+    ]
+}
+
+// Built-in rules with any pack [[apps]] entries merged in
+pub fn app_rules(packs: &[RoastPack]) -> Vec<AppRule> {
+    let mut rules = builtin_rules();
+    for pack in packs {
+        for entry in &pack.apps {
+            if entry.id.is_empty() {
+                continue;
+            }
+            let index = match rules.iter().position(|rule| rule.id == entry.id) {
+                Some(index) => index,
+                None => {
+                    rules.push(builtin(&entry.id, &entry.id, &[], &[]));
+                    rules.len() - 1
+                }
+            };
+            let rule = &mut rules[index];
+            rule.names.extend(entry.names.iter().cloned());
+            let roasts = entry.roasts.iter().map(PackRoast::to_roast);
+            if pack.lang.is_some() {
+                rule.localized.extend(roasts);
+            } else {
+                rule.roasts.extend(roasts);
+            }
+            if let Some(label) = &entry.label {
+                rule.label = label.clone();
+            }
+        }
+    }
+    rules
+}
+
+// Finds recognised apps in the process list, heaviest first
+pub fn inspect(sys: &System, rules: &[AppRule]) -> Vec<RunningApp> {
+    let total_memory = sys.total_memory().max(1) as f64;
+    let total_cpu = (sys.cpus().len().max(1) * 100) as f64;
+    let processes = sys.processes().values().map(|process| (process.name(), process.exe(), process.memory(), process.cpu_usage() as f64));
+    tally(rules, processes, total_memory, total_cpu)
+}
+
+// Adds up (process name, executable, memory bytes, CPU percent) per app. A
+// process matches by its name or, failing that, its executable's file name.
+fn tally<'p>(rules: &[AppRule], processes: impl Iterator<Item = (&'p str, &'p Path, u64, f64)>, total_memory: f64, total_cpu: f64) -> Vec<RunningApp> {
+    let mut by_name: HashMap<String, usize> = HashMap::new();
+    for (index, rule) in rules.iter().enumerate() {
+        for name in &rule.names {
+            by_name.entry(name.to_lowercase()).or_insert(index);
+        }
+    }
+
+    // rule index -> (process count, memory bytes, cpu percent)
+    let mut usage: HashMap<usize, (usize, u64, f64)> = HashMap::new();
+    for (name, exe, memory, cpu) in processes {
+        let exe_name = exe.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let name = name.to_lowercase();
+        let index = by_name.get(&name).or_else(|| by_name.get(&exe_name));
+        if let Some(&index) = index {
+            let entry = usage.entry(index).or_insert((0, 0, 0.0));
+            entry.0 += 1;
+            entry.1 += memory;
+            entry.2 += cpu;
+        }
+    }
+
+    let mut apps: Vec<RunningApp> = usage
+        .into_iter()
+        .map(|(index, (processes, memory, cpu))| RunningApp {
+            id: rules[index].id.clone(),
+            label: rules[index].label.clone(),
+            processes,
+            mem_share: memory as f64 / total_memory,
+            cpu_share: cpu / total_cpu,
+        })
+        // Miners get roasted no matter how quiet they are
+        .filter(|app| app.id == "miner" || app.share() >= MIN_SHARE)
+        .collect();
+    apps.sort_by(|a, b| b.share().partial_cmp(&a.share()).unwrap_or(std::cmp::Ordering::Equal));
+    apps
+}
+
+// Fills the {app} and {share} placeholders of an app roast
+pub fn fill(roast: &str, app: &RunningApp) -> String {
+    roast
+        .replace("{app}", &app.label)
+        .replace("{share}", &format!("{:.0}%", app.share() * 100.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(text: &str, lang: Option<&str>) -> RoastPack {
+        RoastPack { lang: lang.map(String::from), ..toml::from_str(text).unwrap() }
+    }
+
+    fn rule<'r>(rules: &'r [AppRule], id: &str) -> &'r AppRule {
+        rules.iter().find(|rule| rule.id == id).unwrap()
+    }
+
+    #[test]
+    fn packs_extend_builtins_and_add_apps() {
+        let extra = pack(
+            r#"
+            [[apps]]
+            id = "firefox"
+            label = "Firefox Nightly"
+            names = ["firefox-nightly"]
+            roasts = ["Nightly, of course."]
+
+            [[apps]]
+            id = "zed"
+            names = ["zed"]
+            roasts = ["Zed is fast. You aren't."]
+
+            [[apps]]
+            names = ["nameless"]
+            "#,
+            None,
+        );
+        let translation = pack(
+            r#"
+            [[apps]]
+            id = "firefox"
+            roasts = ["Schon wieder Firefox."]
+            "#,
+            Some("de"),
+        );
+        let builtins = builtin_rules().len();
+        let rules = app_rules(&[extra, translation]);
+        // The entry without an id is skipped
+        assert_eq!(rules.len(), builtins + 1);
+
+        let firefox = rule(&rules, "firefox");
+        assert_eq!(firefox.label, "Firefox Nightly");
+        assert!(firefox.names.iter().any(|name| name == "firefox") && firefox.names.iter().any(|name| name == "firefox-nightly"));
+        assert_eq!(firefox.roasts.last().unwrap().text, "Nightly, of course.");
+        assert_eq!(firefox.localized.len(), 1);
+        assert_eq!(firefox.localized[0].text, "Schon wieder Firefox.");
+
+        let zed = rule(&rules, "zed");
+        assert_eq!(zed.label, "zed");
+        assert_eq!(zed.names, ["zed"]);
+        assert_eq!(zed.roasts.len(), 1);
+    }
+
+    #[test]
+    fn matches_by_name_or_executable() {
+        let rules = app_rules(&[]);
+        let processes = [
+            // Firefox's content processes are named after their thread
+            ("Isolated Web Co", Path::new("/usr/lib/firefox/firefox"), 300, 0.0),
+            ("firefox", Path::new("/usr/lib/firefox/firefox"), 500, 0.0),
+            ("Slack", Path::new(""), 200, 0.0),
+            ("bash", Path::new("/usr/bin/bash"), 900, 0.0),
+        ];
+        let apps = tally(&rules, processes.into_iter(), 1000.0, 100.0);
+        let ids: Vec<&str> = apps.iter().map(|app| app.id.as_str()).collect();
+        assert_eq!(ids, ["firefox", "slack"]);
+        assert_eq!(apps[0].processes, 2);
+        assert_eq!(apps[0].mem_share, 0.8);
+    }
+
+    #[test]
+    fn quiet_apps_are_dropped_but_miners_are_not() {
+        let rules = app_rules(&[]);
+        let processes = [
+            ("slack", Path::new(""), 5, 0.5),
+            ("xmrig", Path::new(""), 1, 0.0),
+            ("spotify", Path::new(""), 0, 4.0),
+        ];
+        let apps = tally(&rules, processes.into_iter(), 1000.0, 400.0);
+        let ids: Vec<&str> = apps.iter().map(|app| app.id.as_str()).collect();
+        // Spotify's 1% of the CPU is enough; Slack's half a percent of each isn't
+        assert_eq!(ids, ["spotify", "miner"]);
+    }
+
+    #[test]
+    fn fills_the_label_and_larger_share() {
+        let app = RunningApp { id: "chrome".to_string(), label: "Google Chrome".to_string(), processes: 12, mem_share: 0.426, cpu_share: 0.1 };
+        assert_eq!(fill("{app} is using {share} of your RAM", &app), "Google Chrome is using 43% of your RAM");
+    }
+}
@@ -0,0 +1,522 @@
+use crate::config::ProjectConfig;
+use crate::devenv::full_size;
+use crate::roast::Roast;
+use crate::snapshot::Snapshot;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// What `cargo roast` found in the current workspace
+pub struct Project {
+    pub name: String,
+    pub root: PathBuf,
+    pub edition: String,
+    pub members: usize,
+    // Distinct dependency names across all members (normal, dev and build)
+    pub dependencies: usize,
+    // Crates that appear in Cargo.lock with more than one version
+    pub duplicate_crates: Vec<String>,
+    pub unsafe_blocks: usize,
+    pub unwraps: usize,
+    // Non-blank, non-comment lines of Rust
+    pub code_lines: usize,
+    pub target_size: u64,
+}
+
+impl Project {
+    pub fn unwraps_per_kloc(&self) -> f64 {
+        self.unwraps as f64 * 1000.0 / self.code_lines.max(1) as f64
+    }
+}
+
+// Finds the workspace that contains `dir`: the outermost Cargo.toml with a
+// [workspace] table, or else the nearest Cargo.toml
+pub fn find_root(dir: &Path) -> Option<PathBuf> {
+    let manifests: Vec<&Path> = dir.ancestors().filter(|d| d.join("Cargo.toml").is_file()).collect();
+    manifests
+        .iter()
+        .rev()
+        .find(|d| read_manifest(d).is_some_and(|m| m.get("workspace").is_some()))
+        .or_else(|| manifests.first())
+        .map(|d| d.to_path_buf())
+}
+
+fn read_manifest(dir: &Path) -> Option<toml::Value> {
+    let text = fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    toml::from_str(&text).ok()
+}
+
+pub fn inspect(root: &Path) -> Result<Project, String> {
+    let manifest = read_manifest(root).ok_or_else(|| format!("can't read {}", root.join("Cargo.toml").display()))?;
+
+    // Member directories; "crates/*" style globs are expanded one level
+    let mut member_dirs: Vec<PathBuf> = Vec::new();
+    if manifest.get("package").is_some() {
+        member_dirs.push(root.to_path_buf());
+    }
+    let members = manifest
+        .get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(|m| m.as_array())
+        .cloned()
+        .unwrap_or_default();
+    for member in members.iter().filter_map(|m| m.as_str()) {
+        match member.strip_suffix("/*") {
+            Some(parent) => {
+                if let Ok(entries) = fs::read_dir(root.join(parent)) {
+                    member_dirs.extend(entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.join("Cargo.toml").is_file()));
+                }
+            }
+            None => member_dirs.push(root.join(member)),
+        }
+    }
+
+    let mut dependencies = BTreeSet::new();
+    let mut edition = None;
+    let mut name = None;
+    for dir in &member_dirs {
+        let member = match read_manifest(dir) {
+            Some(member) => member,
+            None => continue,
+        };
+        dependencies.extend(dependency_names(&member));
+        let package = member.get("package");
+        if name.is_none() {
+            name = package.and_then(|p| p.get("name")).and_then(|n| n.as_str()).map(String::from);
+        }
+        if edition.is_none() {
+            edition = package.and_then(|p| p.get("edition")).and_then(|e| e.as_str()).map(String::from);
+        }
+    }
+    let edition = edition
+        .or_else(|| {
+            let package = manifest.get("workspace")?.get("package")?;
+            package.get("edition")?.as_str().map(String::from)
+        })
+        .unwrap_or_else(|| "2015".to_string());
+
+    let sources = scan_sources(root);
+    let target = std::env::var_os("CARGO_TARGET_DIR").map_or_else(|| root.join("target"), PathBuf::from);
+
+    Ok(Project {
+        name: name.unwrap_or_else(|| root.file_name().map_or("workspace".to_string(), |n| n.to_string_lossy().to_string())),
+        root: root.to_path_buf(),
+        edition,
+        members: member_dirs.len(),
+        dependencies: dependencies.len(),
+        duplicate_crates: duplicate_crates(root),
+        unsafe_blocks: sources.unsafe_blocks,
+        unwraps: sources.unwraps,
+        code_lines: sources.code_lines,
+        // Exact, unlike the dev environment's capped walk: this is what the
+        // size roast is about, and `cargo roast` is run on purpose
+        target_size: full_size(&target),
+    })
+}
+
+fn dependency_names(manifest: &toml::Value) -> Vec<String> {
+    const TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+    let mut tables: Vec<&toml::Value> = TABLES.iter().filter_map(|t| manifest.get(*t)).collect();
+    // [target.'cfg(...)'.dependencies]
+    if let Some(targets) = manifest.get("target").and_then(|t| t.as_table()) {
+        for target in targets.values() {
+            tables.extend(TABLES.iter().filter_map(|t| target.get(*t)));
+        }
+    }
+    tables
+        .into_iter()
+        .filter_map(|table| table.as_table())
+        .flat_map(|table| table.keys().cloned())
+        .collect()
+}
+
+fn duplicate_crates(root: &Path) -> Vec<String> {
+    let lock: toml::Value = match fs::read_to_string(root.join("Cargo.lock")).ok().and_then(|t| toml::from_str(&t).ok()) {
+        Some(lock) => lock,
+        None => return Vec::new(),
+    };
+    let mut versions: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for package in lock.get("package").and_then(|p| p.as_array()).into_iter().flatten() {
+        if let (Some(name), Some(version)) = (package.get("name").and_then(|n| n.as_str()), package.get("version").and_then(|v| v.as_str())) {
+            versions.entry(name).or_default().insert(version);
+        }
+    }
+    versions
+        .into_iter()
+        .filter(|(_, versions)| versions.len() > 1)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+#[derive(Default)]
+struct SourceStats {
+    code_lines: usize,
+    unwraps: usize,
+    unsafe_blocks: usize,
+}
+
+// Walks every .rs file below root, skipping target/ and hidden directories
+fn scan_sources(root: &Path) -> SourceStats {
+    let mut stats = SourceStats::default();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(kind) if kind.is_dir() && !file_name.starts_with('.') && file_name != "target" => {
+                    pending.push(path);
+                }
+                Ok(kind) if kind.is_file() && file_name.ends_with(".rs") => {
+                    if let Ok(text) = fs::read_to_string(&path) {
+                        scan_file(&text, &mut stats);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    stats
+}
+
+fn scan_file(text: &str, stats: &mut SourceStats) {
+    for line in strip_strings(text).lines() {
+        let code = line.trim();
+        if code.is_empty() {
+            continue;
+        }
+        stats.code_lines += 1;
+        stats.unwraps += code.matches(".unwrap()").count();
+        let spaced = code.replace('{', " { ");
+        let words: Vec<&str> = spaced
+            .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '{'))
+            .filter(|w| !w.is_empty())
+            .collect();
+        // Only blocks: `unsafe fn`, `unsafe impl` and the like declare
+        // something unsafe rather than doing something unsafe
+        stats.unsafe_blocks += words.windows(2).filter(|pair| pair == &["unsafe", "{"]).count();
+    }
+}
+
+// Empties string literals (plain, byte and raw, keeping the quotes) and char
+// literals, and drops comments, keeping every newline so lines still line up.
+// Lifetimes like 'a are left alone.
+fn strip_strings(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let ident = |i: usize| chars.get(i).is_some_and(|c| c.is_alphanumeric() || *c == '_');
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match (c, chars.get(i + 1).copied()) {
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            ('/', Some('*')) => {
+                // Block comments nest
+                let mut depth = 0;
+                while i < chars.len() {
+                    match (chars[i], chars.get(i + 1).copied()) {
+                        ('/', Some('*')) => {
+                            depth += 1;
+                            i += 2;
+                        }
+                        ('*', Some('/')) => {
+                            depth -= 1;
+                            i += 2;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        ('\n', _) => {
+                            out.push('\n');
+                            i += 1;
+                        }
+                        _ => i += 1,
+                    }
+                }
+            }
+            ('"', _) => {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    match chars[i] {
+                        '\\' => i += 1,
+                        '\n' => out.push('\n'),
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                out.push_str("\"\"");
+                i += 1;
+            }
+            // r"...", r#"..."# and br"...", but not a name ending in r
+            ('r', Some('"' | '#')) if i == 0 || !ident(i - 1) || (chars[i - 1] == 'b' && (i == 1 || !ident(i - 2))) => {
+                let hashes = chars[i + 1..].iter().take_while(|c| **c == '#').count();
+                let open = i + 1 + hashes;
+                if chars.get(open) != Some(&'"') {
+                    // A raw identifier like r#type
+                    out.push(c);
+                    i += 1;
+                    continue;
+                }
+                i = open + 1;
+                while i < chars.len() && !(chars[i] == '"' && chars[i + 1..].iter().take(hashes).filter(|c| **c == '#').count() == hashes) {
+                    if chars[i] == '\n' {
+                        out.push('\n');
+                    }
+                    i += 1;
+                }
+                out.push_str("\"\"");
+                i += 1 + hashes;
+            }
+            // '"', '\'' and '\u{..}', but not the lifetime in &'a str
+            ('\'', Some('\\')) => {
+                i += 2;
+                while i < chars.len() && chars[i] != '\'' && chars[i] != '\n' {
+                    i += 1;
+                }
+                out.push_str("''");
+                i += 1;
+            }
+            ('\'', Some(_)) if chars.get(i + 2) == Some(&'\'') => {
+                out.push_str("''");
+                i += 3;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+// Names of the pools that apply to this project, each with its built-in lines
+pub fn roast_pools(project: &Project, config: &ProjectConfig, target_gb: f64) -> Vec<(&'static str, &'static [Roast])> {
+    let mut pools: Vec<(&'static str, &'static [Roast])> = Vec::new();
+    if project.dependencies >= config.dependencies {
+        pools.push(("project.dependencies", DEPENDENCY_ROASTS));
+    }
+    if matches!(project.edition.as_str(), "2015" | "2018") {
+        pools.push(("project.edition", EDITION_ROASTS));
+    }
+    if !project.duplicate_crates.is_empty() && project.duplicate_crates.len() >= config.duplicates {
+        pools.push(("project.duplicates", DUPLICATE_ROASTS));
+    }
+    if project.unsafe_blocks >= config.unsafe_blocks {
+        pools.push(("project.unsafe", UNSAFE_ROASTS));
+    }
+    if project.unwraps_per_kloc() >= config.unwraps_per_kloc {
+        pools.push(("project.unwrap", UNWRAP_ROASTS));
+    }
+    if project.target_size as f64 >= target_gb * 1024.0 * 1024.0 * 1024.0 {
+        pools.push(("project.target", TARGET_ROASTS));
+    }
+    if pools.is_empty() {
+        pools.push(("project.clean", CLEAN_ROASTS));
+    }
+    pools
+}
+
+const DEPENDENCY_ROASTS: &[Roast] = &[
+    Roast::gentle("{dependencies} dependencies. Your Cargo.toml reads like the credits of a Marvel movie."),
+    Roast::savage("{name} pulls in {dependencies} crates. Supply chain attack? More like supply chain buffet."),
+    Roast::standard("With {dependencies} dependencies, you didn't write a program, you curated one."),
+    Roast::standard("{dependencies} dependencies and a clean build still finishes sometime next Tuesday."),
+];
+
+const EDITION_ROASTS: &[Roast] = &[
+    Roast::standard("Edition {edition}? Your Rust is so old it still writes `extern crate`."),
+    Roast::standard("{name} is on the {edition} edition. Even the borrow checker has moved on."),
+    Roast::gentle("Edition {edition}. `cargo fix --edition` exists, and it's lonely."),
+];
+
+const DUPLICATE_ROASTS: &[Roast] = &[
+    Roast::standard("Cargo.lock has {duplicates} crates in multiple versions ({duplicate_list}). Compile once, compile twice, compile forever."),
+    Roast::gentle("{duplicates} duplicated crates. Your dependency tree has an identity crisis."),
+    Roast::standard("Two versions of {duplicate}? Your binary is paying rent for both."),
+];
+
+const UNSAFE_ROASTS: &[Roast] = &[
+    Roast::savage("{unsafe_blocks} unsafe blocks. You rewrote it in Rust, then rewrote it back in C."),
+    Roast::gentle("{unsafe_blocks} uses of unsafe. The borrow checker isn't mad, just disappointed."),
+    Roast::standard("With {unsafe_blocks} unsafe blocks, 'memory safe' is more of a marketing claim for {name}."),
+];
+
+const UNWRAP_ROASTS: &[Roast] = &[
+    Roast::gentle("{unwraps} calls to .unwrap(). Error handling is just optimism with extra steps, right?"),
+    Roast::standard("{unwrap_density} unwraps per thousand lines. Your panic handler works overtime."),
+    Roast::standard("{name} unwraps like it's Christmas morning. {unwraps} presents, zero error messages."),
+    Roast::savage(".unwrap() {unwraps} times. The `?` operator is right there, it's one character."),
+];
+
+const TARGET_ROASTS: &[Roast] = &[
+    Roast::standard("{name}'s target/ is {target}. The binary is 4 MB. The rest is your disk's problem."),
+    Roast::gentle("{target} of build artifacts. `cargo clean` is free and so is your disk, after."),
+];
+
+const CLEAN_ROASTS: &[Roast] = &[
+    Roast::savage("Few dependencies, no unsafe, barely any unwraps. Either you're good or you haven't written anything yet.").personal(),
+    Roast::gentle("{name} is suspiciously clean. Did you just run `cargo new`?"),
+    Roast::standard("Nothing to roast in {name}. That's the most damning review possible: it's boring."),
+];
+
+// The pools above, by name
+pub const POOLS: &[(&str, &[Roast])] = &[
+    ("project.dependencies", DEPENDENCY_ROASTS),
+    ("project.edition", EDITION_ROASTS),
+    ("project.duplicates", DUPLICATE_ROASTS),
+    ("project.unsafe", UNSAFE_ROASTS),
+    ("project.unwrap", UNWRAP_ROASTS),
+    ("project.target", TARGET_ROASTS),
+    ("project.clean", CLEAN_ROASTS),
+];
+
+// Facts for roast conditions, e.g. "project.unsafe_blocks > 20"
+pub fn snapshot(project: &Project) -> Snapshot {
+    let mut snapshot = Snapshot::default();
+    snapshot.set("project.name", project.name.as_str());
+    snapshot.set("project.edition", project.edition.as_str());
+    snapshot.set("project.members", project.members);
+    snapshot.set("project.dependencies", project.dependencies);
+    snapshot.set("project.duplicates", project.duplicate_crates.len());
+    snapshot.set("project.unsafe_blocks", project.unsafe_blocks);
+    snapshot.set("project.unwraps", project.unwraps);
+    snapshot.set("project.unwraps_per_kloc", project.unwraps_per_kloc());
+    snapshot.set("project.code_lines", project.code_lines);
+    snapshot.set("project.target_gb", project.target_size as f64 / 1024.0 / 1024.0 / 1024.0);
+    snapshot
+}
+
+// Fills the project placeholders ({name}, {dependencies}, {unwraps}, ...)
+pub fn fill(roast: &str, project: &Project) -> String {
+    let shown: Vec<&str> = project.duplicate_crates.iter().take(3).map(|s| s.as_str()).collect();
+    roast
+        .replace("{name}", &project.name)
+        .replace("{edition}", &project.edition)
+        .replace("{dependencies}", &project.dependencies.to_string())
+        .replace("{duplicates}", &project.duplicate_crates.len().to_string())
+        .replace("{duplicate_list}", &shown.join(", "))
+        .replace("{duplicate}", project.duplicate_crates.first().map_or("", |s| s.as_str()))
+        .replace("{unsafe_blocks}", &project.unsafe_blocks.to_string())
+        .replace("{unwraps}", &project.unwraps.to_string())
+        .replace("{unwrap_density}", &format!("{:.1}", project.unwraps_per_kloc()))
+        .replace("{target}", &crate::devenv::format_size(project.target_size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(text: &str) -> SourceStats {
+        let mut stats = SourceStats::default();
+        scan_file(text, &mut stats);
+        stats
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rust_roast-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn counts_only_unsafe_blocks() {
+        let stats = scan("unsafe fn raw() {}\nunsafe impl Send for X {}\npub unsafe trait Y {}\nlet x = unsafe { *p };\nunsafe{ f() }\n");
+        assert_eq!(stats.unsafe_blocks, 2);
+        assert_eq!(stats.code_lines, 5);
+    }
+
+    #[test]
+    fn ignores_strings_and_comments() {
+        let stats = scan("// x.unwrap()\nlet s = \"x.unwrap() unsafe {\";\n/* y.unwrap()\n   unsafe { } */\nz.unwrap(); // w.unwrap()\n");
+        assert_eq!(stats.unwraps, 1);
+        assert_eq!(stats.unsafe_blocks, 0);
+        assert_eq!(stats.code_lines, 2);
+    }
+
+    #[test]
+    fn char_literals_dont_open_strings() {
+        let stats = scan("if c == '\"' { a.unwrap(); }\nlet q = '\\''; let b = unsafe { *p };\nfn f<'a>(s: &'a str) { s.parse::<u8>().unwrap(); }\n");
+        assert_eq!(stats.unwraps, 2);
+        assert_eq!(stats.unsafe_blocks, 1);
+    }
+
+    #[test]
+    fn raw_and_multiline_strings() {
+        assert_eq!(strip_strings(r##"let s = r#"a "quoted" .unwrap()"#; x"##), "let s = \"\"; x");
+        assert_eq!(strip_strings("br\"\\\" r#type"), "b\"\" r#type");
+        let stats = scan("let s = \"first\n.unwrap()\nunsafe {\";\nlet t = r\"\n\".len(); u.unwrap();\n");
+        assert_eq!(stats.unwraps, 1);
+        assert_eq!(stats.unsafe_blocks, 0);
+        assert_eq!(stats.code_lines, 4);
+    }
+
+    #[test]
+    fn finds_crates_locked_at_two_versions() {
+        let dir = temp_dir("lock");
+        fs::write(
+            dir.join("Cargo.lock"),
+            r#"
+version = 3
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+
+[[package]]
+name = "syn"
+version = "2.0.48"
+
+[[package]]
+name = "serde"
+version = "1.0.195"
+"#,
+        )
+        .unwrap();
+        assert_eq!(duplicate_crates(&dir), ["syn"]);
+        let _ = fs::remove_dir_all(&dir);
+        assert!(duplicate_crates(&dir).is_empty());
+    }
+
+    #[test]
+    fn root_is_the_outermost_workspace() {
+        let dir = temp_dir("root");
+        let member = dir.join("crates").join("core");
+        let nested = member.join("src").join("bin");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(member.join("Cargo.toml"), "[package]\nname = \"core\"\n").unwrap();
+        // Without a workspace above, the nearest manifest
+        assert_eq!(find_root(&nested), Some(member.clone()));
+        fs::write(dir.join("Cargo.toml"), "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+        assert_eq!(find_root(&nested), Some(dir.clone()));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn no_duplicate_roast_without_duplicates() {
+        let project = Project {
+            name: "demo".to_string(),
+            root: PathBuf::new(),
+            edition: "2021".to_string(),
+            members: 1,
+            dependencies: 0,
+            duplicate_crates: Vec::new(),
+            unsafe_blocks: 0,
+            unwraps: 0,
+            code_lines: 100,
+            target_size: 0,
+        };
+        let config = ProjectConfig { duplicates: 0, ..ProjectConfig::default() };
+        let pools = roast_pools(&project, &config, 1.0);
+        assert!(!pools.iter().any(|(name, _)| *name == "project.duplicates"));
+        assert_eq!(fill("[{duplicate}]", &project), "[]");
+    }
+}
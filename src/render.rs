@@ -0,0 +1,373 @@
+use crate::card;
+use crate::html;
+use crate::i18n::tr;
+use crate::logo::Logo;
+use crate::markdown;
+use crate::roast::{Picked, Roaster};
+use crate::theme;
+use serde::Deserialize;
+use std::io::{IsTerminal, Write};
+use std::sync::{Mutex, OnceLock};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+// Shared terminal output so every binary looks the same
+
+// Narrower than this, a spec value moves under its label instead of beside it
+const MIN_VALUE_COLUMNS: usize = 20;
+
+// Columns between the logo and the text in the fetch layout
+const LOGO_GAP: usize = 3;
+
+// With less room than this beside the logo, the fetch layout leaves it out
+const MIN_TEXT_COLUMNS: usize = 40;
+
+// How the output is arranged: a list, or beside a logo like neofetch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    #[default]
+    Plain,
+    Fetch,
+}
+
+// What the output is: text for the terminal, an SVG or PNG card, a web page,
+// or Markdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Format {
+    #[default]
+    Text,
+    Svg,
+    Png,
+    Html,
+    Markdown,
+}
+
+// A heading and the spec lines under it
+#[derive(Debug, Clone, Default)]
+pub struct Section {
+    pub title: String,
+    pub specs: Vec<(String, String)>,
+}
+
+// Everything a run shows, kept for the formats that are laid out at the end
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub sections: Vec<Section>,
+    pub score: Option<u32>,
+    pub heading: String,
+    // Each roast with its category ("cpu" for a roast from "cpu.low"), or ""
+    // when that isn't known, as for a language model's roasts
+    pub roasts: Vec<(String, Picked)>,
+    pub snapshot: serde_json::Value,
+}
+
+static FORMAT: OnceLock<Format> = OnceLock::new();
+
+// Whether --format markdown adds the raw snapshot
+static DETAILS: OnceLock<bool> = OnceLock::new();
+
+static REPORT: Mutex<Report> = Mutex::new(Report {
+    sections: Vec::new(),
+    score: None,
+    heading: String::new(),
+    roasts: Vec::new(),
+    snapshot: serde_json::Value::Null,
+});
+
+// The fetch layout's logo, and the lines that go beside it until `finish`
+struct Fetch {
+    logo: Logo,
+    lines: Vec<String>,
+}
+
+static FETCH: Mutex<Option<Fetch>> = Mutex::new(None);
+
+static WIDTH: OnceLock<Option<usize>> = OnceLock::new();
+
+// Columns to wrap to: the terminal's width, or $COLUMNS when stdout isn't a
+// terminal. None (no wrapping) when neither is known.
+pub fn width() -> Option<usize> {
+    *WIDTH.get_or_init(|| {
+        let terminal = terminal_size::terminal_size().map(|(terminal_size::Width(columns), _)| columns as usize);
+        detect_width(terminal, std::env::var("COLUMNS").ok().as_deref())
+    })
+}
+
+// The terminal's width if there is one, else $COLUMNS if it's a number
+fn detect_width(terminal: Option<usize>, columns: Option<&str>) -> Option<usize> {
+    if let Some(terminal) = terminal {
+        return Some(terminal).filter(|&columns| columns > 0);
+    }
+    columns.and_then(|columns| columns.trim().parse().ok()).filter(|&columns| columns > 0)
+}
+
+// CJK punctuation a line shouldn't start or end with
+const NO_LINE_START: &[char] = &['、', '。', '，', '．', '）', '」', '』', '！', '？', 'ー'];
+const NO_LINE_END: &[char] = &['（', '「', '『'];
+
+fn is_wide(c: char) -> bool {
+    c.width() == Some(2)
+}
+
+// The pieces a line can break between, each with whether a space comes before
+// it: words, split further around CJK characters, which can break anywhere
+fn pieces(text: &str) -> Vec<(bool, String)> {
+    let mut pieces = Vec::new();
+    for word in text.split_whitespace() {
+        let mut space = true;
+        let mut piece = String::new();
+        for c in word.chars() {
+            if let Some(last) = piece.chars().last() {
+                let breakable = (is_wide(last) || is_wide(c)) && !NO_LINE_START.contains(&c) && !NO_LINE_END.contains(&last);
+                if breakable {
+                    pieces.push((space, std::mem::take(&mut piece)));
+                    space = false;
+                }
+            }
+            piece.push(c);
+        }
+        pieces.push((space, piece));
+    }
+    pieces
+}
+
+// Splits `text` into lines of at most `first` columns for the first line and
+// `rest` for the others, measured as the terminal shows them (CJK characters
+// take two columns). Lines break at spaces and between CJK characters; a word
+// too long for a line of its own is split wherever it has to be.
+pub fn wrap(text: &str, first: usize, rest: usize) -> Vec<String> {
+    if text.width() <= first {
+        return vec![text.to_string()];
+    }
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    let mut used = 0;
+    for (space, piece) in pieces(text) {
+        let limit = if lines.is_empty() { first } else { rest }.max(1);
+        let gap = usize::from(space && used > 0);
+        let piece_width = piece.width();
+        if used + gap + piece_width <= limit {
+            if gap > 0 {
+                line.push(' ');
+            }
+            line.push_str(&piece);
+            used += gap + piece_width;
+            continue;
+        }
+        if used > 0 {
+            lines.push(std::mem::take(&mut line));
+            used = 0;
+        }
+        for c in piece.chars() {
+            let limit = if lines.is_empty() { first } else { rest }.max(1);
+            let char_width = c.width().unwrap_or(0);
+            if used > 0 && used + char_width > limit {
+                lines.push(std::mem::take(&mut line));
+                used = 0;
+            }
+            line.push(c);
+            used += char_width;
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+pub fn set_format(format: Format) {
+    let _ = FORMAT.set(format);
+}
+
+fn format() -> Format {
+    FORMAT.get().copied().unwrap_or_default()
+}
+
+pub fn set_details(details: bool) {
+    let _ = DETAILS.set(details);
+}
+
+// Output from here to `finish` goes beside `logo`, unless the terminal is
+// too narrow for both
+pub fn begin_fetch(logo: Logo) {
+    if width().is_some_and(|width| width < logo.width + LOGO_GAP + MIN_TEXT_COLUMNS) {
+        return;
+    }
+    *FETCH.lock().unwrap() = Some(Fetch { logo, lines: Vec::new() });
+}
+
+// Writes out a card or page, or prints the logo and the lines held back for it side by side
+pub fn finish() {
+    match format() {
+        Format::Text => finish_fetch(),
+        Format::Svg => print!("{}", card::svg(&REPORT.lock().unwrap(), theme::current())),
+        Format::Html => print!("{}", html::page(&REPORT.lock().unwrap(), theme::current())),
+        Format::Markdown => print!("{}", markdown::page(&REPORT.lock().unwrap(), DETAILS.get().copied().unwrap_or(false))),
+        Format::Png => {
+            if std::io::stdout().is_terminal() {
+                eprintln!("rust_roast: not writing a PNG to the terminal; redirect it to a file");
+                std::process::exit(1);
+            }
+            let png = card::png(&card::svg(&REPORT.lock().unwrap(), theme::current())).and_then(|png| {
+                let mut stdout = std::io::stdout().lock();
+                stdout.write_all(&png).and_then(|_| stdout.flush()).map_err(|err| err.to_string())
+            });
+            if let Err(err) = png {
+                eprintln!("rust_roast: couldn't write the PNG: {}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+fn finish_fetch() {
+    let Some(Fetch { logo, lines }) = FETCH.lock().unwrap().take() else { return };
+    let start = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !line.is_empty()).map_or(start, |end| end + 1);
+    let lines = &lines[start..end];
+    println!();
+    for row in 0..logo.lines.len().max(lines.len()) {
+        let (art, art_width) = logo.lines.get(row).map_or(("", 0), |(art, width)| (art.as_str(), *width));
+        match lines.get(row).filter(|line| !line.is_empty()) {
+            Some(line) => println!("{}{:pad$}{}", art, "", line, pad = logo.width - art_width + LOGO_GAP),
+            None => println!("{}", art),
+        }
+    }
+    println!();
+}
+
+// Prints a line, or holds it for the fetch layout
+fn emit(line: String) {
+    match FETCH.lock().unwrap().as_mut() {
+        Some(fetch) => fetch.lines.push(line),
+        None => println!("{}", line),
+    }
+}
+
+// Columns left for text: the width, less the logo's column in the fetch layout
+fn columns() -> Option<usize> {
+    let logo = FETCH.lock().unwrap().as_ref().map_or(0, |fetch| fetch.logo.width + LOGO_GAP);
+    width().map(|width| width.saturating_sub(logo))
+}
+
+pub fn specs_header(title: &str) {
+    REPORT.lock().unwrap().sections.push(Section { title: title.to_string(), specs: Vec::new() });
+    if format() != Format::Text {
+        return;
+    }
+    emit(String::new());
+    emit(theme::current().header.paint(&format!("--- {} ---", title)));
+}
+
+// The machine's score, for cards and web pages
+pub fn score(score: Option<u32>) {
+    REPORT.lock().unwrap().score = score;
+}
+
+// "  label: value", with a long value wrapped under its first line, or under
+// the label when there's no room beside it
+pub fn spec(label: &str, value: &str) {
+    if let Some(section) = REPORT.lock().unwrap().sections.last_mut() {
+        section.specs.push((label.to_string(), value.to_string()));
+    }
+    if format() != Format::Text {
+        return;
+    }
+    let theme = theme::current();
+    let column = 2 + label.width() + 2;
+    match columns() {
+        Some(width) if width >= column + MIN_VALUE_COLUMNS.min(value.width()) => {
+            let mut lines = wrap(value, width - column, width - column).into_iter();
+            emit(format!("  {}: {}", theme.label.paint(label), theme.value.paint(&lines.next().unwrap_or_default())));
+            for line in lines {
+                emit(format!("{:column$}{}", "", theme.value.paint(&line)));
+            }
+        }
+        Some(width) => {
+            emit(format!("  {}:", theme.label.paint(label)));
+            for line in wrap(value, width.saturating_sub(4), width.saturating_sub(4)) {
+                emit(format!("    {}", theme.value.paint(&line)));
+            }
+        }
+        None => emit(format!("  {}: {}", theme.label.paint(label), theme.value.paint(value))),
+    }
+}
+
+// The roasts under a heading for the mode, each colored by the severity it
+// was written at. Each roast wraps with its later lines indented a little
+// further, so it's clear where the next one starts.
+pub fn roasts(roaster: &Roaster, roasts: &[Picked]) {
+    {
+        let mut report = REPORT.lock().unwrap();
+        report.heading = tr(roaster.mode.header()).to_string();
+        // Roasts from the pools each have a picked pool; a model's don't
+        let categories: Vec<&str> = if roaster.picked.len() == roasts.len() {
+            roaster.picked.iter().map(|pool| pool.split('.').next().unwrap_or(pool)).collect()
+        } else {
+            vec![""; roasts.len()]
+        };
+        report.roasts = categories.iter().map(|category| category.to_string()).zip(roasts.iter().cloned()).collect();
+        report.snapshot = serde_json::to_value(&roaster.snapshot).unwrap_or_default();
+    }
+    if format() != Format::Text {
+        return;
+    }
+    let theme = theme::current();
+    emit(String::new());
+    emit(theme.roast.paint(&format!("--- {} ---", tr(roaster.mode.header()))));
+    for roast in roasts {
+        let style = theme.severity(roast.severity);
+        let lines = match columns() {
+            Some(width) => wrap(&roast.text, width.saturating_sub(2), width.saturating_sub(4)),
+            None => vec![roast.text.clone()],
+        };
+        for (index, line) in lines.iter().enumerate() {
+            let indent = if index == 0 { 2 } else { 4 };
+            emit(format!("{:indent$}{}", "", style.paint(line), indent = indent));
+        }
+    }
+    emit(String::new());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_at_spaces() {
+        assert_eq!(wrap("short", 10, 10), ["short"]);
+        assert_eq!(wrap("your cpu is slower than a dial-up modem", 16, 12), ["your cpu is", "slower than", "a dial-up", "modem"]);
+        // No line ever ends up empty or starting with a space
+        assert_eq!(wrap("a  b", 1, 1), ["a", "b"]);
+    }
+
+    #[test]
+    fn splits_words_longer_than_a_line() {
+        assert_eq!(wrap("supercalifragilistic ok", 8, 8), ["supercal", "ifragili", "stic ok"]);
+        // Even a zero width takes a character per line rather than looping
+        assert_eq!(wrap("abc", 0, 0), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn counts_wide_characters_twice() {
+        // Eight CJK characters are sixteen columns
+        let lines = wrap("メモリが足りない", 8, 8);
+        assert_eq!(lines, ["メモリが", "足りない"]);
+        assert!(lines.iter().all(|line| line.width() <= 8));
+        // A line doesn't start with 。, so it goes down with the character before it
+        assert_eq!(wrap("遅いです。本当に", 8, 8), ["遅いで", "す。本当", "に"]);
+        // Mixed text breaks at spaces and between CJK characters
+        assert_eq!(wrap("CPU は遅い", 6, 6), ["CPU は", "遅い"]);
+    }
+
+    #[test]
+    fn width_falls_back_to_columns() {
+        assert_eq!(detect_width(Some(120), Some("80")), Some(120));
+        assert_eq!(detect_width(None, Some("80")), Some(80));
+        assert_eq!(detect_width(None, Some(" 72\n")), Some(72));
+        assert_eq!(detect_width(None, Some("wide")), None);
+        assert_eq!(detect_width(None, Some("0")), None);
+        assert_eq!(detect_width(None, None), None);
+        assert_eq!(detect_width(Some(0), Some("80")), None);
+    }
+}
@@ -0,0 +1,437 @@
+use crate::grammar::Grammar;
+use crate::config::LlmConfig;
+use crate::history::History;
+use crate::i18n;
+use crate::llm;
+use crate::markov::{self, Markov};
+use crate::pack::{self, RoastPack};
+use crate::snapshot::{self, Snapshot};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Gentle,
+    #[default]
+    Standard,
+    Savage,
+}
+
+impl Severity {
+    // This severity followed by every milder one
+    fn and_milder(self) -> impl Iterator<Item = Severity> {
+        [Severity::Savage, Severity::Standard, Severity::Gentle]
+            .into_iter()
+            .filter(move |s| *s <= self)
+    }
+}
+
+// What a run says about the machine: roasts, compliments ("toasts"), or each
+// roast followed by a backhanded compliment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    #[default]
+    Roast,
+    Toast,
+    Mixed,
+}
+
+impl Mode {
+    // The i18n key for the heading above the lines
+    pub fn header(self) -> &'static str {
+        match self {
+            Mode::Toast => "header.toast",
+            Mode::Roast | Mode::Mixed => "header.roast",
+        }
+    }
+}
+
+// Where roasts come from: the pools as written, generated from the packs'
+// grammars, remixed from all the lines by a Markov chain, or written by a
+// language model behind an OpenAI-compatible API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    #[default]
+    Pools,
+    Grammar,
+    Markov,
+    Llm,
+}
+
+// A single roast line. Built-in pools are `&[Roast]` consts; packs add owned ones.
+#[derive(Debug, Clone)]
+pub struct Roast {
+    pub text: Cow<'static, str>,
+    pub severity: Severity,
+    // Jokes about the person rather than the machine; dropped in workplace-safe mode
+    pub personal: bool,
+    // Only eligible when this holds for the snapshot, e.g. "ram.total_gb < 4 && form_factor == laptop"
+    pub condition: Option<Cow<'static, str>>,
+    // Relative odds among lines that match equally specifically
+    pub weight: f64,
+}
+
+impl Roast {
+    const fn new(text: &'static str, severity: Severity) -> Roast {
+        Roast { text: Cow::Borrowed(text), severity, personal: false, condition: None, weight: 1.0 }
+    }
+
+    pub const fn gentle(text: &'static str) -> Roast {
+        Roast::new(text, Severity::Gentle)
+    }
+
+    pub const fn standard(text: &'static str) -> Roast {
+        Roast::new(text, Severity::Standard)
+    }
+
+    pub const fn savage(text: &'static str) -> Roast {
+        Roast::new(text, Severity::Savage)
+    }
+
+    pub const fn personal(mut self) -> Roast {
+        self.personal = true;
+        self
+    }
+
+    pub const fn when(mut self, condition: &'static str) -> Roast {
+        // Built-in lines start without a condition, so there's nothing to drop;
+        // forgetting the old value is what lets this stay a const fn
+        std::mem::forget(self.condition.replace(Cow::Borrowed(condition)));
+        self
+    }
+
+    pub const fn weight(mut self, weight: f64) -> Roast {
+        self.weight = weight;
+        self
+    }
+
+    // How specific a match this line is for the snapshot: 0 for unconditional lines,
+    // the number of clauses that held otherwise. None if it doesn't apply (or its
+    // condition doesn't parse; `rust_roast lint` reports those).
+    pub fn specificity(&self, snapshot: &Snapshot) -> Option<usize> {
+        match &self.condition {
+            None => Some(0),
+            Some(condition) => snapshot::check(condition, snapshot).ok().flatten(),
+        }
+    }
+}
+
+// Which roasts the user wants to see
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tone {
+    pub severity: Severity,
+    pub workplace_safe: bool,
+}
+
+impl Tone {
+    pub fn allows(&self, roast: &Roast) -> bool {
+        roast.severity <= self.severity && !(self.workplace_safe && roast.personal)
+    }
+}
+
+// How often the grammar is expanded again when it produces a recent roast
+const GENERATE_ATTEMPTS: usize = 8;
+
+// A line as picked, with the severity it was written at, which is what it's
+// styled by. Generated lines and a model's are at the severity asked for.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Picked {
+    pub text: String,
+    pub severity: Severity,
+}
+
+impl Picked {
+    // The same line with its text changed, e.g. placeholders filled
+    pub fn map(self, f: impl FnOnce(&str) -> String) -> Picked {
+        Picked { text: f(&self.text), severity: self.severity }
+    }
+}
+
+// Picks a roast at the requested severity, falling back to milder ones
+// (never harsher) when the pool has none at that level. Among the lines whose
+// conditions hold, only the most specific ones are considered, chosen by weight.
+pub fn pick<'a, R: Rng>(pool: &'a [Roast], tone: &Tone, snapshot: &Snapshot, rng: &mut R) -> Option<&'a Roast> {
+    for severity in tone.severity.and_milder() {
+        let matching: Vec<(&Roast, usize)> = pool
+            .iter()
+            .filter(|roast| roast.severity == severity && tone.allows(roast))
+            .filter_map(|roast| roast.specificity(snapshot).map(|specificity| (roast, specificity)))
+            .collect();
+        let best = match matching.iter().map(|(_, specificity)| *specificity).max() {
+            Some(best) => best,
+            None => continue,
+        };
+        let candidates: Vec<&Roast> = matching
+            .into_iter()
+            .filter(|(_, specificity)| *specificity == best)
+            .map(|(roast, _)| roast)
+            .collect();
+        // All-zero weights mean "never", so fall through to milder lines
+        if let Ok(roast) = candidates.choose_weighted(rng, |roast| roast.weight.max(0.0)) {
+            return Some(roast);
+        }
+    }
+    None
+}
+
+// Everything a run needs to choose roasts: the loaded packs, their grammar and
+// Markov chain, the mode, backend and tone, the model server, the snapshot
+// conditions are checked against, the recent history and the RNG
+pub struct Roaster {
+    pub packs: Vec<RoastPack>,
+    pub grammar: Grammar,
+    // Trained on first use, once the snapshot is known
+    pub markov: Option<Markov>,
+    pub mode: Mode,
+    pub backend: Backend,
+    pub tone: Tone,
+    pub llm: LlmConfig,
+    pub snapshot: Snapshot,
+    pub history: History,
+    pub rng: StdRng,
+    pub seed: Option<u64>,
+    // The pool of every roast picked so far, in order, for output that
+    // groups roasts by category
+    pub picked: Vec<String>,
+}
+
+impl Roaster {
+    pub fn new(packs: Vec<RoastPack>, tone: Tone, history: History) -> Roaster {
+        Roaster {
+            grammar: Grammar::from_packs(&packs),
+            markov: None,
+            packs,
+            mode: Mode::Roast,
+            backend: Backend::Pools,
+            tone,
+            llm: LlmConfig::default(),
+            snapshot: Snapshot::default(),
+            history,
+            rng: StdRng::from_entropy(),
+            seed: None,
+            picked: Vec::new(),
+        }
+    }
+
+    // A roaster set up one setting at a time; whatever isn't set is as `new`
+    // leaves it, with the standard tone and no history
+    pub fn builder(packs: Vec<RoastPack>) -> RoasterBuilder {
+        RoasterBuilder { roaster: Roaster::new(packs, Tone::default(), History::disabled()) }
+    }
+
+    // Makes every choice repeatable, including the model's where the server
+    // supports seeds
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.seed = Some(seed);
+    }
+
+    // With --backend llm, all of the run's roasts from the model in one
+    // request. None if the backend is something else, or (after saying why)
+    // if the request fails, so the caller uses the pools instead.
+    pub fn llm_roasts(&mut self) -> Option<Vec<Picked>> {
+        if self.backend != Backend::Llm {
+            return None;
+        }
+        let request = llm::Request {
+            snapshot: &self.snapshot,
+            tone: self.tone,
+            mode: self.mode,
+            language: i18n::languages().first().map(String::as_str),
+            seed: self.seed,
+        };
+        match llm::roasts(&self.llm, &request) {
+            Ok(roasts) => {
+                for roast in &roasts {
+                    self.history.record(roast);
+                }
+                let severity = self.tone.severity;
+                Some(roasts.into_iter().map(|text| Picked { text, severity }).collect())
+            }
+            Err(err) => {
+                eprintln!("rust_roast: model server failed, using the built-in roasts: {}", err);
+                None
+            }
+        }
+    }
+
+    // Picks a line from a named pool (built-in plus packs), in the user's
+    // language if a translation pack covers it
+    pub fn pick(&mut self, name: &str, builtin: &[Roast]) -> Option<Picked> {
+        let localized = pack::localized_pool(name, &self.packs);
+        let lines = pack::pool(name, builtin, &self.packs);
+        self.pick_entry(name, &localized, &lines)
+    }
+
+    // Picks for the pool `name` according to the mode. `localized` and
+    // `english` are its roasts; compliments come from the parallel
+    // "toast.<name>" and "backhanded.<name>" pools instead.
+    pub fn pick_entry(&mut self, name: &str, localized: &[Roast], english: &[Roast]) -> Option<Picked> {
+        let picked = match self.mode {
+            Mode::Roast => self.pick_lines(name, localized, english),
+            Mode::Toast => self.compliment("toast", name),
+            Mode::Mixed => {
+                let roast = self.pick_lines(name, localized, english)?;
+                match self.compliment("backhanded", name) {
+                    Some(compliment) => Some(roast.map(|text| format!("{} {}", text, compliment.text))),
+                    None => Some(roast),
+                }
+            }
+        };
+        if picked.is_some() {
+            self.picked.push(name.to_string());
+        }
+        picked
+    }
+
+    // A line from "<kind>.<name>", or from the whole category ("toast.os" for
+    // "os.arch") when nothing covers that exact pool
+    fn compliment(&mut self, kind: &str, name: &str) -> Option<Picked> {
+        let category = name.split('.').next().unwrap_or(name);
+        for pool in [name, category] {
+            let pool = format!("{}.{}", kind, pool);
+            let localized = pack::localized_pool(&pool, &self.packs);
+            let lines = pack::pool(&pool, &[], &self.packs);
+            if let Some(line) = self.pick_lines(&pool, &localized, &lines) {
+                return Some(line);
+            }
+        }
+        None
+    }
+
+    // A line for the pool `name` from the backend, or from the pool itself
+    // when the backend has nothing for it
+    fn pick_lines(&mut self, name: &str, localized: &[Roast], english: &[Roast]) -> Option<Picked> {
+        self.generate(name, localized, english).or_else(|| self.pick_localized(localized, english))
+    }
+
+    // A generated line for the pool, retrying a few times for a roast that
+    // wasn't shown recently
+    fn generate(&mut self, name: &str, localized: &[Roast], english: &[Roast]) -> Option<Picked> {
+        let mut text = None;
+        for _ in 0..GENERATE_ATTEMPTS {
+            text = match self.backend {
+                Backend::Pools | Backend::Llm => return None,
+                Backend::Grammar => self.expand(name),
+                Backend::Markov => self.remix(name, localized, english),
+            };
+            if text.as_deref().is_none_or(|text| !self.history.seen(text)) {
+                break;
+            }
+        }
+        let text = text?;
+        self.history.record(&text);
+        Some(Picked { text, severity: self.tone.severity })
+    }
+
+    // Expands the grammar symbol for the pool, or for its category
+    fn expand(&mut self, name: &str) -> Option<String> {
+        let category = name.split('.').next().unwrap_or(name);
+        let symbol = [name, category].into_iter().find(|symbol| self.grammar.has(symbol))?;
+        self.grammar.expand(symbol, &self.tone, &self.snapshot, &mut self.rng)
+    }
+
+    // Remixes the pool's lines with everything else the chain has learned.
+    // Remixes are English only, so translated pools and compliments are left alone.
+    fn remix(&mut self, name: &str, localized: &[Roast], english: &[Roast]) -> Option<String> {
+        if !localized.is_empty() || markov::is_compliment(name) {
+            return None;
+        }
+        let pool = markov::usable(english, &self.tone, &self.snapshot);
+        let (packs, tone, snapshot) = (&self.packs, &self.tone, &self.snapshot);
+        let chain = self.markov.get_or_insert_with(|| Markov::train(markov::corpus(packs, tone, snapshot)));
+        chain.remix(&pool, &mut self.rng)
+    }
+
+    // Prefers translated lines, falling back to the English ones when there
+    // are none (or none that suit the tone and snapshot)
+    pub fn pick_localized(&mut self, localized: &[Roast], english: &[Roast]) -> Option<Picked> {
+        self.pick_from(localized).or_else(|| self.pick_from(english))
+    }
+
+    // Picks from an already merged pool, preferring lines not shown recently.
+    // A repeat beats saying nothing, so a fully seen pool is used as is.
+    pub fn pick_from(&mut self, pool: &[Roast]) -> Option<Picked> {
+        let fresh: Vec<Roast> = pool.iter().filter(|roast| !self.history.seen(&roast.text)).cloned().collect();
+        let roast = match pick(&fresh, &self.tone, &self.snapshot, &mut self.rng) {
+            Some(roast) => roast,
+            None => pick(pool, &self.tone, &self.snapshot, &mut self.rng)?,
+        };
+        self.history.record(&roast.text);
+        Some(Picked { text: roast.text.to_string(), severity: roast.severity })
+    }
+}
+
+pub struct RoasterBuilder {
+    roaster: Roaster,
+}
+
+impl RoasterBuilder {
+    pub fn tone(mut self, tone: Tone) -> RoasterBuilder {
+        self.roaster.tone = tone;
+        self
+    }
+
+    pub fn history(mut self, history: History) -> RoasterBuilder {
+        self.roaster.history = history;
+        self
+    }
+
+    pub fn mode(mut self, mode: Mode) -> RoasterBuilder {
+        self.roaster.mode = mode;
+        self
+    }
+
+    pub fn backend(mut self, backend: Backend) -> RoasterBuilder {
+        self.roaster.backend = backend;
+        self
+    }
+
+    pub fn llm(mut self, llm: LlmConfig) -> RoasterBuilder {
+        self.roaster.llm = llm;
+        self
+    }
+
+    // None keeps the choices random
+    pub fn seed(mut self, seed: Option<u64>) -> RoasterBuilder {
+        if let Some(seed) = seed {
+            self.roaster.set_seed(seed);
+        }
+        self
+    }
+
+    pub fn build(self) -> Roaster {
+        self.roaster
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POOL: &[Roast] = &[Roast::gentle("Mild."), Roast::standard("Medium.")];
+
+    #[test]
+    fn picked_lines_keep_their_severity() {
+        let mut roaster = Roaster::builder(Vec::new()).tone(Tone { severity: Severity::Savage, workplace_safe: false }).seed(Some(1)).build();
+        // Nothing savage, so the next milder line, which says so
+        let picked = roaster.pick_from(POOL).unwrap();
+        assert_eq!(picked, Picked { text: "Medium.".to_string(), severity: Severity::Standard });
+        roaster.tone.severity = Severity::Gentle;
+        assert_eq!(roaster.pick_from(POOL).unwrap().severity, Severity::Gentle);
+    }
+
+    #[test]
+    fn mixed_lines_take_the_roasts_severity() {
+        let mut roaster = Roaster::builder(Vec::new()).mode(Mode::Mixed).seed(Some(1)).build();
+        let picked = roaster.pick_entry("cpu.low", &[], POOL).unwrap();
+        assert_eq!(picked.severity, Severity::Standard);
+        assert_eq!(roaster.picked, ["cpu.low"]);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::snapshot::{Fact, Snapshot};
+
+// Points per part of the machine; they add up to 100
+const CPU_POINTS: f64 = 25.0;
+const RAM_POINTS: f64 = 25.0;
+const GPU_POINTS: f64 = 20.0;
+const STORAGE_POINTS: f64 = 15.0;
+const UPKEEP_POINTS: f64 = 15.0;
+
+// Full marks at this many cores and this much RAM
+const FULL_CORES: f64 = 16.0;
+const FULL_RAM_GB: f64 = 32.0;
+
+fn number(snapshot: &Snapshot, key: &str) -> Option<f64> {
+    match snapshot.get(key) {
+        Some(Fact::Number(value)) => Some(*value),
+        _ => None,
+    }
+}
+
+fn flag(snapshot: &Snapshot, key: &str) -> bool {
+    snapshot.get(key) == Some(&Fact::Bool(true))
+}
+
+// How good the machine is, out of 100: cores, RAM, the GPU tier, SSD vs HDD,
+// and upkeep (a supported OS on a recent kernel). None for snapshots that
+// aren't of a machine, like a project's or a repository's.
+pub fn score(snapshot: &Snapshot) -> Option<u32> {
+    let cores = number(snapshot, "cpu.cores")?;
+    let ram_gb = number(snapshot, "ram.total_gb").unwrap_or(0.0);
+    let cpu = (cores / FULL_CORES).min(1.0) * CPU_POINTS;
+    let ram = (ram_gb / FULL_RAM_GB).min(1.0) * RAM_POINTS;
+    let gpu = match snapshot.get("gpu.tier") {
+        Some(Fact::Text(tier)) if tier == "high_end" => GPU_POINTS,
+        Some(Fact::Text(tier)) if tier == "low_end" => GPU_POINTS * 0.6,
+        _ => GPU_POINTS * 0.25,
+    };
+    let storage = if flag(snapshot, "storage.ssd") {
+        STORAGE_POINTS
+    } else if flag(snapshot, "storage.hdd") {
+        STORAGE_POINTS / 3.0
+    } else {
+        STORAGE_POINTS * 2.0 / 3.0
+    };
+    // An end-of-life OS costs half, and each year of kernel age a tenth
+    let mut upkeep = UPKEEP_POINTS;
+    if flag(snapshot, "os.eol") {
+        upkeep -= UPKEEP_POINTS / 2.0;
+    }
+    upkeep -= number(snapshot, "kernel.age_years").unwrap_or(0.0) * UPKEEP_POINTS / 10.0;
+    let total = cpu + ram + gpu + storage + upkeep.max(0.0);
+    Some(total.round().clamp(0.0, 100.0) as u32)
+}
+
+// A school grade for a score: A from 85, B from 70, C from 55, D from 40, F below
+pub fn grade(score: u32) -> &'static str {
+    match score {
+        85.. => "A",
+        70..=84 => "B",
+        55..=69 => "C",
+        40..=54 => "D",
+        _ => "F",
+    }
+}
@@ -0,0 +1,293 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// Everything a run found out about the machine (or project, or repository), as flat
+// dotted keys like "ram.total_gb" or "form_factor". Roast conditions are checked
+// against it.
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(transparent)]
+pub struct Snapshot {
+    facts: BTreeMap<String, Fact>,
+}
+
+// Every fact a run can set, so conditions can be checked for typos
+pub const FACTS: &[&str] = &[
+    "cpu.brand", "cpu.cores", "cpu.mhz", "ram.total_gb", "ram.used_gb", "gpu.name", "gpu.tier", "form_factor", "chassis",
+    "storage.hdd", "storage.ssd", "power.profile", "power.on_battery", "power.battery_percent", "os.id", "os.version",
+    "os.rolling", "os.eol", "kernel.version", "kernel.age_years", "packages.total", "packages.cargo_bins", "dev.shell",
+    "dev.editor", "dev.toolchains", "dev.nightly", "dev.cargo_registry_gb", "desktop.name", "desktop.wm",
+    "desktop.server", "desktop.tiling", "desktop.displays", "desktop.max_hz", "uptime.days", "uptime.boots_today",
+    "app.top", "app.mem_share", "app.cpu_share", "project.name", "project.edition", "project.members",
+    "project.dependencies", "project.duplicates", "project.unsafe_blocks", "project.unwraps", "project.unwraps_per_kloc",
+    "project.code_lines", "project.target_gb", "git.commits", "git.lazy_commits", "git.lazy_share", "git.night_share",
+    "git.branches", "git.forced_updates", "git.rewrites", "git.largest_blob_mb", "live.cpu", "live.ram", "live.gpu",
+    "live.swap_gb", "live.temperature",
+];
+
+// Groups whose facts are named after what was found: "packages.<manager>"
+// and "app.<id>"
+const OPEN_GROUPS: &[&str] = &["packages", "app"];
+
+// Facts that hold text, even when it looks like a number ("22.04", "2021")
+const TEXT_FACTS: &[&str] = &[
+    "cpu.brand", "gpu.name", "gpu.tier", "form_factor", "chassis", "power.profile", "os.id", "os.version",
+    "kernel.version", "dev.shell", "dev.editor", "desktop.name", "desktop.wm", "desktop.server", "app.top",
+    "project.name", "project.edition",
+];
+
+// Whether a run can set `key`
+pub fn is_fact(key: &str) -> bool {
+    FACTS.contains(&key) || key.split_once('.').is_some_and(|(group, name)| OPEN_GROUPS.contains(&group) && !name.is_empty())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Fact {
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+// Whole numbers print without decimals, others with one: "16", "5.9"
+impl std::fmt::Display for Fact {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Fact::Bool(value) => write!(f, "{}", value),
+            Fact::Number(value) if value.fract() == 0.0 => write!(f, "{}", value),
+            Fact::Number(value) => write!(f, "{:.1}", value),
+            Fact::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<bool> for Fact {
+    fn from(value: bool) -> Fact {
+        Fact::Bool(value)
+    }
+}
+
+impl From<f64> for Fact {
+    fn from(value: f64) -> Fact {
+        Fact::Number(value)
+    }
+}
+
+impl From<u64> for Fact {
+    fn from(value: u64) -> Fact {
+        Fact::Number(value as f64)
+    }
+}
+
+impl From<usize> for Fact {
+    fn from(value: usize) -> Fact {
+        Fact::Number(value as f64)
+    }
+}
+
+impl From<&str> for Fact {
+    fn from(value: &str) -> Fact {
+        Fact::Text(value.to_string())
+    }
+}
+
+impl From<String> for Fact {
+    fn from(value: String) -> Fact {
+        Fact::Text(value)
+    }
+}
+
+impl Snapshot {
+    pub fn set(&mut self, key: &str, value: impl Into<Fact>) {
+        self.facts.insert(key.to_string(), value.into());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Fact> {
+        self.facts.get(key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Fact)> {
+        self.facts.iter()
+    }
+}
+
+// Comparison operators. Where two match at the same spot ("<=" and "<") the longer wins.
+const OPERATORS: &[&str] = &["<=", ">=", "==", "!=", "<", ">"];
+
+// One `key op value` test, or a bare key that must be true
+struct Clause<'a> {
+    key: &'a str,
+    op: &'a str,
+    value: Fact,
+    // The value as written
+    literal: &'a str,
+}
+
+fn parse_clause(text: &str) -> Result<Clause<'_>, String> {
+    let text = text.trim();
+    let found = OPERATORS
+        .iter()
+        .filter_map(|op| text.find(op).map(|at| (at, *op)))
+        .min_by_key(|(at, op)| (*at, std::cmp::Reverse(op.len())));
+    let (key, op, value) = match found {
+        Some((at, op)) => (text[..at].trim(), op, text[at + op.len()..].trim()),
+        None => (text, "==", "true"),
+    };
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return Err(format!("expected `field op value`, got \"{}\"", text));
+    }
+    if value.is_empty() {
+        return Err(format!("missing value after \"{}\" in \"{}\"", op, text));
+    }
+    let literal = value;
+    let value = if let Some(quoted) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Fact::Text(quoted.to_string())
+    } else if let Ok(flag) = value.parse::<bool>() {
+        Fact::Bool(flag)
+    } else if let Ok(number) = value.parse::<f64>() {
+        Fact::Number(number)
+    } else {
+        Fact::Text(value.to_string())
+    };
+    Ok(Clause { key, op, value, literal })
+}
+
+impl Clause<'_> {
+    fn holds(&self, snapshot: &Snapshot) -> bool {
+        let ordering = match (snapshot.get(self.key), &self.value) {
+            (Some(Fact::Number(a)), Fact::Number(b)) => a.partial_cmp(b),
+            (Some(Fact::Text(a)), Fact::Text(b)) => Some(a.to_lowercase().cmp(&b.to_lowercase())),
+            (Some(Fact::Bool(a)), Fact::Bool(b)) => Some(a.cmp(b)),
+            // Missing fields and fields of another type simply don't match
+            _ => None,
+        };
+        ordering.is_some_and(|ordering| match self.op {
+            "==" => ordering.is_eq(),
+            "!=" => ordering.is_ne(),
+            "<" => ordering.is_lt(),
+            "<=" => ordering.is_le(),
+            ">" => ordering.is_gt(),
+            _ => ordering.is_ge(),
+        })
+    }
+}
+
+// Checks a condition like `ram.total_gb < 4 && form_factor == laptop`. `&&` binds
+// tighter than `||`. Returns how specific the match was (clauses in the
+// alternative that held), or None if it doesn't hold.
+pub fn check(condition: &str, snapshot: &Snapshot) -> Result<Option<usize>, String> {
+    let mut best = None;
+    for alternative in condition.split("||") {
+        let clauses = alternative.split("&&").map(parse_clause).collect::<Result<Vec<_>, _>>()?;
+        if clauses.iter().all(|clause| clause.holds(snapshot)) {
+            best = best.max(Some(clauses.len()));
+        }
+    }
+    Ok(best)
+}
+
+// Parses a condition without evaluating it, for the pack linter. Facts no
+// run sets are errors too, since a typo would just never match, and so are
+// unquoted numbers compared with text facts, for the same reason.
+pub fn validate(condition: &str) -> Result<(), String> {
+    for clause in condition.split("||").flat_map(|alternative| alternative.split("&&")) {
+        let clause = parse_clause(clause)?;
+        if !is_fact(clause.key) {
+            return Err(format!("unknown fact \"{}\"", clause.key));
+        }
+        if matches!(clause.value, Fact::Number(_)) && TEXT_FACTS.contains(&clause.key) {
+            return Err(format!("\"{}\" is text, so {} never matches; quote it: \"{}\"", clause.key, clause.literal, clause.literal));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> Snapshot {
+        let mut snapshot = Snapshot::default();
+        snapshot.set("ram.total_gb", 4.0);
+        snapshot.set("cpu.cores", 2u64);
+        snapshot.set("form_factor", "laptop");
+        snapshot.set("os.version", "22.04");
+        snapshot.set("storage.hdd", true);
+        snapshot
+    }
+
+    fn holds(condition: &str) -> bool {
+        check(condition, &snapshot()).unwrap().is_some()
+    }
+
+    #[test]
+    fn comparisons() {
+        assert!(holds("ram.total_gb < 8"));
+        assert!(holds("ram.total_gb <= 4"));
+        assert!(!holds("ram.total_gb < 4"));
+        assert!(holds("ram.total_gb >= 4"));
+        assert!(!holds("ram.total_gb > 4"));
+        assert!(holds("ram.total_gb == 4"));
+        assert!(holds("ram.total_gb != 8"));
+        // Text ignores case, and a bare key means "is true"
+        assert!(holds("form_factor == LAPTOP"));
+        assert!(holds("storage.hdd"));
+        assert!(!holds("storage.hdd == false"));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        // (false && true) || true
+        assert!(holds("ram.total_gb > 8 && cpu.cores == 2 || form_factor == laptop"));
+        // true || (false && true)
+        assert!(holds("form_factor == laptop || ram.total_gb > 8 && cpu.cores == 2"));
+        // (true && false) || false
+        assert!(!holds("form_factor == laptop && ram.total_gb > 8 || cpu.cores > 2"));
+        // Specificity is the number of clauses in the best alternative that held
+        assert_eq!(check("cpu.cores == 2 || cpu.cores == 2 && form_factor == laptop", &snapshot()), Ok(Some(2)));
+    }
+
+    #[test]
+    fn quoted_values_stay_text() {
+        // Unquoted, 22.04 is a number and doesn't match the text fact
+        assert!(!holds("os.version == 22.04"));
+        assert!(holds("os.version == \"22.04\""));
+        assert!(holds("form_factor == \"laptop\""));
+    }
+
+    #[test]
+    fn missing_keys_and_other_types_dont_match() {
+        assert!(!holds("gpu.tier == integrated"));
+        assert!(!holds("gpu.tier != integrated"));
+        assert!(!holds("form_factor > 4"));
+        assert!(!holds("ram.total_gb == laptop"));
+    }
+
+    #[test]
+    fn malformed_clauses_are_errors() {
+        assert!(check("ram.total_gb <", &snapshot()).is_err());
+        assert!(check("< 4", &snapshot()).is_err());
+        assert!(check("ram total_gb < 4", &snapshot()).is_err());
+        assert!(check("ram.total_gb < 4 &&", &snapshot()).is_err());
+        assert!(validate("cpu.cores >").is_err());
+    }
+
+    #[test]
+    fn validate_knows_the_facts() {
+        assert_eq!(validate("ram.total_gb < 4 && form_factor == laptop || live.cpu > 90"), Ok(()));
+        // Facts named after what was found
+        assert_eq!(validate("app.slack && packages.snap > 10"), Ok(()));
+        assert_eq!(validate("cpu.cores_count > 4"), Err("unknown fact \"cpu.cores_count\"".to_string()));
+        assert!(validate("ram.total_gb < 4 || formfactor == laptop").is_err());
+        assert!(validate("app.").is_err());
+    }
+
+    #[test]
+    fn validate_wants_text_facts_quoted() {
+        assert_eq!(validate("os.version == 22.04"), Err("\"os.version\" is text, so 22.04 never matches; quote it: \"22.04\"".to_string()));
+        assert!(validate("os.id == ubuntu && project.edition < 2021").is_err());
+        assert_eq!(validate("os.version == \"22.04\" && project.edition == \"2018\""), Ok(()));
+        // Numbers stay fine for number facts, and words for text ones
+        assert_eq!(validate("kernel.age_years > 2 && os.id == ubuntu"), Ok(()));
+        assert!(TEXT_FACTS.iter().all(|key| is_fact(key)));
+    }
+}
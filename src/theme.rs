@@ -0,0 +1,511 @@
+use crate::config::config_dir;
+use crate::roast::Severity;
+use serde::Deserialize;
+use std::fs;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+// Themes shipped with the binary, by name
+pub const BUILTIN_THEMES: &[(&str, &str)] = &[
+    ("default", include_str!("../themes/default.toml")),
+    ("monochrome", include_str!("../themes/monochrome.toml")),
+    ("solarized", include_str!("../themes/solarized.toml")),
+    ("high-contrast", include_str!("../themes/high-contrast.toml")),
+    ("dracula", include_str!("../themes/dracula.toml")),
+];
+
+// The 16 basic colors by name, with their usual xterm values for matching
+// truecolor themes on terminals that only have these
+const BASIC: &[(&str, (u8, u8, u8))] = &[
+    ("black", (0, 0, 0)),
+    ("red", (205, 0, 0)),
+    ("green", (0, 205, 0)),
+    ("yellow", (205, 205, 0)),
+    ("blue", (0, 0, 238)),
+    ("magenta", (205, 0, 205)),
+    ("cyan", (0, 205, 205)),
+    ("white", (229, 229, 229)),
+    ("bright_black", (127, 127, 127)),
+    ("bright_red", (255, 0, 0)),
+    ("bright_green", (0, 255, 0)),
+    ("bright_yellow", (255, 255, 0)),
+    ("bright_blue", (92, 92, 255)),
+    ("bright_magenta", (255, 0, 255)),
+    ("bright_cyan", (0, 255, 255)),
+    ("bright_white", (255, 255, 255)),
+];
+
+// How many colors the terminal can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Depth {
+    #[default]
+    Basic,
+    Ansi256,
+    TrueColor,
+}
+
+// COLORTERM=truecolor (or 24bit) means 24-bit color; a TERM like
+// xterm-256color means the 256-color palette; anything else gets the basic 16
+pub fn detect_depth() -> Depth {
+    depth(std::env::var("COLORTERM").ok().as_deref(), std::env::var("TERM").ok().as_deref())
+}
+
+fn depth(colorterm: Option<&str>, term: Option<&str>) -> Depth {
+    let colorterm = colorterm.unwrap_or_default().to_lowercase();
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        return Depth::TrueColor;
+    }
+    if term.unwrap_or_default().contains("256color") {
+        return Depth::Ansi256;
+    }
+    Depth::Basic
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    // One of the 16 basic colors, 0-15
+    Basic(u8),
+    // An entry of the 256-color palette
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    // "red", "bright_cyan", "208" (256-color palette), "#ff79c6" or "#f7c"
+    pub fn parse(text: &str) -> Result<Color, String> {
+        let text = text.trim();
+        if let Some(hex) = text.strip_prefix('#') {
+            // #rgb is short for #rrggbb, as in CSS
+            let hex = match hex.len() {
+                3 => hex.chars().flat_map(|c| [c, c]).collect(),
+                _ => hex.to_string(),
+            };
+            let value = u32::from_str_radix(&hex, 16).ok().filter(|_| hex.len() == 6 && hex.is_ascii());
+            return match value {
+                Some(value) => Ok(Color::Rgb((value >> 16) as u8, (value >> 8) as u8, value as u8)),
+                None => Err(format!("\"{}\" isn't a #rrggbb color", text)),
+            };
+        }
+        if let Ok(index) = text.parse::<u8>() {
+            return Ok(Color::Indexed(index));
+        }
+        let name = text.to_lowercase().replace(['-', ' '], "_");
+        match BASIC.iter().position(|(basic, _)| *basic == name) {
+            Some(index) => Ok(Color::Basic(index as u8)),
+            None => Err(format!("unknown color \"{}\"", text)),
+        }
+    }
+
+    // The color's RGB value (for the 16 basic colors, the usual xterm one)
+    pub fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            Color::Basic(index) | Color::Indexed(index) => indexed_rgb(index),
+            Color::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+
+    // "#rrggbb", for images and web pages
+    pub fn hex(self) -> String {
+        let (r, g, b) = self.rgb();
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+
+    // The nearest color the terminal can show
+    pub fn approximate(self, depth: Depth) -> Color {
+        match (self, depth) {
+            (Color::Indexed(index), Depth::Basic) => Color::Basic(basic_index(indexed_rgb(index))),
+            (Color::Rgb(r, g, b), Depth::Ansi256) => Color::Indexed(palette_index((r, g, b))),
+            (Color::Rgb(r, g, b), Depth::Basic) => Color::Basic(basic_index((r, g, b))),
+            _ => self,
+        }
+    }
+
+    // SGR parameters for this color as a foreground (or background) color,
+    // approximated to what the terminal can show
+    fn sgr(self, depth: Depth, background: bool) -> String {
+        match self.approximate(depth) {
+            Color::Basic(index) => {
+                let base = if background { 40 } else { 30 };
+                let bright = if index >= 8 { 60 } else { 0 };
+                (base + bright + index as u32 % 8).to_string()
+            }
+            Color::Indexed(index) => format!("{};5;{}", if background { 48 } else { 38 }, index),
+            Color::Rgb(r, g, b) => format!("{};2;{};{};{}", if background { 48 } else { 38 }, r, g, b),
+        }
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+// The nearest of the 16 basic colors
+fn basic_index(rgb: (u8, u8, u8)) -> u8 {
+    (0..BASIC.len()).min_by_key(|&index| distance(BASIC[index].1, rgb)).unwrap_or(7) as u8
+}
+
+// Levels of the 6x6x6 color cube in the 256-color palette
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+// The RGB value of a 256-color palette entry
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => BASIC[index as usize].1,
+        16..=231 => {
+            let cube = index - 16;
+            (CUBE[(cube / 36) as usize], CUBE[(cube / 6 % 6) as usize], CUBE[(cube % 6) as usize])
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+// The nearest entry in the color cube or the gray ramp
+fn palette_index(rgb: (u8, u8, u8)) -> u8 {
+    (16..=255u8).min_by_key(|&index| distance(indexed_rgb(index), rgb)).unwrap_or(15)
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl Style {
+    // `text` wrapped in the escape codes for this style, or as is when colors are off
+    pub fn paint(&self, text: &str) -> String {
+        let theme = current();
+        if !theme.enabled || text.is_empty() {
+            return text.to_string();
+        }
+        let mut codes = Vec::new();
+        for (on, code) in [(self.bold, "1"), (self.dim, "2"), (self.italic, "3"), (self.underline, "4")] {
+            if on {
+                codes.push(code.to_string());
+            }
+        }
+        codes.extend(self.fg.map(|color| color.sgr(theme.depth, false)));
+        codes.extend(self.bg.map(|color| color.sgr(theme.depth, true)));
+        if codes.is_empty() {
+            return text.to_string();
+        }
+        format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+    }
+}
+
+// A role as written in a theme file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct StyleEntry {
+    fg: Option<String>,
+    bg: Option<String>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl StyleEntry {
+    fn to_style(&self) -> Result<Style, String> {
+        Ok(Style {
+            fg: self.fg.as_deref().map(Color::parse).transpose()?,
+            bg: self.bg.as_deref().map(Color::parse).transpose()?,
+            bold: self.bold,
+            dim: self.dim,
+            italic: self.italic,
+            underline: self.underline,
+        })
+    }
+}
+
+// A theme file: an optional built-in theme to start from, and the roles it changes
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeFile {
+    base: Option<String>,
+    header: Option<StyleEntry>,
+    label: Option<StyleEntry>,
+    value: Option<StyleEntry>,
+    roast: Option<StyleEntry>,
+    gentle: Option<StyleEntry>,
+    standard: Option<StyleEntry>,
+    savage: Option<StyleEntry>,
+    card: Option<StyleEntry>,
+}
+
+// Styles for each part of the output: spec headers, labels and values, the
+// roast heading, and roast lines by the severity they were asked for at. The
+// card role is only for images: its background, and its border and text.
+#[derive(Debug, Clone, Default)]
+pub struct Theme {
+    pub header: Style,
+    pub label: Style,
+    pub value: Style,
+    pub roast: Style,
+    pub gentle: Style,
+    pub standard: Style,
+    pub savage: Style,
+    pub card: Style,
+    pub depth: Depth,
+    pub enabled: bool,
+}
+
+impl Theme {
+    pub fn severity(&self, severity: Severity) -> &Style {
+        match severity {
+            Severity::Gentle => &self.gentle,
+            Severity::Standard => &self.standard,
+            Severity::Savage => &self.savage,
+        }
+    }
+
+    // Applies the roles a theme file sets on top of this theme
+    fn apply(&mut self, file: &ThemeFile) -> Result<(), String> {
+        let roles = [
+            (&mut self.header, &file.header, "header"),
+            (&mut self.label, &file.label, "label"),
+            (&mut self.value, &file.value, "value"),
+            (&mut self.roast, &file.roast, "roast"),
+            (&mut self.gentle, &file.gentle, "gentle"),
+            (&mut self.standard, &file.standard, "standard"),
+            (&mut self.savage, &file.savage, "savage"),
+            (&mut self.card, &file.card, "card"),
+        ];
+        for (style, entry, role) in roles {
+            if let Some(entry) = entry {
+                *style = entry.to_style().map_err(|err| format!("[{}]: {}", role, err))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Theme files can build on each other this many times
+const MAX_BASES: usize = 4;
+
+fn builtin(name: &str) -> Option<&'static str> {
+    BUILTIN_THEMES.iter().find(|(builtin, _)| *builtin == name).map(|(_, text)| *text)
+}
+
+// Builds a theme from a theme file's text. Roles it leaves out come from its
+// `base` theme, or from the built-in `fallback` theme when it doesn't name one.
+fn from_text(text: &str, fallback: Option<&str>, depth: usize) -> Result<Theme, String> {
+    let file: ThemeFile = toml::from_str(text).map_err(|e| e.to_string().trim().to_string())?;
+    let mut theme = match (file.base.as_deref(), fallback.and_then(builtin)) {
+        (Some(base), _) if depth >= MAX_BASES => return Err(format!("base \"{}\" nests too deeply", base)),
+        (Some(base), _) => load(base, depth + 1)?,
+        (None, Some(fallback)) => from_text(fallback, None, depth + 1)?,
+        (None, None) => Theme::default(),
+    };
+    theme.apply(&file)?;
+    Ok(theme)
+}
+
+// A theme by name: ~/.config/rust_roast/themes/<name>.toml, then the built-in
+// one, or a path to a theme file. User themes start from the default theme.
+fn load(name: &str, depth: usize) -> Result<Theme, String> {
+    let user = config_dir().map(|dir| dir.join("themes").join(format!("{}.toml", name)));
+    let path = if name.ends_with(".toml") { Some(name.into()) } else { user.filter(|path| path.is_file()) };
+    if let Some(path) = path {
+        let text = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        return from_text(&text, Some("default"), depth).map_err(|e| format!("{}: {}", path.display(), e));
+    }
+    match builtin(name) {
+        Some(text) => from_text(text, None, depth),
+        None => Err(format!("unknown theme \"{}\"", name)),
+    }
+}
+
+// When to use colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+// In auto mode, colors are on when stdout is a terminal, unless NO_COLOR or
+// CLICOLOR=0 is set; CLICOLOR_FORCE turns them on even when piped
+fn colors_wanted(choice: ColorChoice) -> bool {
+    let var = |name| std::env::var_os(name).map(|value| value.to_string_lossy().into_owned());
+    wants_colors(choice, var("NO_COLOR").as_deref(), var("CLICOLOR").as_deref(), var("CLICOLOR_FORCE").as_deref(), std::io::stdout().is_terminal())
+}
+
+fn wants_colors(choice: ColorChoice, no_color: Option<&str>, clicolor: Option<&str>, clicolor_force: Option<&str>, terminal: bool) -> bool {
+    match choice {
+        ColorChoice::Always => return true,
+        ColorChoice::Never => return false,
+        ColorChoice::Auto => {}
+    }
+    if clicolor_force.is_some_and(|value| !value.is_empty() && value != "0") {
+        return true;
+    }
+    if no_color.is_some_and(|value| !value.is_empty()) || clicolor == Some("0") {
+        return false;
+    }
+    terminal
+}
+
+// Picks the theme for this run, falling back to the default one (with a
+// warning) if it can't be loaded
+pub fn init(name: &str, color: ColorChoice) {
+    let mut theme = load(name, 0).unwrap_or_else(|err| {
+        eprintln!("rust_roast: using the default theme: {}", err);
+        load("default", 0).unwrap_or_default()
+    });
+    theme.depth = detect_depth();
+    theme.enabled = colors_wanted(color);
+    let _ = THEME.set(theme);
+}
+
+pub fn current() -> &'static Theme {
+    THEME.get_or_init(|| {
+        let mut theme = load("default", 0).unwrap_or_default();
+        theme.depth = detect_depth();
+        theme.enabled = colors_wanted(ColorChoice::Auto);
+        theme
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_colors() {
+        assert_eq!(Color::parse("red"), Ok(Color::Basic(1)));
+        assert_eq!(Color::parse(" Bright-Cyan "), Ok(Color::Basic(14)));
+        assert_eq!(Color::parse("bright white"), Ok(Color::Basic(15)));
+        assert_eq!(Color::parse("#ff79c6"), Ok(Color::Rgb(255, 121, 198)));
+        assert_eq!(Color::parse("#F80"), Ok(Color::Rgb(255, 136, 0)));
+        assert_eq!(Color::parse("0"), Ok(Color::Indexed(0)));
+        assert_eq!(Color::parse("255"), Ok(Color::Indexed(255)));
+        for bad in ["256", "-1", "#ff79c", "#ggg", "#ff79c6ff", "#+ff", "pink", ""] {
+            assert!(Color::parse(bad).is_err(), "{:?} parsed", bad);
+        }
+    }
+
+    #[test]
+    fn approximates_to_the_terminal() {
+        let pink = Color::Rgb(255, 121, 198);
+        assert_eq!(pink.approximate(Depth::TrueColor), pink);
+        assert_eq!(pink.approximate(Depth::Ansi256), Color::Indexed(212));
+        assert_eq!(Color::Rgb(255, 136, 0).approximate(Depth::Basic), Color::Basic(3));
+        assert_eq!(Color::Rgb(250, 20, 240).approximate(Depth::Basic), Color::Basic(13));
+        // Grays land on the gray ramp
+        assert_eq!(Color::Rgb(128, 128, 128).approximate(Depth::Ansi256), Color::Indexed(244));
+        assert_eq!(Color::Indexed(196).approximate(Depth::Basic), Color::Basic(9));
+        assert_eq!(Color::Indexed(196).approximate(Depth::Ansi256), Color::Indexed(196));
+        assert_eq!(Color::Basic(3).approximate(Depth::Basic), Color::Basic(3));
+    }
+
+    #[test]
+    fn sgr_codes() {
+        let pink = Color::Rgb(255, 121, 198);
+        assert_eq!(pink.sgr(Depth::TrueColor, false), "38;2;255;121;198");
+        assert_eq!(pink.sgr(Depth::TrueColor, true), "48;2;255;121;198");
+        assert_eq!(pink.sgr(Depth::Ansi256, false), "38;5;212");
+        let magenta = Color::Rgb(250, 20, 240);
+        assert_eq!(magenta.sgr(Depth::Basic, false), "95");
+        assert_eq!(magenta.sgr(Depth::Basic, true), "105");
+        assert_eq!(Color::Basic(1).sgr(Depth::TrueColor, false), "31");
+        assert_eq!(Color::Basic(1).sgr(Depth::TrueColor, true), "41");
+    }
+
+    #[test]
+    fn roles_come_from_the_base() {
+        let dracula = load("dracula", 0).unwrap();
+        let theme = from_text("base = \"dracula\"\n[savage]\nfg = \"#f80\"\nbold = true\n", Some("default"), 0).unwrap();
+        assert_eq!(theme.savage, Style { fg: Some(Color::Rgb(255, 136, 0)), bold: true, ..Style::default() });
+        assert_eq!(theme.header, dracula.header);
+        // Without a base, the fallback
+        let theme = from_text("[label]\nfg = \"red\"\n", Some("default"), 0).unwrap();
+        assert_eq!(theme.roast, load("default", 0).unwrap().roast);
+        assert_eq!(theme.label.fg, Some(Color::Basic(1)));
+    }
+
+    #[test]
+    fn base_cycles_stop() {
+        let dir = std::env::temp_dir().join(format!("rust_roast-themes-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (a, b) = (dir.join("a.toml"), dir.join("b.toml"));
+        fs::write(&a, format!("base = {:?}\n", b.display().to_string())).unwrap();
+        fs::write(&b, format!("base = {:?}\n", a.display().to_string())).unwrap();
+        let err = load(&a.display().to_string(), 0).unwrap_err();
+        assert!(err.contains("nests too deeply"), "{}", err);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rejects_bad_theme_files() {
+        let err = from_text("[savge]\nfg = \"red\"\n", None, 0).unwrap_err();
+        assert!(err.contains("unknown field `savge`"), "{}", err);
+        let err = from_text("[savage]\ncolor = \"red\"\n", None, 0).unwrap_err();
+        assert!(err.contains("unknown field `color`"), "{}", err);
+        let err = from_text("[savage]\nfg = \"pink\"\n", None, 0).unwrap_err();
+        assert_eq!(err, "[savage]: unknown color \"pink\"");
+        assert_eq!(load("nope", 0).unwrap_err(), "unknown theme \"nope\"");
+    }
+
+    #[test]
+    fn color_choice_precedence() {
+        use ColorChoice::*;
+        // (choice, NO_COLOR, CLICOLOR, CLICOLOR_FORCE, terminal, colors)
+        let cases = [
+            (Auto, None, None, None, true, true),
+            (Auto, None, None, None, false, false),
+            (Auto, Some("1"), None, None, true, false),
+            // An empty NO_COLOR doesn't count
+            (Auto, Some(""), None, None, true, true),
+            (Auto, None, Some("0"), None, true, false),
+            (Auto, None, Some("1"), None, false, false),
+            (Auto, None, None, Some("1"), false, true),
+            (Auto, None, None, Some("0"), false, false),
+            (Auto, None, None, Some(""), false, false),
+            // Forcing beats both ways of turning colors off
+            (Auto, Some("1"), Some("0"), Some("1"), false, true),
+            // Flags beat the environment
+            (Always, Some("1"), Some("0"), None, false, true),
+            (Never, None, None, Some("1"), true, false),
+        ];
+        for (choice, no_color, clicolor, force, terminal, colors) in cases {
+            assert_eq!(wants_colors(choice, no_color, clicolor, force, terminal), colors, "{:?} {:?} {:?} {:?} {}", choice, no_color, clicolor, force, terminal);
+        }
+    }
+
+    #[test]
+    fn color_depth_from_the_environment() {
+        // (COLORTERM, TERM, depth)
+        let cases = [
+            (Some("truecolor"), Some("xterm-256color"), Depth::TrueColor),
+            (Some("24bit"), None, Depth::TrueColor),
+            (Some("TrueColor"), Some("xterm"), Depth::TrueColor),
+            (Some("yes"), Some("xterm-256color"), Depth::Ansi256),
+            (None, Some("screen-256color"), Depth::Ansi256),
+            (None, Some("xterm"), Depth::Basic),
+            (None, Some("linux"), Depth::Basic),
+            (None, None, Depth::Basic),
+        ];
+        for (colorterm, term, expected) in cases {
+            assert_eq!(depth(colorterm, term), expected, "{:?} {:?}", colorterm, term);
+        }
+    }
+
+    #[test]
+    fn builtin_themes_load() {
+        for (name, _) in BUILTIN_THEMES {
+            assert!(load(name, 0).is_ok(), "{}", name);
+        }
+    }
+}
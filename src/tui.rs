@@ -0,0 +1,388 @@
+use crate::config::TuiConfig;
+use crate::i18n::{tr, trf};
+use crate::live::{Sample, Sampler};
+use crate::roast::{Picked, Roast, Roaster, Severity};
+use crate::theme::{self, Color};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Margin, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Padding, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+// How often the gauges are refreshed
+const TICK: Duration = Duration::from_secs(1);
+
+// A reading has to drop this many points below its threshold before the
+// panel lets go of it, so a value hovering at the line doesn't reroll every tick
+const HYSTERESIS: f32 = 5.0;
+
+pub const CPU_BUSY_ROASTS: &[Roast] = &[
+    Roast::gentle("{cpu}% CPU. Your processor would like a word about its workload."),
+    Roast::standard("{cpu}% CPU. Somewhere a fan is begging for mercy."),
+    Roast::standard("Your CPU is at {cpu}%. Was that a build, or did you open a second browser tab?"),
+    Roast::savage("{cpu}% CPU and the machine still feels slow. That's not load, that's a cry for help."),
+    Roast::standard("Every core is busy. I'd offer to help, but I'd need a core for that.").personal(),
+];
+
+pub const RAM_FULL_ROASTS: &[Roast] = &[
+    Roast::gentle("{ram}% of your RAM is in use. Maybe a tab or two could go?"),
+    Roast::standard("RAM at {ram}%. Your browser is setting up a second home."),
+    Roast::standard("{ram}% RAM. Swap is warming up on the bench."),
+    Roast::savage("{ram}% of your RAM, gone. The OOM killer is reading your process list like a menu."),
+    Roast::standard("You're at {ram}% RAM. Closing things is allowed, you know.").personal(),
+];
+
+pub const GPU_BUSY_ROASTS: &[Roast] = &[
+    Roast::gentle("GPU at {gpu}%. Something in here is very pretty."),
+    Roast::standard("{gpu}% GPU. Either you're training a model or the desktop animations got ambitious."),
+    Roast::savage("{gpu}% GPU and your frame rate still isn't anything to brag about."),
+];
+
+pub const HOT_ROASTS: &[Roast] = &[
+    Roast::gentle("{temperature}°C. Your machine could use a little fresh air."),
+    Roast::standard("{temperature}°C. You could fry an egg on that, and at this rate you'll have to."),
+    Roast::standard("Running at {temperature}°C. Thermal throttling is about to take over your schedule."),
+    Roast::savage("{temperature}°C. That's not a computer anymore, it's a space heater with opinions."),
+];
+
+pub const IDLE_ROASTS: &[Roast] = &[
+    Roast::gentle("All quiet. Your machine is resting, and frankly it's earned it."),
+    Roast::standard("Idle again. This machine works about as hard as its owner.").personal(),
+    Roast::standard("Nothing's happening. All that hardware, just sitting there looking at you."),
+    Roast::savage("The gauges aren't moving. Did you buy this thing to watch it idle?").personal(),
+];
+
+// The pools above, by name
+pub const POOLS: &[(&str, &[Roast])] = &[
+    ("cpu.busy", CPU_BUSY_ROASTS),
+    ("ram.full", RAM_FULL_ROASTS),
+    ("gpu.busy", GPU_BUSY_ROASTS),
+    ("thermal.hot", HOT_ROASTS),
+    ("general.idle", IDLE_ROASTS),
+];
+
+// The pool for what stands out in `sample`, most pressing first: heat, RAM,
+// CPU, then GPU. Everything under its threshold is the idle pool. The pool
+// already on screen keeps its place until its reading drops well below the line.
+pub fn roast_pool(sample: &Sample, config: &TuiConfig, current: &str) -> (&'static str, &'static [Roast]) {
+    let readings: [(&'static str, &'static [Roast], Option<f32>, f32); 4] = [
+        ("thermal.hot", HOT_ROASTS, sample.temperature, config.hot_celsius),
+        ("ram.full", RAM_FULL_ROASTS, Some(sample.ram_percent()), config.ram_full),
+        ("cpu.busy", CPU_BUSY_ROASTS, Some(sample.cpu), config.cpu_busy),
+        ("gpu.busy", GPU_BUSY_ROASTS, sample.gpu, config.gpu_busy),
+    ];
+    for (name, pool, value, threshold) in readings {
+        let threshold = if name == current { threshold - HYSTERESIS } else { threshold };
+        if value.is_some_and(|value| value >= threshold) {
+            return (name, pool);
+        }
+    }
+    ("general.idle", IDLE_ROASTS)
+}
+
+// Fills the {cpu}, {ram}, {gpu} and {temperature} placeholders
+pub fn fill(roast: &str, sample: &Sample) -> String {
+    roast
+        .replace("{cpu}", &format!("{:.0}", sample.cpu))
+        .replace("{ram}", &format!("{:.0}", sample.ram_percent()))
+        .replace("{gpu}", &format!("{:.0}", sample.gpu.unwrap_or(0.0)))
+        .replace("{temperature}", &format!("{:.0}", sample.temperature.unwrap_or(0.0)))
+}
+
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (index, byte)| n | (*byte as u32) << (16 - 8 * index));
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(BASE64[(n >> (18 - 6 * index) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+// Puts `text` on the clipboard with an OSC 52 escape, which the terminal
+// handles, so it works over SSH and without a clipboard tool installed
+fn copy(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()
+}
+
+fn color(color: Color) -> ratatui::style::Color {
+    match color.approximate(theme::current().depth) {
+        Color::Basic(index) | Color::Indexed(index) => ratatui::style::Color::Indexed(index),
+        Color::Rgb(r, g, b) => ratatui::style::Color::Rgb(r, g, b),
+    }
+}
+
+// A theme role as a ratatui style; plain when colors are off
+fn style(role: &theme::Style) -> Style {
+    let mut style = Style::default();
+    if !theme::current().enabled {
+        return style;
+    }
+    if let Some(fg) = role.fg {
+        style = style.fg(color(fg));
+    }
+    if let Some(bg) = role.bg {
+        style = style.bg(color(bg));
+    }
+    for (on, modifier) in [(role.bold, Modifier::BOLD), (role.dim, Modifier::DIM), (role.italic, Modifier::ITALIC), (role.underline, Modifier::UNDERLINED)] {
+        if on {
+            style = style.add_modifier(modifier);
+        }
+    }
+    style
+}
+
+struct App<'a> {
+    roaster: &'a mut Roaster,
+    config: &'a TuiConfig,
+    sampler: Sampler,
+    sample: Sample,
+    pool: &'static str,
+    roast: Picked,
+    // A note for the footer, like "copied"
+    status: &'static str,
+}
+
+impl App<'_> {
+    fn tick(&mut self) {
+        self.sample = self.sampler.sample();
+        self.react();
+    }
+
+    // A new roast if the sample changed what stands out
+    fn react(&mut self) {
+        self.sample.record(&mut self.roaster.snapshot);
+        let (name, _) = roast_pool(&self.sample, self.config, self.pool);
+        if name != self.pool {
+            self.pool = name;
+            self.reroll();
+        }
+    }
+
+    fn reroll(&mut self) {
+        let (name, pool) = roast_pool(&self.sample, self.config, self.pool);
+        if let Some(roast) = self.roaster.pick(name, pool) {
+            self.roast = roast.map(|text| fill(text, &self.sample));
+        }
+        self.status = "";
+    }
+
+    fn cycle_severity(&mut self) {
+        self.roaster.tone.severity = match self.roaster.tone.severity {
+            Severity::Gentle => Severity::Standard,
+            Severity::Standard => Severity::Savage,
+            Severity::Savage => Severity::Gentle,
+        };
+        self.reroll();
+    }
+
+    fn copy(&mut self) {
+        self.status = match copy(&self.roast.text) {
+            Ok(()) => "tui.copied",
+            Err(_) => "tui.copy_failed",
+        };
+    }
+}
+
+// Gauge color by how close a reading is to its threshold, like roasts at
+// that severity
+fn level(value: f32, threshold: f32) -> Style {
+    let theme = theme::current();
+    let role = if value >= threshold {
+        &theme.savage
+    } else if value >= threshold / 2.0 {
+        &theme.standard
+    } else {
+        &theme.gentle
+    };
+    style(role)
+}
+
+// A gauge's fill in percent, the threshold it's colored against, and the
+// reading as text
+struct Reading {
+    percent: f32,
+    threshold: f32,
+    text: String,
+}
+
+fn gauge(frame: &mut Frame, area: Rect, label: &str, reading: Option<Reading>) {
+    let theme = theme::current();
+    let [label_area, gauge_area, value_area] = Layout::horizontal([Constraint::Length(13), Constraint::Min(10), Constraint::Length(20)]).spacing(1).areas(area);
+    frame.render_widget(Paragraph::new(label).style(style(&theme.label)), label_area);
+    let Some(reading) = reading else {
+        frame.render_widget(Paragraph::new(tr("value.unavailable")).style(style(&theme.value)), value_area);
+        return;
+    };
+    // Shades rather than colors tell filled from empty, so the bar reads with colors off too
+    let filled = ((reading.percent / 100.0).clamp(0.0, 1.0) * gauge_area.width as f32).round() as usize;
+    let bar = Line::from(vec![
+        Span::styled("█".repeat(filled), level(reading.percent, reading.threshold)),
+        Span::styled("░".repeat(gauge_area.width as usize - filled), Style::default().add_modifier(Modifier::DIM)),
+    ]);
+    frame.render_widget(Paragraph::new(bar), gauge_area);
+    frame.render_widget(Paragraph::new(reading.text).style(style(&theme.value)), value_area);
+}
+
+fn draw(frame: &mut Frame, app: &App) {
+    let theme = theme::current();
+    let sample = &app.sample;
+    let gib = |bytes: u64| bytes as f64 / 1024.0 / 1024.0 / 1024.0;
+    let mut rows = vec![
+        (tr("label.cpu"), Some(Reading { percent: sample.cpu, threshold: app.config.cpu_busy, text: format!("{:.0}%", sample.cpu) })),
+        (
+            tr("label.ram"),
+            Some(Reading {
+                percent: sample.ram_percent(),
+                threshold: app.config.ram_full,
+                text: format!("{:.1} / {:.1} GB", gib(sample.ram_used), gib(sample.ram_total)),
+            }),
+        ),
+    ];
+    if sample.swap_total > 0 {
+        let reading = Reading {
+            percent: sample.swap_used as f32 / sample.swap_total as f32 * 100.0,
+            threshold: app.config.ram_full,
+            text: format!("{:.1} / {:.1} GB", gib(sample.swap_used), gib(sample.swap_total)),
+        };
+        rows.push((tr("label.swap"), Some(reading)));
+    }
+    rows.push((tr("label.gpu"), sample.gpu.map(|gpu| Reading { percent: gpu, threshold: app.config.gpu_busy, text: format!("{:.0}%", gpu) })));
+    // The scale tops out at 100 °C
+    let temperature = sample.temperature.map(|celsius| Reading { percent: celsius, threshold: app.config.hot_celsius, text: format!("{:.0} °C", celsius) });
+    rows.push((tr("label.temperature"), temperature));
+
+    let [gauges_area, roast_area, footer_area] =
+        Layout::vertical([Constraint::Length(rows.len() as u16 + 2), Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+    let gauges = Block::bordered().title(Span::styled(" rust_roast ", style(&theme.header)));
+    let inner = gauges.inner(gauges_area);
+    frame.render_widget(gauges, gauges_area);
+    let row_areas = Layout::vertical(vec![Constraint::Length(1); rows.len()]).split(inner.inner(Margin::new(1, 0)));
+    for ((label, reading), area) in rows.into_iter().zip(row_areas.iter()) {
+        gauge(frame, *area, label, reading);
+    }
+
+    let heading = format!(" {} ", tr(app.roaster.mode.header()));
+    let roast = Paragraph::new(app.roast.text.as_str())
+        .style(style(theme.severity(app.roast.severity)))
+        .wrap(Wrap { trim: true })
+        .block(Block::bordered().padding(Padding::horizontal(1)).title(Span::styled(heading, style(&theme.roast))));
+    frame.render_widget(roast, roast_area);
+
+    let severity = match app.roaster.tone.severity {
+        Severity::Gentle => tr("severity.gentle"),
+        Severity::Standard => tr("severity.standard"),
+        Severity::Savage => tr("severity.savage"),
+    };
+    let mut footer = vec![Span::styled(trf("tui.keys", &[("severity", severity)]), style(&theme.label))];
+    if !app.status.is_empty() {
+        footer.push(Span::raw("  "));
+        footer.push(Span::styled(tr(app.status), style(&theme.value)));
+    }
+    frame.render_widget(Paragraph::new(Line::from(footer)), footer_area);
+}
+
+fn run_app(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<()> {
+    let mut next_tick = Instant::now() + TICK;
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+        if event::poll(next_tick.saturating_duration_since(Instant::now()))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                    KeyCode::Char('r') | KeyCode::Char(' ') => app.reroll(),
+                    KeyCode::Char('s') => app.cycle_severity(),
+                    KeyCode::Char('c') | KeyCode::Char('y') => app.copy(),
+                    _ => {}
+                }
+            }
+        }
+        if Instant::now() >= next_tick {
+            app.tick();
+            next_tick += TICK;
+        }
+    }
+}
+
+// A live dashboard: gauges for CPU, RAM, swap, GPU and temperature, and a
+// roast about whatever stands out right now. r rerolls, s changes the
+// severity, c copies the roast, q quits.
+pub fn run(roaster: &mut Roaster, config: &TuiConfig) -> Result<(), String> {
+    if !io::stdout().is_terminal() {
+        return Err("the dashboard needs a terminal".to_string());
+    }
+    let mut sampler = Sampler::new();
+    let sample = sampler.sample();
+    let mut app = App { roaster, config, sampler, sample, pool: "", roast: Picked::default(), status: "" };
+    app.react();
+    let mut terminal = ratatui::try_init().map_err(|err| err.to_string())?;
+    let result = run_app(&mut terminal, &mut app);
+    ratatui::restore();
+    result.map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(sample: &Sample, current: &str) -> &'static str {
+        roast_pool(sample, &TuiConfig::default(), current).0
+    }
+
+    #[test]
+    fn most_pressing_reading_wins() {
+        let sample = Sample { cpu: 95.0, ram_used: 95, ram_total: 100, temperature: Some(90.0), gpu: Some(99.0), ..Sample::default() };
+        assert_eq!(pool(&sample, ""), "thermal.hot");
+        assert_eq!(pool(&Sample { temperature: None, ..sample.clone() }, ""), "ram.full");
+        assert_eq!(pool(&Sample { temperature: None, ram_used: 10, ..sample.clone() }, ""), "cpu.busy");
+        assert_eq!(pool(&Sample { cpu: 5.0, temperature: None, ram_used: 10, ..sample }, ""), "gpu.busy");
+        assert_eq!(pool(&Sample::default(), ""), "general.idle");
+    }
+
+    #[test]
+    fn current_pool_holds_until_well_below_the_line() {
+        let cpu = |cpu| Sample { cpu, ..Sample::default() };
+        // Not busy enough to start...
+        assert_eq!(pool(&cpu(87.0), "general.idle"), "general.idle");
+        // ...but busy enough to stay
+        assert_eq!(pool(&cpu(87.0), "cpu.busy"), "cpu.busy");
+        assert_eq!(pool(&cpu(85.0), "cpu.busy"), "cpu.busy");
+        assert_eq!(pool(&cpu(84.9), "cpu.busy"), "general.idle");
+        // The hysteresis only helps the pool on screen
+        let both = Sample { cpu: 87.0, ram_used: 92, ram_total: 100, ..Sample::default() };
+        assert_eq!(pool(&both, "cpu.busy"), "ram.full");
+    }
+
+    #[test]
+    fn base64_matches_rfc_4648() {
+        let vectors = [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foob", "Zm9vYg=="), ("fooba", "Zm9vYmE="), ("foobar", "Zm9vYmFy")];
+        for (text, encoded) in vectors {
+            assert_eq!(base64(text.as_bytes()), encoded, "{:?}", text);
+        }
+        // The high bits, and the last two letters of the alphabet
+        assert_eq!(base64(&[0xfb, 0xff, 0xbf]), "+/+/");
+        assert_eq!(base64("°C".as_bytes()), "wrBD");
+    }
+
+    #[test]
+    fn fills_readings() {
+        let sample = Sample { cpu: 93.4, ram_used: 1, ram_total: 2, gpu: Some(71.6), ..Sample::default() };
+        assert_eq!(fill("{cpu}/{ram}/{gpu}/{temperature}", &sample), "93/50/72/0");
+    }
+}
@@ -0,0 +1,236 @@
+use crate::config::UptimeConfig;
+use crate::roast::Roast;
+use std::fs;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sysinfo::{System, SystemExt};
+
+const DAY: u64 = 24 * 60 * 60;
+
+// utmp record layout on 64-bit Linux (struct utmp in <bits/utmp.h>)
+const UTMP_RECORD_SIZE: usize = 384;
+const UTMP_TV_SEC_OFFSET: usize = 340;
+const UTMP_BOOT_TIME: i16 = 2;
+
+pub struct UptimeInfo {
+    pub uptime_secs: u64,
+    // Boot timestamps (seconds since epoch), oldest first, always including the current boot
+    pub boots: Vec<u64>,
+}
+
+impl UptimeInfo {
+    pub fn uptime_days(&self) -> u64 {
+        self.uptime_secs / DAY
+    }
+
+    // Boots within the last 24 hours, counting the current one
+    pub fn boots_last_day(&self, now: u64) -> usize {
+        self.boots.iter().filter(|&&boot| boot + DAY >= now).count()
+    }
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+pub fn gather(sys: &System) -> UptimeInfo {
+    let mut boots = journal_boots();
+    if boots.is_empty() {
+        boots = wtmp_boots();
+    }
+    // The history may lag behind (or not exist); make sure the current boot is in it
+    let boot_time = sys.boot_time();
+    if boots.last().is_none_or(|&last| last.abs_diff(boot_time) > 60) {
+        boots.push(boot_time);
+    }
+    UptimeInfo {
+        uptime_secs: sys.uptime(),
+        boots,
+    }
+}
+
+// Formats a duration as "3d 4h", "5h 12m" or "7m"
+pub fn format_duration(secs: u64) -> String {
+    let days = secs / DAY;
+    let hours = secs % DAY / 3600;
+    let minutes = secs % 3600 / 60;
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+// Boot history from `journalctl --list-boots`, which only knows about persisted journals
+fn journal_boots() -> Vec<u64> {
+    let output = match Command::new("journalctl").args(["--list-boots", "--utc", "--no-pager"]).output() {
+        Ok(output) if output.status.success() => output,
+        _ => return Vec::new(),
+    };
+    parse_list_boots(&String::from_utf8_lossy(&output.stdout))
+}
+
+// Boot times from `journalctl --list-boots` output, oldest first. Lines are
+// IDX BOOT_ID FIRST_ENTRY LAST_ENTRY, e.g.
+//  -1 4c1b... Mon 2024-01-01 10:00:00 UTC Mon 2024-01-01 12:00:00 UTC
+fn parse_list_boots(text: &str) -> Vec<u64> {
+    let mut boots: Vec<u64> = text
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let date = fields.iter().position(|f| f.len() == 10 && f.as_bytes()[4] == b'-')?;
+            parse_utc(fields[date], fields.get(date + 1)?)
+        })
+        .collect();
+    boots.sort_unstable();
+    boots
+}
+
+// Boot history from the BOOT_TIME records in /var/log/wtmp
+fn wtmp_boots() -> Vec<u64> {
+    fs::read("/var/log/wtmp").map_or_else(|_| Vec::new(), |data| parse_wtmp(&data))
+}
+
+// Boot times of the BOOT_TIME records in wtmp data, oldest first
+fn parse_wtmp(data: &[u8]) -> Vec<u64> {
+    let mut boots: Vec<u64> = data
+        .chunks_exact(UTMP_RECORD_SIZE)
+        .filter(|record| i16::from_ne_bytes([record[0], record[1]]) == UTMP_BOOT_TIME)
+        .map(|record| {
+            let sec = &record[UTMP_TV_SEC_OFFSET..UTMP_TV_SEC_OFFSET + 4];
+            i32::from_ne_bytes([sec[0], sec[1], sec[2], sec[3]]).max(0) as u64
+        })
+        .collect();
+    boots.sort_unstable();
+    boots
+}
+
+// Parses "YYYY-MM-DD" and "HH:MM:SS" (UTC) into seconds since the epoch
+pub fn parse_utc(date: &str, time: &str) -> Option<u64> {
+    let mut ymd = date.split('-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (ymd.next()??, ymd.next()??, ymd.next()??);
+    let mut hms = time.split(':').map(|part| part.parse::<u64>().ok());
+    let (hour, minute, second) = (hms.next()??, hms.next()??, hms.next()??);
+
+    // Days from civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+    if days < 0 {
+        return None;
+    }
+    Some(days as u64 * DAY + hour * 3600 + minute * 60 + second)
+}
+
+// Picks the uptime roast pool (name and built-in lines) that applies, if any
+pub fn roast_pool(info: &UptimeInfo, config: &UptimeConfig, now: u64) -> Option<(&'static str, &'static [Roast])> {
+    if info.boots_last_day(now) >= config.reboots_per_day {
+        Some(("uptime.reboot_loop", REBOOT_LOOP_ROASTS))
+    } else if info.uptime_days() >= config.ancient_days {
+        Some(("uptime.ancient", ANCIENT_UPTIME_ROASTS))
+    } else if info.uptime_days() >= config.long_days {
+        Some(("uptime.long", LONG_UPTIME_ROASTS))
+    } else {
+        None
+    }
+}
+
+const REBOOT_LOOP_ROASTS: &[Roast] = &[
+    Roast::gentle("Rebooted {boots} times today? That's not a computer, that's a light switch."),
+    Roast::savage("Your machine boots more often than you commit. Impressive, in the worst way.").personal(),
+    Roast::standard("Have you tried turning it off and on again? Oh, you have. {boots} times. Today."),
+    Roast::gentle("Your uptime is measured in coffee breaks."),
+    Roast::standard("At this rate your bootloader deserves a salary."),
+    Roast::savage("Your system crashes so often the kernel started a support group."),
+];
+
+const LONG_UPTIME_ROASTS: &[Roast] = &[
+    Roast::savage("Up for {uptime} without a reboot. Those pending kernel patches are aging like milk."),
+    Roast::standard("{uptime} of uptime. Your package manager has been trying to get your attention."),
+    Roast::gentle("You haven't rebooted in {uptime}. Is that stability or fear?"),
+    Roast::standard("Your uptime is impressive. Your security posture is not."),
+    Roast::standard("That many days without a reboot? Your RAM is basically a fossil record at this point."),
+];
+
+const ANCIENT_UPTIME_ROASTS: &[Roast] = &[
+    Roast::standard("{uptime} of uptime. This machine hasn't seen a patch since the last Olympics."),
+    Roast::savage("Up for {uptime}. Somewhere a CVE has your name on it."),
+    Roast::gentle("Your uptime is old enough to have its own birthday party."),
+    Roast::standard("You're not running a server, you're running a museum exhibit."),
+    Roast::standard("Rebooting now would be an act of archaeology."),
+];
+
+// The pools above, by name
+pub const POOLS: &[(&str, &[Roast])] = &[
+    ("uptime.reboot_loop", REBOOT_LOOP_ROASTS),
+    ("uptime.long", LONG_UPTIME_ROASTS),
+    ("uptime.ancient", ANCIENT_UPTIME_ROASTS),
+];
+
+// Fills the {uptime} and {boots} placeholders
+pub fn fill(roast: &str, info: &UptimeInfo, now: u64) -> String {
+    roast
+        .replace("{uptime}", &format_duration(info.uptime_secs))
+        .replace("{boots}", &info.boots_last_day(now).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_utc_dates() {
+        assert_eq!(parse_utc("1970-01-01", "00:00:00"), Some(0));
+        assert_eq!(parse_utc("2024-01-01", "10:00:00"), Some(1_704_103_200));
+        // Leap day
+        assert_eq!(parse_utc("2024-03-01", "00:00:00").unwrap() - parse_utc("2024-02-28", "00:00:00").unwrap(), 2 * DAY);
+        assert_eq!(parse_utc("1969-12-31", "23:59:59"), None);
+        assert_eq!(parse_utc("2024-01", "10:00:00"), None);
+        assert_eq!(parse_utc("2024-01-01", "10:00"), None);
+    }
+
+    #[test]
+    fn parses_list_boots() {
+        let text = "\
+IDX BOOT ID                          FIRST ENTRY                 LAST ENTRY
+ -2 4c1b3f3c0a8a4d0f9d6f0d1f2e3a4b5c Mon 2024-01-01 12:00:00 UTC Mon 2024-01-01 13:00:00 UTC
+ -1 5d2c4e4d1b9b5e1a0e7a1e2a3f4b5c6d Mon 2024-01-01 10:00:00 UTC Mon 2024-01-01 11:00:00 UTC
+  0 6e3d5f5e2cac6f2b1f8b2f3b4a5c6d7e Tue 2024-01-02 08:30:00 UTC Tue 2024-01-02 09:00:00 UTC
+";
+        let boots = parse_list_boots(text);
+        assert_eq!(boots, [1_704_103_200, 1_704_110_400, 1_704_184_200]);
+        assert!(parse_list_boots("No journal boot entry found from the specified boot offset.").is_empty());
+    }
+
+    // A utmp record of `kind` at `secs`
+    fn record(kind: i16, secs: i32) -> Vec<u8> {
+        let mut record = vec![0; UTMP_RECORD_SIZE];
+        record[..2].copy_from_slice(&kind.to_ne_bytes());
+        record[UTMP_TV_SEC_OFFSET..UTMP_TV_SEC_OFFSET + 4].copy_from_slice(&secs.to_ne_bytes());
+        record
+    }
+
+    #[test]
+    fn parses_wtmp_boot_records() {
+        const USER_PROCESS: i16 = 7;
+        let mut data = record(UTMP_BOOT_TIME, 1_704_110_400);
+        data.extend(record(USER_PROCESS, 1_704_110_500));
+        data.extend(record(UTMP_BOOT_TIME, 1_704_103_200));
+        // A torn record at the end is ignored
+        data.extend(&record(UTMP_BOOT_TIME, 1_704_200_000)[..100]);
+        assert_eq!(parse_wtmp(&data), [1_704_103_200, 1_704_110_400]);
+    }
+
+    #[test]
+    fn counts_boots_in_the_last_day() {
+        let now = 1_704_184_200;
+        let info = UptimeInfo { uptime_secs: 600, boots: vec![now - 2 * DAY, now - DAY + 1, now - 600] };
+        assert_eq!(info.boots_last_day(now), 2);
+    }
+}
@@ -0,0 +1,335 @@
+use crate::config::WatchConfig;
+use crate::live::{Sample, Sampler};
+use crate::processes::{self, AppRule};
+use crate::roast::{Picked, Roast, Roaster};
+use crate::theme;
+use crate::uptime;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+pub const BATTERY_LOW_ROASTS: &[Roast] = &[
+    Roast::gentle("{battery}% battery. Might be time to look for a socket."),
+    Roast::standard("{battery}% battery and still going. Living dangerously, I see."),
+    Roast::standard("Your battery is at {battery}%. Whatever you're doing, save it now."),
+    Roast::savage("{battery}% battery. Your laptop is about to make the decision you keep putting off.").personal(),
+];
+
+pub const SWAP_ROASTS: &[Roast] = &[
+    Roast::gentle("{swap} MB in swap. Your RAM is asking the disk for a favor."),
+    Roast::standard("Swapping has begun. Everything is about to get slower, including you."),
+    Roast::standard("{swap} MB swapped out. Your SSD didn't sign up to be RAM."),
+    Roast::savage("{swap} MB of swap. Your disk is now doing RAM's job, badly, and everyone can tell."),
+];
+
+pub const SPIKE_ROASTS: &[Roast] = &[
+    Roast::gentle("Up {rise}°C in no time, now at {temperature}°C. Something's working hard."),
+    Roast::standard("{temperature}°C, up {rise}°C. Your fans just woke up and they're not happy."),
+    Roast::standard("A {rise}°C jump to {temperature}°C. Was that a build or a bonfire?"),
+    Roast::savage("{temperature}°C and climbing. Your laptop is auditioning as a hotplate."),
+];
+
+// For programs without an [[apps]] entry; the placeholders are the ones app roasts use
+pub const HOG_ROASTS: &[Roast] = &[
+    Roast::gentle("{app} is using {share} of your RAM. It's making itself at home."),
+    Roast::standard("{app} has {share} of your RAM now. Everything else gets the leftovers."),
+    Roast::standard("{share} of your RAM for {app}. Was that part of the plan?").personal(),
+    Roast::savage("{app} ate {share} of your RAM and it's still hungry."),
+];
+
+// The pools above, by name
+pub const POOLS: &[(&str, &[Roast])] = &[
+    ("power.battery_low", BATTERY_LOW_ROASTS),
+    ("ram.swap", SWAP_ROASTS),
+    ("thermal.spike", SPIKE_ROASTS),
+    ("apps.hog", HOG_ROASTS),
+];
+
+// Something that just happened and is worth a roast: what fills the pool's
+// placeholders, and the same readings as numbers for JSON
+pub struct Event {
+    // Also the name of the pool it's roasted from
+    pub kind: &'static str,
+    pub pool: &'static [Roast],
+    pub placeholders: Vec<(&'static str, String)>,
+    pub facts: Vec<(&'static str, Value)>,
+}
+
+impl Event {
+    pub fn fill(&self, roast: &str) -> String {
+        let mut text = roast.to_string();
+        for (name, value) in &self.placeholders {
+            text = text.replace(&format!("{{{}}}", name), value);
+        }
+        text
+    }
+
+    // What the cooldown is kept per: the kind, and for hogs the program too,
+    // so a second hog isn't held back by the first
+    fn cooldown_key(&self) -> String {
+        match self.placeholders.iter().find(|(name, _)| *name == "app") {
+            Some((_, program)) => format!("{}:{}", self.kind, program),
+            None => self.kind.to_string(),
+        }
+    }
+}
+
+// Turns samples into events. Each event fires when its condition starts, not
+// for as long as it lasts, so a low battery is roasted once, not every sample.
+pub struct Watcher<'a> {
+    config: &'a WatchConfig,
+    battery_low: bool,
+    swapping: bool,
+    // Programs already over their RAM share
+    hogs: HashSet<String>,
+    // Recent temperatures, oldest first
+    temperatures: VecDeque<(Instant, f32)>,
+    // When each kind of event (each program, for hogs) was last roasted
+    last: HashMap<String, Instant>,
+}
+
+impl<'a> Watcher<'a> {
+    pub fn new(config: &'a WatchConfig) -> Watcher<'a> {
+        Watcher {
+            config,
+            battery_low: false,
+            swapping: false,
+            hogs: HashSet::new(),
+            temperatures: VecDeque::new(),
+            last: HashMap::new(),
+        }
+    }
+
+    // The events that start with this sample. `programs` is memory per
+    // program, heaviest first.
+    pub fn events(&mut self, sample: &Sample, programs: &[(String, u64)], now: Instant) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        let battery_low = sample.on_battery && sample.battery.is_some_and(|percent| percent < self.config.battery_low);
+        if battery_low && !self.battery_low {
+            let percent = sample.battery.unwrap_or(0);
+            events.push(Event {
+                kind: "power.battery_low",
+                pool: BATTERY_LOW_ROASTS,
+                placeholders: vec![("battery", percent.to_string())],
+                facts: vec![("battery", percent.into())],
+            });
+        }
+        self.battery_low = battery_low;
+
+        let swap_mb = sample.swap_used / 1024 / 1024;
+        let swapping = swap_mb >= self.config.swap_mb;
+        if swapping && !self.swapping {
+            events.push(Event { kind: "ram.swap", pool: SWAP_ROASTS, placeholders: vec![("swap", swap_mb.to_string())], facts: vec![("swap_mb", swap_mb.into())] });
+        }
+        self.swapping = swapping;
+
+        // A spike is a rise over the coolest reading of the last few seconds
+        if let Some(temperature) = sample.temperature {
+            let window = Duration::from_secs(self.config.spike_secs);
+            self.temperatures.retain(|(at, _)| now.duration_since(*at) <= window);
+            self.temperatures.push_back((now, temperature));
+            let coolest = self.temperatures.iter().map(|(_, celsius)| *celsius).fold(temperature, f32::min);
+            let rise = temperature - coolest;
+            if rise >= self.config.spike_celsius {
+                // Start over from here, so the same climb isn't a spike again next sample
+                self.temperatures.clear();
+                self.temperatures.push_back((now, temperature));
+                let (temperature, rise) = (temperature.round() as i64, rise.round() as i64);
+                events.push(Event {
+                    kind: "thermal.spike",
+                    pool: SPIKE_ROASTS,
+                    placeholders: vec![("temperature", temperature.to_string()), ("rise", rise.to_string())],
+                    facts: vec![("temperature", temperature.into()), ("rise", rise.into())],
+                });
+            }
+        }
+
+        let total = sample.ram_total.max(1) as f64;
+        let hogs: HashSet<String> = programs
+            .iter()
+            .filter(|(_, bytes)| *bytes as f64 / total >= self.config.program_ram_share)
+            .map(|(name, _)| name.clone())
+            .collect();
+        for (name, bytes) in programs.iter().filter(|(name, _)| hogs.contains(name) && !self.hogs.contains(name)) {
+            let share = (*bytes as f64 / total * 100.0).round() as i64;
+            events.push(Event {
+                kind: "apps.hog",
+                pool: HOG_ROASTS,
+                placeholders: vec![("app", name.clone()), ("share", format!("{}%", share))],
+                facts: vec![("program", name.as_str().into()), ("share", share.into())],
+            });
+        }
+        self.hogs = hogs;
+
+        events
+    }
+
+    // Whether an event like this may be roasted now, at most once per cooldown
+    pub fn allow(&mut self, event: &Event, now: Instant) -> bool {
+        let cooldown = Duration::from_secs(self.config.cooldown_secs);
+        let key = event.cooldown_key();
+        if self.last.get(&key).is_some_and(|last| now.duration_since(*last) < cooldown) {
+            return false;
+        }
+        self.last.insert(key, now);
+        true
+    }
+}
+
+// The [[apps]] entry for the program an "apps.hog" event is about, if it has one
+fn known_app<'r>(rules: &'r [AppRule], event: &Event) -> Option<&'r AppRule> {
+    if event.kind != "apps.hog" {
+        return None;
+    }
+    let (_, program) = event.placeholders.iter().find(|(name, _)| *name == "app")?;
+    rules.iter().find(|rule| rule.names.iter().any(|name| name.eq_ignore_ascii_case(program)))
+}
+
+fn emit(out: &mut impl Write, event: &Event, roast: &Picked, json: bool) -> io::Result<()> {
+    if json {
+        let mut object = serde_json::Map::new();
+        object.insert("time".to_string(), uptime::now_secs().into());
+        object.insert("event".to_string(), event.kind.into());
+        object.insert("roast".to_string(), roast.text.as_str().into());
+        object.insert("severity".to_string(), serde_json::to_value(roast.severity).unwrap_or_default());
+        for (name, value) in &event.facts {
+            object.insert(name.to_string(), value.clone());
+        }
+        writeln!(out, "{}", Value::Object(object))?;
+    } else {
+        writeln!(out, "{}", theme::current().severity(roast.severity).paint(&roast.text))?;
+    }
+    out.flush()
+}
+
+// How often a sleeping watch checks for Ctrl-C
+const NAP: Duration = Duration::from_millis(100);
+
+// Samples the machine until Ctrl-C (or until nothing reads the output),
+// printing a roast (or with `json`, a JSON object per line) for every event
+// that isn't rate limited. Returns rather than exiting, so the caller can
+// save the history.
+pub fn run(roaster: &mut Roaster, config: &WatchConfig, json: bool) -> Result<(), String> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let handler = Arc::clone(&stop);
+    ctrlc::set_handler(move || handler.store(true, Ordering::SeqCst)).map_err(|err| format!("can't catch Ctrl-C: {}", err))?;
+    let mut sampler = Sampler::new();
+    let mut watcher = Watcher::new(config);
+    let rules = processes::app_rules(&roaster.packs);
+    let mut out = io::stdout();
+    let interval = Duration::from_secs(config.interval_secs.max(1));
+    while !stop.load(Ordering::SeqCst) {
+        let sample = sampler.sample();
+        let programs = sampler.programs();
+        let now = Instant::now();
+        sample.record(&mut roaster.snapshot);
+        for event in watcher.events(&sample, &programs, now) {
+            if !watcher.allow(&event, now) {
+                continue;
+            }
+            // Apps with an [[apps]] entry get their own roasts, under their own name
+            let app_roast = known_app(&rules, &event).and_then(|rule| {
+                let roast = roaster.pick_entry(&format!("apps.{}", rule.id), &rule.localized, &rule.roasts)?;
+                Some(roast.map(|text| event.fill(&text.replace("{app}", &rule.label))))
+            });
+            let Some(roast) = app_roast.or_else(|| roaster.pick(event.kind, event.pool).map(|roast| roast.map(|text| event.fill(text)))) else { continue };
+            match emit(&mut out, &event, &roast, json) {
+                Ok(()) => {}
+                // Whatever was reading the events has gone away
+                Err(err) if err.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+                Err(err) => return Err(err.to_string()),
+            }
+        }
+        // In short naps, so Ctrl-C doesn't wait out the interval
+        let wake = Instant::now() + interval;
+        while !stop.load(Ordering::SeqCst) && Instant::now() < wake {
+            std::thread::sleep(NAP.min(wake.saturating_duration_since(Instant::now())));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    fn sample() -> Sample {
+        Sample { ram_total: 16 * GB, ..Sample::default() }
+    }
+
+    fn kinds(events: &[Event]) -> Vec<&'static str> {
+        events.iter().map(|event| event.kind).collect()
+    }
+
+    #[test]
+    fn battery_and_swap_fire_once_when_they_start() {
+        let config = WatchConfig::default();
+        let mut watcher = Watcher::new(&config);
+        let now = Instant::now();
+        let low = Sample { on_battery: true, battery: Some(5), swap_used: 128 * 1024 * 1024, ..sample() };
+        assert_eq!(kinds(&watcher.events(&low, &[], now)), ["power.battery_low", "ram.swap"]);
+        // Still low and still swapping: nothing new started
+        assert!(watcher.events(&low, &[], now).is_empty());
+        // Plugged in, then unplugged again: a new rising edge
+        let charging = Sample { on_battery: false, ..low.clone() };
+        assert!(watcher.events(&charging, &[], now).is_empty());
+        assert_eq!(kinds(&watcher.events(&low, &[], now)), ["power.battery_low"]);
+    }
+
+    #[test]
+    fn temperature_spikes_over_the_window() {
+        let config = WatchConfig::default();
+        let mut watcher = Watcher::new(&config);
+        let start = Instant::now();
+        let at = |secs, celsius| (start + Duration::from_secs(secs), Sample { temperature: Some(celsius), ..sample() });
+        for (now, sample) in [at(0, 50.0), at(10, 60.0)] {
+            assert!(watcher.events(&sample, &[], now).is_empty());
+        }
+        let (now, hot) = at(20, 66.0);
+        let events = watcher.events(&hot, &[], now);
+        assert_eq!(kinds(&events), ["thermal.spike"]);
+        assert_eq!(events[0].fill("+{rise} to {temperature}"), "+16 to 66");
+        // The same climb isn't a spike again
+        let (now, hotter) = at(25, 67.0);
+        assert!(watcher.events(&hotter, &[], now).is_empty());
+        // A rise spread over more than the window isn't one either
+        let (now, slow) = at(80, 80.0);
+        assert!(watcher.events(&slow, &[], now).is_empty());
+    }
+
+    #[test]
+    fn every_new_hog_fires() {
+        let config = WatchConfig::default();
+        let mut watcher = Watcher::new(&config);
+        let now = Instant::now();
+        let programs = [("firefox".to_string(), 6 * GB), ("slack".to_string(), 5 * GB), ("vim".to_string(), GB / 100)];
+        let events = watcher.events(&sample(), &programs, now);
+        assert_eq!(kinds(&events), ["apps.hog", "apps.hog"]);
+        assert_eq!(events[1].fill("{app} at {share}"), "slack at 31%");
+        assert!(watcher.events(&sample(), &programs, now).is_empty());
+    }
+
+    #[test]
+    fn cooldown_is_per_kind_and_program() {
+        let config = WatchConfig::default();
+        let mut watcher = Watcher::new(&config);
+        let now = Instant::now();
+        let programs = [("firefox".to_string(), 6 * GB), ("slack".to_string(), 5 * GB)];
+        let hogs = watcher.events(&sample(), &programs, now);
+        // A second hog isn't held back by the first
+        assert!(hogs.iter().all(|event| watcher.allow(event, now)));
+        assert!(!watcher.allow(&hogs[0], now + Duration::from_secs(60)));
+        let swap = Sample { swap_used: 128 * 1024 * 1024, ..sample() };
+        let swapping = watcher.events(&swap, &programs, now);
+        assert!(watcher.allow(&swapping[0], now));
+        let later = now + Duration::from_secs(config.cooldown_secs);
+        assert!(watcher.allow(&hogs[0], later));
+        assert!(!watcher.allow(&hogs[0], later));
+    }
+}
@@ -0,0 +1,94 @@
+// --format svg and png cards: the same report has to give the same bytes
+// every time, so cards can be checked into regression tests.
+
+use rust_roast::card;
+use rust_roast::html;
+use rust_roast::render::{Report, Section};
+use rust_roast::roast::{Picked, Severity};
+use rust_roast::theme::{self, Color};
+
+fn report() -> Report {
+    Report {
+        sections: vec![Section {
+            title: "System Specs".to_string(),
+            specs: vec![
+                ("CPU".to_string(), "Potato Dual-Core (2 cores @ 800 MHz)".to_string()),
+                ("RAM".to_string(), "4.00 GB total, 3.90 GB used".to_string()),
+            ],
+        }],
+        score: Some(23),
+        heading: "The Roast".to_string(),
+        roasts: vec![
+            ("ram".to_string(), Picked { text: "Your RAM is <full> & so is your browser.".to_string(), severity: Severity::Savage }),
+            (
+                "general".to_string(),
+                Picked {
+                    text: "This roast is long enough that it has to wrap onto a second line of the card, and then keep going.".to_string(),
+                    severity: Severity::Gentle,
+                },
+            ),
+        ],
+        snapshot: serde_json::json!({ "ram.total_gb": 4.0 }),
+    }
+}
+
+#[test]
+fn svg_is_deterministic() {
+    let first = card::svg(&report(), theme::current());
+    assert_eq!(first, card::svg(&report(), theme::current()));
+    assert!(first.starts_with("<svg "));
+    assert!(first.contains("Your RAM is &lt;full&gt; &amp; so is your browser."));
+    assert!(first.contains(">23/100</text>"));
+}
+
+#[test]
+fn png_is_deterministic() {
+    let svg = card::svg(&report(), theme::current());
+    let first = card::png(&svg).expect("card renders");
+    assert_eq!(first, card::png(&svg).unwrap());
+    assert_eq!(&first[..8], b"\x89PNG\r\n\x1a\n");
+}
+
+#[test]
+fn roasts_take_their_own_severity_color() {
+    // The default theme colors every severity alike
+    let mut theme = theme::current().clone();
+    theme.gentle.fg = Some(Color::Rgb(0, 255, 0));
+    theme.savage.fg = Some(Color::Rgb(255, 0, 0));
+    let svg = card::svg(&report(), &theme);
+    let line = |text: &str| svg.lines().find(|line| line.contains(text)).unwrap().to_string();
+    assert!(line("Your RAM is").contains("fill=\"#ff0000\""));
+    assert!(line("This roast is long").contains("fill=\"#00ff00\""));
+}
+
+#[test]
+fn page_classes_roasts_by_severity() {
+    let page = html::page(&report(), theme::current());
+    assert!(page.contains("<li class=\"savage\">Your RAM is &lt;full&gt; &amp; so is your browser.</li>"));
+    assert!(page.contains("<li class=\"gentle\">This roast is long"));
+}
+
+// A report with accents, symbols and Cyrillic, all in the bundled font
+fn non_ascii_report() -> Report {
+    let mut report = report();
+    report.sections[0].specs.push(("Größe".to_string(), "Ñandú — 90 °C, Привет".to_string()));
+    report.roasts[0].1.text = "Dein Lüfter klingt wie ein Föhn… «très» chaud.".to_string();
+    report
+}
+
+#[test]
+fn non_ascii_png_is_deterministic() {
+    let svg = card::svg(&non_ascii_report(), theme::current());
+    assert!(card::covers(&svg));
+    let first = card::png(&svg).expect("card renders");
+    assert_eq!(first, card::png(&svg).unwrap());
+    assert_ne!(first, card::png(&card::svg(&report(), theme::current())).unwrap());
+}
+
+#[test]
+fn bundled_font_coverage() {
+    assert!(card::covers("RAM: 4.00 GB ─ ±5%"));
+    // Japanese falls back to the system's fonts
+    assert!(!card::covers("メモリが足りません"));
+    assert!(!card::covers("GeForce 显卡"));
+}
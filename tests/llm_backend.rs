@@ -0,0 +1,114 @@
+// --backend llm against a stub OpenAI-compatible server on localhost, so these
+// run offline.
+
+use rust_roast::config::LlmConfig;
+use rust_roast::history::History;
+use rust_roast::llm;
+use rust_roast::roast::{Backend, Roaster, Severity, Tone};
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// Answers one request with `reply` as the chat content (or not at all when
+// `reply` is None) and hands back the JSON body it received
+fn stub_server(reply: Option<&'static str>) -> (String, JoinHandle<Value>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}/v1", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        assert!(request_line.starts_with("POST /v1/chat/completions "), "{}", request_line);
+        let mut length = 0;
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            if header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    length = value.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        match reply {
+            Some(content) => {
+                let reply = serde_json::json!({ "choices": [{ "message": { "role": "assistant", "content": content } }] }).to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    reply.len(),
+                    reply
+                );
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+            }
+            // Hold the connection open past the client's timeout
+            None => thread::sleep(Duration::from_secs(3)),
+        }
+        serde_json::from_slice(&body).unwrap()
+    });
+    (endpoint, handle)
+}
+
+fn roaster(endpoint: String, timeout_secs: u64) -> Roaster {
+    let tone = Tone { severity: Severity::Savage, workplace_safe: true };
+    let mut roaster = Roaster::new(Vec::new(), tone, History::disabled());
+    roaster.backend = Backend::Llm;
+    roaster.llm = LlmConfig { endpoint: Some(endpoint), timeout_secs, roasts: 2, ..LlmConfig::default() };
+    roaster.snapshot.set("ram.total_gb", 4.0);
+    roaster
+}
+
+#[test]
+fn sends_snapshot_and_severity_and_returns_lines() {
+    let (endpoint, server) = stub_server(Some("1. Four gigabytes. Cute.\n\n2. \"Your RAM is a rounding error.\"\n3. One too many."));
+    let mut roaster = roaster(endpoint, 5);
+    roaster.set_seed(7);
+
+    let roasts = roaster.llm_roasts().expect("stub server answered");
+    let texts: Vec<&str> = roasts.iter().map(|roast| roast.text.as_str()).collect();
+    assert_eq!(texts, ["Four gigabytes. Cute.", "Your RAM is a rounding error."]);
+    // The model wrote them at the severity asked for
+    assert!(roasts.iter().all(|roast| roast.severity == Severity::Savage));
+
+    let body = server.join().unwrap();
+    assert_eq!(body["seed"], 7);
+    let facts: Value = serde_json::from_str(body["messages"][1]["content"].as_str().unwrap()).unwrap();
+    assert_eq!(facts["severity"], "savage");
+    assert_eq!(facts["workplace_safe"], true);
+    assert_eq!(facts["mode"], "roast");
+    assert_eq!(facts["count"], 2);
+    assert_eq!(facts["snapshot"]["ram.total_gb"], 4.0);
+}
+
+#[test]
+fn times_out_and_falls_back() {
+    let (endpoint, server) = stub_server(None);
+    let mut roaster = roaster(endpoint, 1);
+
+    assert!(roaster.llm_roasts().is_none());
+    server.join().unwrap();
+}
+
+#[test]
+fn unreachable_server_falls_back() {
+    // Bind and drop a listener to find a port nothing is listening on
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let mut roaster = roaster(format!("http://127.0.0.1:{}/v1", port), 1);
+
+    assert!(roaster.llm_roasts().is_none());
+}
+
+#[test]
+fn parses_reply_lines() {
+    let reply: Value = serde_json::json!({ "error": { "message": "model not loaded" } });
+    assert!(llm::parse_reply(&reply, 5).is_err());
+
+    let reply: Value = serde_json::json!({ "choices": [{ "message": { "content": "- 8 GB in 2026.\n* Bold." } }] });
+    assert_eq!(llm::parse_reply(&reply, 5).unwrap(), ["8 GB in 2026.", "Bold."]);
+}
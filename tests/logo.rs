@@ -0,0 +1,79 @@
+// Logo files: ${n} color markers, and logos in the config directory taking
+// the place of the built-in ones.
+
+use rust_roast::distro::OsRelease;
+use rust_roast::logo::{self, Logo};
+use rust_roast::theme::{self, Color, ColorChoice, Style};
+use std::fs;
+use std::path::PathBuf;
+
+fn dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rust_roast-logo-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// Loads a logo file with colors on
+fn load(name: &str, text: &str) -> Result<Logo, String> {
+    theme::init("default", ColorChoice::Always);
+    let path = dir(name).join("logo.toml");
+    fs::write(&path, text).unwrap();
+    logo::load(path.to_str().unwrap()).map_err(|err| err.rsplit(": ").next().unwrap().to_string())
+}
+
+fn style(color: &str) -> Style {
+    Style { fg: Some(Color::parse(color).unwrap()), bold: true, ..Style::default() }
+}
+
+#[test]
+fn color_markers_switch_colors() {
+    let logo = load("markers", "colors = [\"red\", \"blue\"]\nart = \"\"\"\na${1}b${2}c\n${1}dd\n\"\"\"\n").unwrap();
+    let (red, blue) = (style("red"), style("blue"));
+    assert_eq!(logo.lines[0].0, format!("{}{}{}", red.paint("a"), red.paint("b"), blue.paint("c")));
+    // The color carries over to the next line until a marker changes it
+    assert_eq!(logo.lines[1].0, red.paint("dd"));
+    // Widths leave out the markers and the escapes they turn into
+    assert_eq!(logo.lines.iter().map(|(_, width)| *width).collect::<Vec<_>>(), [3, 2]);
+    assert_eq!(logo.width, 3);
+    assert_eq!(logo::strip_ansi(&logo.lines[0].0), "abc");
+}
+
+#[test]
+fn raw_escapes_pass_through() {
+    let logo = load("escapes", "art = \"\"\"\n\\u001b[31mab\\u001b[0m\n\"\"\"\n").unwrap();
+    assert_eq!(logo.lines[0], ("\x1b[31mab\x1b[0m".to_string(), 2));
+}
+
+#[test]
+fn bad_markers_are_errors() {
+    assert_eq!(load("missing", "colors = [\"red\"]\nart = \"a${2}b\"\n").unwrap_err(), "no color 2 (the logo has 1)");
+    assert_eq!(load("zero", "colors = [\"red\"]\nart = \"a${0}b\"\n").unwrap_err(), "no color 0 (the logo has 1)");
+    assert_eq!(load("word", "colors = [\"red\"]\nart = \"a${x}b\"\n").unwrap_err(), "bad color marker \"${x}\"");
+    assert!(load("color", "colors = [\"not-a-color\"]\nart = \"a\"\n").is_err());
+    // An unclosed marker is just text
+    assert_eq!(logo::strip_ansi(&load("unclosed", "art = \"a${1\"\n").unwrap().lines[0].0), "a${1");
+}
+
+#[test]
+fn user_logos_override_builtin_ones() {
+    theme::init("default", ColorChoice::Always);
+    let config = dir("config");
+    let logos = config.join("rust_roast").join("logos");
+    fs::create_dir_all(&logos).unwrap();
+    fs::write(logos.join("arch.toml"), "art = \"MY ARCH\"\n").unwrap();
+    fs::write(logos.join("mydistro.toml"), "art = \"MINE\"\n").unwrap();
+    // Only this test reads the config directory
+    std::env::set_var("XDG_CONFIG_HOME", &config);
+
+    let arch = logo::load("arch").unwrap();
+    assert_eq!(arch.lines, [("MY ARCH".to_string(), 7)]);
+    // Logos without a user file stay built in
+    assert!(logo::load("debian").unwrap().lines.len() > 1);
+    // A user logo for a distro without a built-in one is detected
+    let os = OsRelease { id: "mydistro".to_string(), id_like: vec!["arch".to_string()], ..OsRelease::default() };
+    assert_eq!(logo::detect(Some(&os), "GenuineIntel", ""), "mydistro");
+    let other = OsRelease { id: "unknown".to_string(), id_like: vec!["arch".to_string()], ..OsRelease::default() };
+    assert_eq!(logo::detect(Some(&other), "GenuineIntel", ""), "arch");
+    assert_eq!(logo::detect(None, "GenuineIntel", ""), "intel");
+}
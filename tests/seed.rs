@@ -0,0 +1,58 @@
+// --seed has to give the same roasts on every run, whatever the earlier runs
+// showed, so each test runs a binary twice against the same state directory.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// An empty home, config and state directory for one test
+fn home(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rust_roast-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn run(binary: &str, args: &[&str], home: &Path, cwd: &Path) -> String {
+    let output = Command::new(binary)
+        .args(args)
+        .current_dir(cwd)
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .env("XDG_STATE_HOME", home.join("state"))
+        .env("LANG", "C")
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn cargo_roast_seed_is_repeatable() {
+    let home = home("cargo-roast");
+    let project = home.join("project");
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::write(project.join("Cargo.toml"), "[package]\nname = \"fixture\"\nversion = \"0.1.0\"\nedition = \"2015\"\n").unwrap();
+    fs::write(project.join("src/lib.rs"), "pub fn answer() -> u32 {\n    \"42\".parse().unwrap()\n}\n").unwrap();
+
+    let args = ["roast", "--seed", "1", "--color", "never"];
+    let first = run(env!("CARGO_BIN_EXE_cargo-roast"), &args, &home, &project);
+    assert_eq!(first, run(env!("CARGO_BIN_EXE_cargo-roast"), &args, &home, &project));
+    assert!(!home.join("state/rust_roast/history.json").exists());
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[test]
+fn rust_roast_seed_is_repeatable() {
+    let home = home("rust_roast");
+    // The specs (RAM in use, uptime) change between runs; the roasts mustn't
+    let roasts = |output: String| output.lines().filter(|line| line.starts_with("- ")).map(str::to_string).collect::<Vec<_>>();
+    let args = ["--seed", "1", "--format", "markdown"];
+    let first = roasts(run(env!("CARGO_BIN_EXE_rust_roast"), &args, &home, &home));
+    assert!(!first.is_empty());
+    assert_eq!(first, roasts(run(env!("CARGO_BIN_EXE_rust_roast"), &args, &home, &home)));
+    assert!(!home.join("state/rust_roast/history.json").exists());
+    let _ = fs::remove_dir_all(&home);
+}